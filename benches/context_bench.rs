@@ -215,6 +215,27 @@ fn bench_scenario(c: &mut Criterion, spec: DatasetSpec, line_numbers: bool) {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = NoPrompt;
@@ -263,6 +284,27 @@ fn bench_scenario(c: &mut Criterion, spec: DatasetSpec, line_numbers: bool) {
                     structure: false,
                     truncate: "smart".to_string(),
                     visibility: "all".to_string(),
+                    format: args.format.clone(),
+                    split: args.split,
+                    since: args.since.clone(),
+                    staged: args.staged,
+                    unstaged: args.unstaged,
+                    diff_against: args.diff_against.clone(),
+                    diff_from: args.diff_from.clone(),
+                    snapshot_save: args.snapshot_save.clone(),
+                    list_snapshots: false,
+                    prune_snapshots: false,
+                    no_redact: args.no_redact,
+                    watch: args.watch,
+                    token_count_format: args.token_count_format.clone(),
+                    tokenizer: args.tokenizer.clone(),
+                    focus: args.focus.clone(),
+                    entry: args.entry.clone(),
+                    entry_depth: args.entry_depth,
+                    symbol: args.symbol.clone(),
+                    signature_docs: args.signature_docs.clone(),
+                    strip_tests: args.strip_tests,
+                    compact: args.compact.clone(),
                 },
                 Config::default(),
                 &prompter,
@@ -0,0 +1,334 @@
+//! Symbol-level change summaries for the auto-diff ("API Changes").
+//!
+//! Both versions of a changed file are reduced to their definitions with the
+//! tree-sitter signature extractors. Definitions are paired by qualified name
+//! and kind, then reported as added, removed, signature changed, or body
+//! changed. A definition's body only counts its own text: editing a method
+//! changes the method, not the class or impl block around it. Whitespace is
+//! ignored throughout, so reformatting alone reports nothing.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// A definition that differs between two versions of a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiChange {
+    /// Qualified name, e.g. `CacheManager::new`
+    pub name: String,
+    /// Signature kind, e.g. `function`, `method`, `struct`
+    pub kind: String,
+    pub change: ApiChangeKind,
+}
+
+/// How a definition changed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiChangeKind {
+    Added { signature: String },
+    Removed { signature: String },
+    SignatureChanged { old: String, new: String },
+    BodyChanged,
+}
+
+/// Compares the definitions of two versions of a file. Empty when the
+/// language has no tree-sitter support (or the feature is disabled).
+pub fn compare_definitions(old_source: &str, new_source: &str, extension: &str) -> Vec<ApiChange> {
+    #[cfg(feature = "tree-sitter-base")]
+    {
+        let spans = |source| crate::tree_sitter::definition_spans_for_file(source, extension);
+        if let (Some(old_spans), Some(new_spans)) = (spans(old_source), spans(new_source)) {
+            return pair_definitions(
+                definitions(old_source, &old_spans),
+                definitions(new_source, &new_spans),
+            );
+        }
+    }
+    #[cfg(not(feature = "tree-sitter-base"))]
+    let _ = (old_source, new_source, extension);
+
+    Vec::new()
+}
+
+/// A definition reduced to what is compared.
+#[cfg(feature = "tree-sitter-base")]
+#[derive(Debug)]
+struct Definition {
+    name: String,
+    kind: String,
+    /// Signature with whitespace collapsed
+    signature: String,
+    /// Own text (nested definitions cut out) with whitespace collapsed
+    body: String,
+}
+
+#[cfg(feature = "tree-sitter-base")]
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(feature = "tree-sitter-base")]
+fn definitions(source: &str, spans: &[crate::tree_sitter::DefinitionSpan]) -> Vec<Definition> {
+    let mut spans: Vec<_> = spans.iter().collect();
+    spans.sort_by_key(|span| span.range.start);
+    spans
+        .iter()
+        .map(|span| {
+            let mut body = String::new();
+            let mut cursor = span.range.start;
+            for nested in spans.iter().filter(|other| {
+                other.range != span.range
+                    && other.range.start >= span.range.start
+                    && other.range.end <= span.range.end
+            }) {
+                if nested.range.start >= cursor {
+                    body.push_str(&source[cursor..nested.range.start]);
+                    cursor = nested.range.end;
+                }
+            }
+            body.push_str(&source[cursor..span.range.end]);
+
+            Definition {
+                name: span.name.clone(),
+                kind: span.kind.to_string(),
+                signature: collapse_whitespace(&span.signature),
+                body: collapse_whitespace(&body),
+            }
+        })
+        .collect()
+}
+
+/// Pairs old and new definitions, first by name, kind and signature, then by
+/// name and kind alone (a changed signature). Unpaired ones were removed or
+/// added. Changes come out in source order: new file first, removals last.
+#[cfg(feature = "tree-sitter-base")]
+fn pair_definitions(old: Vec<Definition>, new: Vec<Definition>) -> Vec<ApiChange> {
+    let mut old: Vec<Option<Definition>> = old.into_iter().map(Some).collect();
+    let mut pairs: Vec<(Option<Definition>, Definition)> = Vec::with_capacity(new.len());
+    let mut unmatched = Vec::new();
+
+    for definition in new {
+        let exact = old.iter().position(|candidate| {
+            candidate.as_ref().is_some_and(|candidate| {
+                candidate.name == definition.name
+                    && candidate.kind == definition.kind
+                    && candidate.signature == definition.signature
+            })
+        });
+        match exact {
+            Some(index) => pairs.push((old[index].take(), definition)),
+            None => {
+                unmatched.push(pairs.len());
+                pairs.push((None, definition));
+            }
+        }
+    }
+    for index in unmatched {
+        let (name, kind) = (&pairs[index].1.name, &pairs[index].1.kind);
+        if let Some(position) = old.iter().position(|candidate| {
+            candidate
+                .as_ref()
+                .is_some_and(|candidate| &candidate.name == name && &candidate.kind == kind)
+        }) {
+            pairs[index].0 = old[position].take();
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (previous, definition) in pairs {
+        let change = match previous {
+            None => ApiChangeKind::Added {
+                signature: definition.signature,
+            },
+            Some(previous) if previous.signature != definition.signature => {
+                ApiChangeKind::SignatureChanged {
+                    old: previous.signature,
+                    new: definition.signature,
+                }
+            }
+            Some(previous) if previous.body != definition.body => ApiChangeKind::BodyChanged,
+            Some(_) => continue,
+        };
+        changes.push(ApiChange {
+            name: definition.name,
+            kind: definition.kind,
+            change,
+        });
+    }
+    changes.extend(old.into_iter().flatten().map(|definition| ApiChange {
+        name: definition.name,
+        kind: definition.kind,
+        change: ApiChangeKind::Removed {
+            signature: definition.signature,
+        },
+    }));
+    changes
+}
+
+/// Renders an "## API Changes" section, one subsection per file. Empty when
+/// no file has API changes.
+pub fn render_api_changes(files: &BTreeMap<PathBuf, Vec<ApiChange>>) -> String {
+    if files.values().all(Vec::is_empty) {
+        return String::new();
+    }
+
+    let mut output = String::new();
+    output.push_str("## API Changes\n\n");
+    for (path, changes) in files {
+        if changes.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("### `{}`\n\n", path.display()));
+        for change in changes {
+            let line = match &change.change {
+                ApiChangeKind::Added { signature } => {
+                    format!("Added {} `{}`: `{}`", change.kind, change.name, signature)
+                }
+                ApiChangeKind::Removed { signature } => {
+                    format!("Removed {} `{}`: `{}`", change.kind, change.name, signature)
+                }
+                ApiChangeKind::SignatureChanged { old, new } => format!(
+                    "Signature changed for {} `{}`: `{}` → `{}`",
+                    change.kind, change.name, old, new
+                ),
+                ApiChangeKind::BodyChanged => {
+                    format!("Body changed for {} `{}`", change.kind, change.name)
+                }
+            };
+            output.push_str(&format!("- {}\n", line));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_api_changes() {
+        let files = BTreeMap::from([(
+            PathBuf::from("src/lib.rs"),
+            vec![
+                ApiChange {
+                    name: "parse".to_string(),
+                    kind: "function".to_string(),
+                    change: ApiChangeKind::SignatureChanged {
+                        old: "pub fn parse(input: &str)".to_string(),
+                        new: "pub fn parse(input: &str, strict: bool)".to_string(),
+                    },
+                },
+                ApiChange {
+                    name: "Cache::clear".to_string(),
+                    kind: "method".to_string(),
+                    change: ApiChangeKind::BodyChanged,
+                },
+            ],
+        )]);
+
+        let output = render_api_changes(&files);
+        assert!(output.starts_with("## API Changes\n\n### `src/lib.rs`\n\n"));
+        assert!(output.contains(
+            "- Signature changed for function `parse`: `pub fn parse(input: &str)` → `pub fn parse(input: &str, strict: bool)`\n"
+        ));
+        assert!(output.contains("- Body changed for method `Cache::clear`\n"));
+
+        assert_eq!(render_api_changes(&BTreeMap::new()), "");
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-rust")]
+    fn test_compare_rust_definitions() {
+        let old = r#"pub struct Cache {
+    entries: Vec<String>,
+}
+
+impl Cache {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+fn helper(x: i32) -> i32 {
+    x + 1
+}
+"#;
+        let new = r#"pub struct Cache {
+    entries: Vec<String>,
+}
+
+impl Cache {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new()
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.truncate(0);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn helper(x: i64) -> i64 {
+    x + 1
+}
+"#;
+        let changes = compare_definitions(old, new, "rs");
+        let summary: Vec<(&str, &ApiChangeKind)> = changes
+            .iter()
+            .map(|change| (change.name.as_str(), &change.change))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Cache::clear", &ApiChangeKind::BodyChanged),
+                (
+                    "Cache::is_empty",
+                    &ApiChangeKind::Added {
+                        signature: "pub fn is_empty(&self) -> bool".to_string()
+                    }
+                ),
+                (
+                    "helper",
+                    &ApiChangeKind::SignatureChanged {
+                        old: "fn helper(x: i32) -> i32".to_string(),
+                        new: "fn helper(x: i64) -> i64".to_string(),
+                    }
+                ),
+                (
+                    "Cache::len",
+                    &ApiChangeKind::Removed {
+                        signature: "pub fn len(&self) -> usize".to_string()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-python")]
+    fn test_method_edit_does_not_change_class() {
+        let old = "class Parser:\n    def parse(self, text):\n        return text.split()\n";
+        let new = "class Parser:\n    def parse(self, text):\n        return text.split(',')\n";
+        let changes = compare_definitions(old, new, "py");
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].name, "Parser::parse");
+        assert_eq!(changes[0].change, ApiChangeKind::BodyChanged);
+    }
+
+    #[test]
+    fn test_unsupported_extension_reports_nothing() {
+        assert!(compare_definitions("a = 1\n", "a = 2\n", "ini").is_empty());
+    }
+}
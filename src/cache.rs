@@ -2,24 +2,38 @@
 //!
 //! This module handles caching of project states to enable the auto-diff feature.
 //! It uses a hash of the project path and configuration to avoid cache collisions
-//! between different projects or configurations.
-
+//! between different projects or configurations. Named snapshots, which later
+//! runs can diff against, are stored next to the cache.
+//!
+//! State files only record each file's path, size, modification time and
+//! content hash. The contents themselves live in a content-addressed blob
+//! store shared by the cache and the snapshots, so an unchanged file is
+//! stored once and never rewritten.
+
+use flate2::Compression;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
 use fs2::FileExt;
+use serde::{Deserialize, Serialize};
 
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::fs::File;
 
-use std::io::{Read, Write};
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 
-use crate::config::Config;
-use crate::state::ProjectState;
+use crate::config::{Config, SnapshotConfig};
+use crate::state::{FileState, ProjectMetadata, ProjectState};
 
 /// Manages cache operations with file locking to prevent corruption
 pub struct CacheManager {
     cache_dir: PathBuf,
     project_hash: String,
     config_hash: String,
+    blobs: BlobStore,
 }
 
 impl CacheManager {
@@ -39,10 +53,15 @@ impl CacheManager {
             let _ = fs::create_dir_all(&cache_dir);
         }
 
+        let blobs = BlobStore::new(
+            &normalized_project_path,
+            CacheCompression::from_config(config),
+        );
         let cache_manager = Self {
             cache_dir,
             project_hash,
             config_hash,
+            blobs,
         };
 
         // Migrate old cache format if present
@@ -158,49 +177,481 @@ impl CacheManager {
 
     /// Read the cached project state with file locking
     pub fn read_cache(&self) -> Result<Option<ProjectState>, Box<dyn std::error::Error>> {
-        let cache_path = self.get_cache_path();
+        read_state_file(&self.get_cache_path(), &self.blobs)
+    }
+
+    /// Write the project state to cache with file locking, then delete the
+    /// blobs no cache or snapshot references any more
+    pub fn write_cache(&self, state: &ProjectState) -> Result<(), Box<dyn std::error::Error>> {
+        write_state_file(&self.get_cache_path(), state, &self.blobs)?;
+        self.blobs.collect_garbage(BLOB_GRACE_PERIOD)?;
+        Ok(())
+    }
+}
 
-        if !cache_path.exists() {
-            return Ok(None);
+/// How file contents are compressed in the blob store (`cache_compression`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheCompression {
+    /// zlib-wrapped deflate, stored as `<hash>.z`
+    #[default]
+    Deflate,
+    /// Raw file contents, stored as `<hash>`
+    None,
+}
+
+impl FromStr for CacheCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "deflate" => Ok(CacheCompression::Deflate),
+            "none" => Ok(CacheCompression::None),
+            _ => Err(format!(
+                "Unknown cache compression '{}' (expected \"deflate\" or \"none\")",
+                s
+            )),
         }
+    }
+}
+
+impl CacheCompression {
+    /// The compression configured in `config`; the default when unset or
+    /// invalid (`run_with_args` rejects invalid values up front)
+    fn from_config(config: &Config) -> Self {
+        config
+            .cache_compression
+            .as_deref()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+}
 
-        let file = File::open(&cache_path)?;
-        // Acquire shared lock to prevent reading while writing
-        file.lock_shared()?;
+/// Version of the state file format written by [`write_state_file`]
+const STATE_FORMAT_VERSION: u32 = 2;
 
-        let mut contents = String::new();
-        let mut file = std::io::BufReader::new(file);
-        file.read_to_string(&mut contents)?;
+/// How long an unreferenced blob survives garbage collection. A concurrent run
+/// may have written (or touched) it without having written its state file yet.
+const BLOB_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
 
-        // Release lock
-        file.get_ref().unlock()?;
+/// A state file: `ProjectState` with every file content replaced by the key of
+/// its blob.
+#[derive(Serialize, Deserialize)]
+struct StoredState {
+    version: u32,
+    timestamp: String,
+    config_hash: String,
+    files: BTreeMap<PathBuf, StoredFile>,
+    metadata: ProjectMetadata,
+}
 
-        let state: ProjectState = serde_json::from_str(&contents)?;
-        Ok(Some(state))
+#[derive(Serialize, Deserialize)]
+struct StoredFile {
+    /// xxh3 of the content, also the blob key
+    hash: String,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// Content-addressed store of file contents shared by the cache and the
+/// snapshots: `.context-builder/blobs/<first two hex digits>/<xxh3 hex>[.z]`.
+///
+/// A content is stored once however many state files reference it, and
+/// writing a state only writes the blobs that do not exist yet.
+struct BlobStore {
+    /// The `.context-builder` directory
+    root: PathBuf,
+    compression: CacheCompression,
+}
+
+impl BlobStore {
+    fn new(project_root: &Path, compression: CacheCompression) -> Self {
+        Self {
+            root: project_root.join(".context-builder"),
+            compression,
+        }
     }
 
-    /// Write the project state to cache with file locking
-    pub fn write_cache(&self, state: &ProjectState) -> Result<(), Box<dyn std::error::Error>> {
-        let cache_path = self.get_cache_path();
+    fn blob_dir(&self) -> PathBuf {
+        self.root.join("blobs")
+    }
 
-        let file = std::fs::OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(false)
-            .open(&cache_path)?;
-        // Acquire exclusive lock BEFORE truncating to prevent TOCTOU races
-        file.lock_exclusive()?;
-        file.set_len(0)?;
+    fn key(content: &str) -> String {
+        format!("{:016x}", xxhash_rust::xxh3::xxh3_64(content.as_bytes()))
+    }
 
-        let json = serde_json::to_string_pretty(state)?;
-        let mut file = std::io::BufWriter::new(file);
-        file.write_all(json.as_bytes())?;
-        file.flush()?;
+    /// Paths the blob `key` may be stored at: compressed, then raw
+    fn blob_paths(&self, key: &str) -> [PathBuf; 2] {
+        let dir = self.blob_dir().join(&key[..2.min(key.len())]);
+        [dir.join(format!("{}.z", key)), dir.join(key)]
+    }
 
-        // Release lock
-        file.get_ref().unlock()?;
+    /// Stores `content`, returning its key. An existing blob is only touched,
+    /// which keeps it out of a concurrent garbage collection.
+    fn put(&self, content: &str) -> io::Result<String> {
+        let key = Self::key(content);
+        let [compressed, raw] = self.blob_paths(&key);
+        for existing in [&compressed, &raw] {
+            if let Ok(file) = File::options().write(true).open(existing) {
+                let _ = file.set_modified(SystemTime::now());
+                return Ok(key);
+            }
+        }
 
-        Ok(())
+        let path = match self.compression {
+            CacheCompression::Deflate => compressed,
+            CacheCompression::None => raw,
+        };
+        let dir = path.parent().expect("blob paths have a parent");
+        fs::create_dir_all(dir)?;
+
+        // Write to a temporary file first so readers never see a partial blob
+        let mut temp = tempfile::NamedTempFile::new_in(dir)?;
+        match self.compression {
+            CacheCompression::Deflate => {
+                let mut encoder = ZlibEncoder::new(temp.as_file_mut(), Compression::default());
+                encoder.write_all(content.as_bytes())?;
+                encoder.finish()?;
+            }
+            CacheCompression::None => temp.write_all(content.as_bytes())?,
+        }
+        temp.persist(&path).map_err(|e| e.error)?;
+        Ok(key)
+    }
+
+    /// Reads the blob `key`, checking that its content still hashes to it
+    fn get(&self, key: &str) -> io::Result<String> {
+        let [compressed, raw] = self.blob_paths(key);
+        let content = match File::open(&compressed) {
+            Ok(file) => {
+                let mut content = String::new();
+                ZlibDecoder::new(file).read_to_string(&mut content)?;
+                content
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => fs::read_to_string(&raw)
+                .map_err(|e| io::Error::new(e.kind(), format!("Missing cache blob {}", key)))?,
+            Err(e) => return Err(e),
+        };
+        if Self::key(&content) != key {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Corrupted cache blob {}", key),
+            ));
+        }
+        Ok(content)
+    }
+
+    /// Deletes the blobs no cache or snapshot state file references, sparing
+    /// those modified within `grace`. Returns the number of deleted blobs.
+    fn collect_garbage(&self, grace: Duration) -> io::Result<usize> {
+        let mut referenced = HashSet::new();
+        for dir in ["cache", "snapshots"] {
+            let entries = match fs::read_dir(self.root.join(dir)) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    continue;
+                }
+                // Old-format and unreadable state files reference no blobs
+                if let Ok(contents) = read_locked(&path)
+                    && let Ok(stored) = serde_json::from_str::<StoredState>(&contents)
+                {
+                    referenced.extend(stored.files.into_values().map(|file| file.hash));
+                }
+            }
+        }
+
+        let shards = match fs::read_dir(self.blob_dir()) {
+            Ok(shards) => shards,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+        let now = SystemTime::now();
+        let mut deleted = 0;
+        for shard in shards.flatten() {
+            for blob in fs::read_dir(shard.path())?.flatten() {
+                let name = blob.file_name();
+                let name = name.to_string_lossy();
+                if referenced.contains(name.strip_suffix(".z").unwrap_or(&name)) {
+                    continue;
+                }
+                let recent = blob
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .is_ok_and(|modified| {
+                        // Modified "in the future" (clock skew) counts as recent
+                        !now.duration_since(modified).is_ok_and(|age| age >= grace)
+                    });
+                if !recent && fs::remove_file(blob.path()).is_ok() {
+                    deleted += 1;
+                }
+            }
+            // Only succeeds once the shard is empty
+            let _ = fs::remove_dir(shard.path());
+        }
+        Ok(deleted)
+    }
+}
+
+/// Deletes every blob of the project at `project_path` that no cache or
+/// snapshot state file references, however recent (`--clear-cache`). Returns
+/// the number of deleted blobs.
+pub fn remove_unreferenced_blobs(project_path: &Path) -> io::Result<usize> {
+    let project_root = CacheManager::normalize_project_path(project_path);
+    BlobStore::new(&project_root, CacheCompression::default()).collect_garbage(Duration::ZERO)
+}
+
+/// Reads a whole file under a shared lock.
+fn read_locked(path: &Path) -> io::Result<String> {
+    let file = File::open(path)?;
+    // Acquire shared lock to prevent reading while writing
+    file.lock_shared()?;
+
+    let mut contents = String::new();
+    let mut file = std::io::BufReader::new(file);
+    file.read_to_string(&mut contents)?;
+
+    // Release lock
+    file.get_ref().unlock()?;
+
+    Ok(contents)
+}
+
+/// Reads a state file under a shared lock, loading the file contents from
+/// `blobs`. `None` if it does not exist. State files from before the blob
+/// store, which embed the contents, are still read.
+fn read_state_file(
+    path: &Path,
+    blobs: &BlobStore,
+) -> Result<Option<ProjectState>, Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = read_locked(path)?;
+    let stored: StoredState = match serde_json::from_str(&contents) {
+        Ok(stored) => stored,
+        Err(e) => {
+            return match serde_json::from_str::<ProjectState>(&contents) {
+                Ok(legacy) => Ok(Some(legacy)),
+                Err(_) => Err(e.into()),
+            };
+        }
+    };
+    if stored.version > STATE_FORMAT_VERSION {
+        return Err(format!(
+            "{} was written by a newer version (state format {})",
+            path.display(),
+            stored.version
+        )
+        .into());
+    }
+
+    let mut files = BTreeMap::new();
+    for (file_path, file) in stored.files {
+        let content = blobs.get(&file.hash)?;
+        files.insert(
+            file_path,
+            FileState {
+                content,
+                size: file.size,
+                modified: file.modified,
+                content_hash: file.hash,
+            },
+        );
+    }
+    Ok(Some(ProjectState {
+        timestamp: stored.timestamp,
+        config_hash: stored.config_hash,
+        files,
+        metadata: stored.metadata,
+    }))
+}
+
+/// Writes the contents of `state` to `blobs`, then the state file under an
+/// exclusive lock.
+fn write_state_file(
+    path: &Path,
+    state: &ProjectState,
+    blobs: &BlobStore,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut files = BTreeMap::new();
+    for (file_path, file) in &state.files {
+        files.insert(
+            file_path.clone(),
+            StoredFile {
+                hash: blobs.put(&file.content)?,
+                size: file.size,
+                modified: file.modified,
+            },
+        );
+    }
+    let stored = StoredState {
+        version: STATE_FORMAT_VERSION,
+        timestamp: state.timestamp.clone(),
+        config_hash: state.config_hash.clone(),
+        files,
+        metadata: state.metadata.clone(),
+    };
+
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    // Acquire exclusive lock BEFORE truncating to prevent TOCTOU races
+    file.lock_exclusive()?;
+    file.set_len(0)?;
+
+    let json = serde_json::to_string_pretty(&stored)?;
+    let mut file = std::io::BufWriter::new(file);
+    file.write_all(json.as_bytes())?;
+    file.flush()?;
+
+    // Release lock
+    file.get_ref().unlock()?;
+
+    Ok(())
+}
+
+/// Named project states kept until pruned (`--snapshot-save`, `--diff-from`).
+///
+/// Unlike the auto-diff cache, snapshots do not depend on the configuration:
+/// each one lives in `.context-builder/snapshots/<name>.json` under the project
+/// root and is only replaced by saving a snapshot with the same name.
+pub struct SnapshotStore {
+    snapshot_dir: PathBuf,
+    blobs: BlobStore,
+}
+
+/// A saved snapshot, as listed by `--list-snapshots`.
+#[derive(Debug, Clone)]
+pub struct SnapshotInfo {
+    pub name: String,
+    /// When the state was captured (`ProjectState::timestamp`)
+    pub timestamp: String,
+    pub file_count: usize,
+    /// When the snapshot file was written; drives retention
+    pub saved: SystemTime,
+}
+
+/// The parts of a snapshot file needed to list it.
+#[derive(Deserialize)]
+struct SnapshotHeader {
+    timestamp: String,
+    metadata: ProjectMetadata,
+}
+
+impl SnapshotStore {
+    /// Snapshot store of the project at `project_path`; `config` only
+    /// chooses the blob compression
+    pub fn new(project_path: &Path, config: &Config) -> Self {
+        let project_root = CacheManager::normalize_project_path(project_path);
+        Self {
+            snapshot_dir: project_root.join(".context-builder").join("snapshots"),
+            blobs: BlobStore::new(&project_root, CacheCompression::from_config(config)),
+        }
+    }
+
+    /// Checks that `name` can be used as a snapshot name: letters, digits,
+    /// `.`, `_` and `-`, not starting with a dot.
+    pub fn validate_name(name: &str) -> io::Result<()> {
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+        if valid {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Invalid snapshot name '{}' (use letters, digits, '.', '_' and '-')",
+                    name
+                ),
+            ))
+        }
+    }
+
+    fn snapshot_path(&self, name: &str) -> PathBuf {
+        self.snapshot_dir.join(format!("{}.json", name))
+    }
+
+    /// Save `state` under `name`, replacing any snapshot with that name
+    pub fn save(&self, name: &str, state: &ProjectState) -> Result<(), Box<dyn std::error::Error>> {
+        Self::validate_name(name)?;
+        fs::create_dir_all(&self.snapshot_dir)?;
+        write_state_file(&self.snapshot_path(name), state, &self.blobs)
+    }
+
+    /// Load the snapshot called `name`, if there is one
+    pub fn load(&self, name: &str) -> Result<Option<ProjectState>, Box<dyn std::error::Error>> {
+        Self::validate_name(name)?;
+        read_state_file(&self.snapshot_path(name), &self.blobs)
+    }
+
+    /// All saved snapshots, most recently saved first
+    pub fn list(&self) -> Result<Vec<SnapshotInfo>, Box<dyn std::error::Error>> {
+        let entries = match fs::read_dir(&self.snapshot_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut snapshots = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(name) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".json"))
+            else {
+                continue;
+            };
+            let contents = fs::read_to_string(&path)?;
+            let header: SnapshotHeader = serde_json::from_str(&contents)?;
+            snapshots.push(SnapshotInfo {
+                name: name.to_string(),
+                timestamp: header.timestamp,
+                file_count: header.metadata.file_count,
+                saved: entry.metadata()?.modified()?,
+            });
+        }
+        snapshots.sort_by(|a, b| b.saved.cmp(&a.saved).then_with(|| a.name.cmp(&b.name)));
+        Ok(snapshots)
+    }
+
+    /// Delete the snapshots outside `policy`: beyond the newest `keep`, or
+    /// saved more than `max_age_days` before `now`. Returns the deleted names.
+    pub fn prune(
+        &self,
+        policy: &SnapshotConfig,
+        now: SystemTime,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let max_age = policy
+            .max_age_days
+            .map(|days| Duration::from_secs(days * 24 * 60 * 60));
+
+        let mut deleted = Vec::new();
+        for (index, snapshot) in self.list()?.into_iter().enumerate() {
+            let beyond_keep = policy.keep.is_some_and(|keep| index >= keep);
+            let too_old = max_age.is_some_and(|max_age| {
+                now.duration_since(snapshot.saved)
+                    .is_ok_and(|age| age > max_age)
+            });
+            if beyond_keep || too_old {
+                fs::remove_file(self.snapshot_path(&snapshot.name))?;
+                deleted.push(snapshot.name);
+            }
+        }
+        if !deleted.is_empty() {
+            self.blobs.collect_garbage(BLOB_GRACE_PERIOD)?;
+        }
+        Ok(deleted)
     }
 }
 
@@ -225,6 +676,223 @@ mod tests {
         );
     }
 
+    fn snapshot_state(timestamp: &str, file_count: usize) -> ProjectState {
+        ProjectState {
+            timestamp: timestamp.to_string(),
+            config_hash: "hash".to_string(),
+            files: std::collections::BTreeMap::new(),
+            metadata: ProjectMetadata {
+                project_name: "test_project".to_string(),
+                file_count,
+                filters: vec![],
+                ignores: vec![],
+                line_numbers: false,
+            },
+        }
+    }
+
+    #[test]
+    fn test_snapshot_name_validation() {
+        assert!(SnapshotStore::validate_name("sprint-12").is_ok());
+        assert!(SnapshotStore::validate_name("v1.2_rc").is_ok());
+        for name in ["", ".hidden", "a/b", "../up", "with space"] {
+            let err = SnapshotStore::validate_name(name).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_save_list_and_prune() {
+        let dir = tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path(), &Config::default());
+        assert!(store.list().unwrap().is_empty());
+        assert!(store.load("missing").unwrap().is_none());
+
+        let now = SystemTime::now();
+        for (index, name) in ["oldest", "middle", "newest"].iter().enumerate() {
+            store
+                .save(name, &snapshot_state(&format!("t{}", index), index))
+                .unwrap();
+            // Saved 40, 20 and 0 days ago
+            let saved = now - Duration::from_secs((2 - index as u64) * 20 * 24 * 60 * 60);
+            File::options()
+                .write(true)
+                .open(store.snapshot_path(name))
+                .unwrap()
+                .set_modified(saved)
+                .unwrap();
+        }
+
+        let names: Vec<String> = store.list().unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["newest", "middle", "oldest"]);
+        assert_eq!(store.load("middle").unwrap().unwrap().timestamp, "t1");
+
+        let by_age = SnapshotConfig {
+            keep: None,
+            max_age_days: Some(30),
+        };
+        assert_eq!(store.prune(&by_age, now).unwrap(), vec!["oldest"]);
+
+        let by_count = SnapshotConfig {
+            keep: Some(1),
+            max_age_days: None,
+        };
+        assert_eq!(store.prune(&by_count, now).unwrap(), vec!["middle"]);
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    fn state_with_files(files: &[(&str, &str)]) -> ProjectState {
+        let mut state = snapshot_state("t", files.len());
+        for (path, content) in files {
+            state.files.insert(
+                PathBuf::from(path),
+                FileState::from_bytes(content.as_bytes().to_vec()),
+            );
+        }
+        state
+    }
+
+    fn blob_files(project_path: &Path) -> Vec<PathBuf> {
+        walkdir::WalkDir::new(project_path.join(".context-builder").join("blobs"))
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .collect()
+    }
+
+    #[test]
+    fn test_cache_compression_parsing() {
+        assert_eq!(
+            "deflate".parse::<CacheCompression>().unwrap(),
+            CacheCompression::Deflate
+        );
+        assert_eq!(
+            "None".parse::<CacheCompression>().unwrap(),
+            CacheCompression::None
+        );
+        assert!("zstd".parse::<CacheCompression>().is_err());
+    }
+
+    #[test]
+    fn test_state_file_holds_no_contents() {
+        let dir = tempdir().unwrap();
+        let cache_manager = CacheManager::new(dir.path(), &Config::default());
+        let state = state_with_files(&[("a.rs", "fn a() {}\n"), ("b.rs", "fn b() {}\n")]);
+        cache_manager.write_cache(&state).unwrap();
+
+        let index = fs::read_to_string(cache_manager.get_cache_path()).unwrap();
+        assert!(!index.contains("fn a()"));
+        assert!(index.contains(&state.files[Path::new("a.rs")].content_hash));
+
+        let blobs = blob_files(dir.path());
+        assert_eq!(blobs.len(), 2);
+        assert!(
+            blobs
+                .iter()
+                .all(|path| path.extension().is_some_and(|ext| ext == "z"))
+        );
+
+        let cached = cache_manager.read_cache().unwrap().unwrap();
+        assert_eq!(cached.files[Path::new("b.rs")].content, "fn b() {}\n");
+        assert_eq!(
+            cached.files[Path::new("b.rs")].content_hash,
+            state.files[Path::new("b.rs")].content_hash
+        );
+    }
+
+    #[test]
+    fn test_blobs_deduplicated_across_cache_and_snapshots() {
+        let dir = tempdir().unwrap();
+        let config = Config {
+            cache_compression: Some("none".to_string()),
+            ..Default::default()
+        };
+        let state = state_with_files(&[
+            ("a.rs", "shared\n"),
+            ("copy_of_a.rs", "shared\n"),
+            ("b.rs", "only b\n"),
+        ]);
+        CacheManager::new(dir.path(), &config)
+            .write_cache(&state)
+            .unwrap();
+        let store = SnapshotStore::new(dir.path(), &config);
+        store.save("v1", &state).unwrap();
+
+        let blobs = blob_files(dir.path());
+        assert_eq!(blobs.len(), 2);
+        assert!(blobs.iter().all(|path| path.extension().is_none()));
+        let loaded = store.load("v1").unwrap().unwrap();
+        assert_eq!(loaded.files[Path::new("copy_of_a.rs")].content, "shared\n");
+    }
+
+    #[test]
+    fn test_reads_state_files_with_embedded_contents() {
+        let dir = tempdir().unwrap();
+        let cache_manager = CacheManager::new(dir.path(), &Config::default());
+        let state = state_with_files(&[("a.rs", "fn a() {}\n")]);
+        fs::write(
+            cache_manager.get_cache_path(),
+            serde_json::to_string(&state).unwrap(),
+        )
+        .unwrap();
+
+        let cached = cache_manager.read_cache().unwrap().unwrap();
+        assert_eq!(cached.files[Path::new("a.rs")].content, "fn a() {}\n");
+    }
+
+    #[test]
+    fn test_missing_or_corrupted_blob_is_an_error() {
+        let dir = tempdir().unwrap();
+        let config = Config {
+            cache_compression: Some("none".to_string()),
+            ..Default::default()
+        };
+        let cache_manager = CacheManager::new(dir.path(), &config);
+        cache_manager
+            .write_cache(&state_with_files(&[("a.rs", "fn a() {}\n")]))
+            .unwrap();
+
+        let blob = blob_files(dir.path()).remove(0);
+        fs::write(&blob, "tampered").unwrap();
+        assert!(cache_manager.read_cache().is_err());
+        fs::remove_file(&blob).unwrap();
+        assert!(cache_manager.read_cache().is_err());
+    }
+
+    #[test]
+    fn test_unreferenced_blobs_collected() {
+        let dir = tempdir().unwrap();
+        let cache_manager = CacheManager::new(dir.path(), &Config::default());
+        let blobs = &cache_manager.blobs;
+        let store = SnapshotStore::new(dir.path(), &Config::default());
+
+        store
+            .save("v1", &state_with_files(&[("a.rs", "old a\n")]))
+            .unwrap();
+        cache_manager
+            .write_cache(&state_with_files(&[("a.rs", "first\n")]))
+            .unwrap();
+        cache_manager
+            .write_cache(&state_with_files(&[("a.rs", "second\n")]))
+            .unwrap();
+        // Within the grace period nothing is deleted
+        assert_eq!(blob_files(dir.path()).len(), 3);
+
+        assert_eq!(blobs.collect_garbage(Duration::ZERO).unwrap(), 1);
+        assert_eq!(blobs.get(&BlobStore::key("old a\n")).unwrap(), "old a\n");
+        assert_eq!(blobs.get(&BlobStore::key("second\n")).unwrap(), "second\n");
+
+        fs::remove_file(store.snapshot_path("v1")).unwrap();
+        assert_eq!(blobs.collect_garbage(Duration::ZERO).unwrap(), 1);
+        assert_eq!(blob_files(dir.path()).len(), 1);
+
+        // What --clear-cache does
+        fs::remove_dir_all(&cache_manager.cache_dir).unwrap();
+        assert_eq!(remove_unreferenced_blobs(dir.path()).unwrap(), 1);
+        assert!(blob_files(dir.path()).is_empty());
+    }
+
     #[test]
     fn test_hash_config() {
         let config1 = Config {
@@ -2,7 +2,12 @@ use clap::Parser;
 
 /// CLI tool to aggregate directory contents into a single Markdown file optimized for LLM consumption
 #[derive(Parser, Debug, Clone)]
-#[clap(author, version, about)]
+#[clap(
+    author,
+    version,
+    about,
+    after_help = "Run `context-builder mcp --help` to serve the project as MCP tools over stdio."
+)]
 pub struct Args {
     /// Directory path to process
     #[clap(short = 'd', long, default_value = ".")]
@@ -28,6 +33,10 @@ pub struct Args {
     #[clap(long)]
     pub token_count: bool,
 
+    /// Token count report format: "text" (tables) or "json"
+    #[clap(long, value_name = "FORMAT", default_value = "text")]
+    pub token_count_format: String,
+
     /// Add line numbers to code blocks in the output
     #[clap(long)]
     pub line_numbers: bool,
@@ -40,6 +49,10 @@ pub struct Args {
     #[clap(long)]
     pub max_tokens: Option<usize>,
 
+    /// Tokenizer for token counts and budgets: "cl100k", "o200k", "p50k", or "chars[:RATIO]" (characters per token)
+    #[clap(long, value_name = "TOKENIZER", default_value = "cl100k")]
+    pub tokenizer: String,
+
     /// Output only diffs (omit full file contents; requires auto-diff & timestamped output)
     #[clap(long, default_value_t = false)]
     pub diff_only: bool,
@@ -67,11 +80,112 @@ pub struct Args {
     /// Filter signatures by visibility: "all", "public", or "private"
     #[clap(long, default_value = "all")]
     pub visibility: String,
+
+    /// How much of each doc comment to keep in signatures: "full", "first-line", or "none"
+    #[clap(long, value_name = "MODE", default_value = "first-line")]
+    pub signature_docs: String,
+
+    /// Remove test modules and test functions from source files (requires tree-sitter feature)
+    #[clap(long)]
+    pub strip_tests: bool,
+
+    /// Remove comments and/or blank lines from file content: "comments", "blank-lines", or "all"
+    #[clap(long, value_name = "LEVEL")]
+    pub compact: Option<String>,
+
+    /// Show files matching this glob in full and every other supported source file as signatures and structure (repeatable; requires tree-sitter feature)
+    #[clap(long, value_name = "GLOB")]
+    pub focus: Vec<String>,
+
+    /// Include only this source file and the project files it imports, transitively (repeatable; requires tree-sitter feature)
+    #[clap(long, value_name = "PATH")]
+    pub entry: Vec<String>,
+
+    /// Follow imports at most this many levels away from the --entry files
+    #[clap(long, value_name = "N")]
+    pub entry_depth: Option<usize>,
+
+    /// Output only the definitions of this symbol, e.g. `CacheManager::new` (repeatable; requires tree-sitter feature)
+    #[clap(long, value_name = "NAME")]
+    pub symbol: Vec<String>,
+
+    /// Output format: "markdown", "json", or "xml"
+    #[clap(long, value_name = "FORMAT", default_value = "markdown")]
+    pub format: String,
+
+    /// Split the output into numbered parts of at most --max-tokens each (e.g. output_part_001.md)
+    #[clap(long)]
+    pub split: bool,
+
+    /// Only include files changed on this branch since it forked from a git revision (like `git diff REF...`), plus manifests and key docs
+    #[clap(long, value_name = "REF")]
+    pub since: Option<String>,
+
+    /// Only include files with staged changes (index vs HEAD), plus manifests and key docs
+    #[clap(long)]
+    pub staged: bool,
+
+    /// Only include files with unstaged changes (working tree vs index), plus manifests and key docs
+    #[clap(long)]
+    pub unstaged: bool,
+
+    /// Diff against the merge-base of HEAD and a git revision, like `git diff REV...`, instead of the previous run (enables auto-diff; no cache or timestamped output needed)
+    #[clap(long, value_name = "REV")]
+    pub diff_against: Option<String>,
+
+    /// Diff against a snapshot saved with --snapshot-save instead of the previous run (enables auto-diff)
+    #[clap(long, value_name = "NAME", conflicts_with = "diff_against")]
+    pub diff_from: Option<String>,
+
+    /// Save the project state as a named snapshot that later runs can --diff-from
+    #[clap(long, value_name = "NAME")]
+    pub snapshot_save: Option<String>,
+
+    /// List the saved snapshots and exit
+    #[clap(long)]
+    pub list_snapshots: bool,
+
+    /// Delete the snapshots outside the [snapshots] retention policy and exit
+    #[clap(long)]
+    pub prune_snapshots: bool,
+
+    /// Disable masking of likely secrets (API keys, tokens, private keys) in the output
+    #[clap(long)]
+    pub no_redact: bool,
+
+    /// Keep running and regenerate the output whenever relevant files change
+    #[clap(long)]
+    pub watch: bool,
+}
+
+/// Run context-builder as an MCP (Model Context Protocol) server over stdio
+#[derive(Parser, Debug, Clone)]
+#[clap(name = "context-builder mcp", version)]
+pub struct McpArgs {
+    /// Project directory the tools operate on
+    #[clap(short = 'd', long, default_value = ".")]
+    pub input: String,
+
+    /// File extensions to include (e.g., --filter rs,toml)
+    #[clap(short = 'f', long, value_delimiter = ',')]
+    pub filter: Vec<String>,
+
+    /// Folder or file names to ignore (e.g., --ignore target --ignore lock)
+    #[clap(short = 'i', long)]
+    pub ignore: Vec<String>,
+
+    /// Disable masking of likely secrets (API keys, tokens, private keys) in tool results
+    #[clap(long)]
+    pub no_redact: bool,
+
+    /// Tokenizer for token counts: "cl100k", "o200k", "p50k", or "chars[:RATIO]"
+    #[clap(long, value_name = "TOKENIZER", default_value = "cl100k")]
+    pub tokenizer: String,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Args;
+    use super::{Args, McpArgs};
     use clap::Parser;
 
     #[test]
@@ -160,6 +274,30 @@ mod tests {
         assert!(!args.clear_cache);
     }
 
+    #[test]
+    fn parses_snapshot_flags() {
+        let args = Args::try_parse_from([
+            "context-builder",
+            "--snapshot-save",
+            "sprint-12",
+            "--diff-from",
+            "sprint-11",
+        ])
+        .expect("should parse snapshot flags");
+        assert_eq!(args.snapshot_save.as_deref(), Some("sprint-12"));
+        assert_eq!(args.diff_from.as_deref(), Some("sprint-11"));
+        assert!(!args.list_snapshots);
+
+        let res = Args::try_parse_from([
+            "context-builder",
+            "--diff-from",
+            "sprint-11",
+            "--diff-against",
+            "main",
+        ]);
+        assert!(res.is_err(), "--diff-from and --diff-against conflict");
+    }
+
     #[test]
     fn parses_diff_only_flag() {
         let args = Args::try_parse_from(["context-builder", "--diff-only"])
@@ -211,4 +349,169 @@ mod tests {
             .expect("should parse with default visibility");
         assert_eq!(args_default.visibility, "all");
     }
+
+    #[test]
+    fn parses_signature_docs() {
+        let args = Args::try_parse_from(["context-builder", "--signature-docs", "full"])
+            .expect("should parse signature docs mode");
+        assert_eq!(args.signature_docs, "full");
+
+        let args_default = Args::try_parse_from(["context-builder"])
+            .expect("should parse with default signature docs mode");
+        assert_eq!(args_default.signature_docs, "first-line");
+    }
+
+    #[test]
+    fn parses_strip_tests_flag() {
+        let args = Args::try_parse_from(["context-builder", "--strip-tests"])
+            .expect("should parse strip-tests flag");
+        assert!(args.strip_tests);
+        assert!(
+            !Args::try_parse_from(["context-builder"])
+                .unwrap()
+                .strip_tests
+        );
+    }
+
+    #[test]
+    fn parses_compact_level() {
+        let args = Args::try_parse_from(["context-builder", "--compact", "blank-lines"])
+            .expect("should parse compact level");
+        assert_eq!(args.compact.as_deref(), Some("blank-lines"));
+        let args_default =
+            Args::try_parse_from(["context-builder"]).expect("should parse without compact level");
+        assert!(args_default.compact.is_none());
+    }
+
+    #[test]
+    fn parses_output_format() {
+        let args = Args::try_parse_from(["context-builder", "--format", "json"])
+            .expect("should parse format flag");
+        assert_eq!(args.format, "json");
+
+        let args_default =
+            Args::try_parse_from(["context-builder"]).expect("should parse with default format");
+        assert_eq!(args_default.format, "markdown");
+    }
+
+    #[test]
+    fn parses_split_flag() {
+        let args = Args::try_parse_from(["context-builder", "--split", "--max-tokens", "1000"])
+            .expect("should parse split flag");
+        assert!(args.split);
+        assert_eq!(args.max_tokens, Some(1000));
+    }
+
+    #[test]
+    fn parses_git_selection_flags() {
+        let args = Args::try_parse_from(["context-builder", "--since", "main", "--staged"])
+            .expect("should parse git selection flags");
+        assert_eq!(args.since.as_deref(), Some("main"));
+        assert!(args.staged);
+        assert!(!args.unstaged);
+    }
+
+    #[test]
+    fn parses_diff_against() {
+        let args = Args::try_parse_from(["context-builder", "--diff-against", "origin/main"])
+            .expect("should parse diff-against");
+        assert_eq!(args.diff_against.as_deref(), Some("origin/main"));
+    }
+
+    #[test]
+    fn parses_token_count_format() {
+        let args = Args::try_parse_from(["context-builder", "--token-count"])
+            .expect("should parse token-count");
+        assert_eq!(args.token_count_format, "text");
+
+        let args = Args::try_parse_from([
+            "context-builder",
+            "--token-count",
+            "--token-count-format",
+            "json",
+        ])
+        .expect("should parse token-count-format");
+        assert_eq!(args.token_count_format, "json");
+    }
+
+    #[test]
+    fn parses_tokenizer() {
+        let args = Args::try_parse_from(["context-builder"]).expect("should parse");
+        assert_eq!(args.tokenizer, "cl100k");
+
+        let args = Args::try_parse_from(["context-builder", "--tokenizer", "chars:3.5"])
+            .expect("should parse tokenizer");
+        assert_eq!(args.tokenizer, "chars:3.5");
+    }
+
+    #[test]
+    fn parses_focus_globs() {
+        let args = Args::try_parse_from([
+            "context-builder",
+            "--focus",
+            "src/parser/**",
+            "--focus",
+            "src/lexer.rs",
+        ])
+        .expect("should parse focus globs");
+        assert_eq!(args.focus, vec!["src/parser/**", "src/lexer.rs"]);
+    }
+
+    #[test]
+    fn parses_entry_files() {
+        let args = Args::try_parse_from([
+            "context-builder",
+            "--entry",
+            "src/main.rs",
+            "--entry",
+            "src/bin/tool.rs",
+            "--entry-depth",
+            "2",
+        ])
+        .expect("should parse entry files");
+        assert_eq!(args.entry, vec!["src/main.rs", "src/bin/tool.rs"]);
+        assert_eq!(args.entry_depth, Some(2));
+    }
+
+    #[test]
+    fn parses_symbols() {
+        let args = Args::try_parse_from([
+            "context-builder",
+            "--symbol",
+            "CacheManager::new",
+            "--symbol",
+            "ProjectState::compare_with",
+        ])
+        .expect("should parse symbols");
+        assert_eq!(
+            args.symbol,
+            vec!["CacheManager::new", "ProjectState::compare_with"]
+        );
+    }
+
+    #[test]
+    fn parses_watch_flag() {
+        let args =
+            Args::try_parse_from(["context-builder", "--watch"]).expect("should parse watch flag");
+        assert!(args.watch);
+    }
+
+    #[test]
+    fn parses_mcp_args() {
+        let args = McpArgs::try_parse_from([
+            "context-builder mcp",
+            "-d",
+            "project",
+            "-f",
+            "rs,toml",
+            "-i",
+            "target",
+            "--no-redact",
+        ])
+        .expect("should parse mcp args");
+        assert_eq!(args.input, "project");
+        assert_eq!(args.filter, vec!["rs".to_string(), "toml".to_string()]);
+        assert_eq!(args.ignore, vec!["target".to_string()]);
+        assert!(args.no_redact);
+    }
 }
@@ -0,0 +1,379 @@
+//! Comment and whitespace compaction (`--compact`).
+//!
+//! Comments are found on the tree-sitter parse tree for supported languages,
+//! so comment markers inside strings are left alone. Other files fall back to
+//! dropping whole lines that start with the format's line comment marker.
+//! Directives that only look like comments (shebangs, `//go:build`,
+//! `/// <reference .../>`) are always kept.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// What `--compact` removes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompactLevel {
+    /// Comments only
+    Comments,
+    /// Blank lines and trailing whitespace only
+    BlankLines,
+    /// Both
+    All,
+}
+
+impl CompactLevel {
+    fn strips_comments(self) -> bool {
+        matches!(self, CompactLevel::Comments | CompactLevel::All)
+    }
+
+    fn strips_blank_lines(self) -> bool {
+        matches!(self, CompactLevel::BlankLines | CompactLevel::All)
+    }
+}
+
+impl FromStr for CompactLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "comments" => Ok(CompactLevel::Comments),
+            "blank-lines" => Ok(CompactLevel::BlankLines),
+            "all" => Ok(CompactLevel::All),
+            _ => Err(format!(
+                "Unknown compact level '{}' (expected \"comments\", \"blank-lines\" or \"all\")",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CompactLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompactLevel::Comments => write!(f, "comments"),
+            CompactLevel::BlankLines => write!(f, "blank-lines"),
+            CompactLevel::All => write!(f, "all"),
+        }
+    }
+}
+
+/// Returns `content` compacted at `level`.
+pub fn compact(content: &str, extension: &str, level: CompactLevel) -> String {
+    let mut output = content.to_string();
+    if level.strips_comments() {
+        output = strip_comments(&output, extension);
+    }
+    if level.strips_blank_lines() {
+        output = strip_blank_lines(&output, extension);
+    }
+    output
+}
+
+/// Comments that change how the file is built or run.
+fn is_directive(comment: &str) -> bool {
+    ["#!", "//go:", "// +build", "/// <reference"]
+        .iter()
+        .any(|prefix| comment.starts_with(prefix))
+}
+
+fn strip_comments(content: &str, extension: &str) -> String {
+    #[cfg(feature = "tree-sitter-base")]
+    if let Some(ranges) = crate::tree_sitter::comment_ranges_for_file(content, extension) {
+        return remove_comment_ranges(content, &ranges);
+    }
+
+    strip_comment_lines(content, extension)
+}
+
+/// Removes the comments at `ranges`: a comment on lines of its own takes
+/// those lines with it, a trailing comment the whitespace before it.
+#[cfg(feature = "tree-sitter-base")]
+fn remove_comment_ranges(content: &str, ranges: &[(usize, usize)]) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for &(start, end) in ranges {
+        let comment = content[start..end].trim_end();
+        if start < cursor || is_directive(comment) {
+            continue;
+        }
+        let end = start + comment.len();
+
+        output.push_str(&content[cursor..start]);
+        let line_end = content[end..].find('\n').map_or(content.len(), |i| end + i);
+        if content[end..line_end].trim().is_empty() {
+            let line_start = output.rfind('\n').map_or(0, |i| i + 1);
+            if output[line_start..].trim().is_empty() {
+                output.truncate(line_start);
+                cursor = (line_end + 1).min(content.len());
+            } else {
+                let kept = output.trim_end_matches([' ', '\t']).len();
+                output.truncate(kept);
+                cursor = line_end;
+            }
+        } else {
+            cursor = end;
+        }
+    }
+    output.push_str(&content[cursor..]);
+    output
+}
+
+/// Line comment marker for files without a tree-sitter grammar, where a
+/// line starting with it is certainly a comment.
+fn line_comment_marker(extension: &str) -> Option<&'static str> {
+    match extension {
+        "rs" | "js" | "mjs" | "cjs" | "jsx" | "ts" | "tsx" | "mts" | "cts" | "go" | "java"
+        | "c" | "h" | "cpp" | "cxx" | "cc" | "hpp" | "hxx" | "hh" | "cs" | "kt" | "kts"
+        | "swift" | "scala" | "sc" | "zig" | "dart" | "php" | "proto" => Some("//"),
+        "py" | "pyw" | "sh" | "bash" | "zsh" | "rb" | "rake" | "pl" | "r" | "yaml" | "yml"
+        | "toml" | "tf" | "cmake" | "dockerfile" => Some("#"),
+        "lua" | "sql" | "hs" => Some("--"),
+        _ => None,
+    }
+}
+
+fn strip_comment_lines(content: &str, extension: &str) -> String {
+    let Some(marker) = line_comment_marker(extension) else {
+        return content.to_string();
+    };
+    content
+        .split_inclusive('\n')
+        .filter(|line| {
+            let line = line.trim_start();
+            !line.starts_with(marker) || is_directive(line)
+        })
+        .collect()
+}
+
+/// Trims trailing whitespace and drops blank lines, except inside string
+/// literals (located on the parse tree) and YAML block scalars, where both
+/// are part of the value. In prose, where blank lines separate paragraphs,
+/// runs of them are collapsed into one instead. Other formats are left
+/// alone: without a parse tree a blank line may belong to a string.
+fn strip_blank_lines(content: &str, extension: &str) -> String {
+    let prose = matches!(extension, "md" | "markdown" | "txt" | "rst" | "adoc");
+    let literals = if prose {
+        Vec::new()
+    } else if matches!(extension, "yaml" | "yml") {
+        yaml_block_scalar_ranges(content)
+    } else {
+        #[cfg(feature = "tree-sitter-base")]
+        let literals = crate::tree_sitter::literal_ranges_for_file(content, extension);
+        #[cfg(not(feature = "tree-sitter-base"))]
+        let literals: Option<Vec<(usize, usize)>> = None;
+        match literals {
+            Some(literals) => literals,
+            None => return content.to_string(),
+        }
+    };
+    let inside = |position: usize| {
+        let index = literals.partition_point(|&(start, _)| start < position);
+        index > 0 && position < literals[index - 1].1
+    };
+
+    let mut output = String::with_capacity(content.len());
+    let mut previous_blank = true;
+    let mut line_start = 0;
+    for line in content.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+        if inside(start) {
+            output.push_str(line);
+            previous_blank = false;
+            continue;
+        }
+        let body = line.strip_suffix('\n').unwrap_or(line);
+        let line = match inside(start + body.len()) {
+            true => body,
+            false => body.trim_end(),
+        };
+        if line.is_empty() {
+            if prose && !previous_blank {
+                output.push('\n');
+            }
+            previous_blank = true;
+            continue;
+        }
+        output.push_str(line);
+        output.push('\n');
+        previous_blank = false;
+    }
+    while prose && output.ends_with("\n\n") {
+        output.pop();
+    }
+    output
+}
+
+/// Byte ranges of the YAML block scalars (`key: |`, `- >-`, ...) in
+/// `content`, each from the newline ending its header line to the end of its
+/// last line. Trailing blank lines belong to it only with the `+` indicator.
+fn yaml_block_scalar_ranges(content: &str) -> Vec<(usize, usize)> {
+    fn indentation(line: &str) -> usize {
+        line.len() - line.trim_start_matches(' ').len()
+    }
+
+    fn is_block_header(line: &str) -> bool {
+        let line = line.split(" #").next().unwrap_or(line).trim_end();
+        let indicator = line.rsplit([' ', ':']).next().unwrap_or(line);
+        indicator.starts_with(['|', '>'])
+            && indicator[1..]
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '+' || c == '-')
+    }
+
+    let lines: Vec<(usize, &str)> = content
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, line))
+        })
+        .collect();
+
+    let mut ranges = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let (start, header) = lines[index];
+        index += 1;
+        let header = header.trim_end_matches(['\n', '\r']);
+        if !is_block_header(header) {
+            continue;
+        }
+        let parent = indentation(header);
+        let keep = header
+            .split(" #")
+            .next()
+            .unwrap_or(header)
+            .trim_end()
+            .ends_with('+');
+        let mut end = None;
+        while index < lines.len() {
+            let (line_start, line) = lines[index];
+            if line.trim().is_empty() {
+                if keep {
+                    end = Some(line_start + line.len());
+                }
+            } else if indentation(line) > parent {
+                end = Some(line_start + line.len());
+            } else {
+                break;
+            }
+            index += 1;
+        }
+        if let Some(end) = end {
+            ranges.push((start + header.len(), end));
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_compact_level() {
+        assert_eq!("comments".parse(), Ok(CompactLevel::Comments));
+        assert_eq!("blank-lines".parse(), Ok(CompactLevel::BlankLines));
+        assert_eq!("ALL".parse(), Ok(CompactLevel::All));
+        assert!("whitespace".parse::<CompactLevel>().is_err());
+        assert_eq!(CompactLevel::BlankLines.to_string(), "blank-lines");
+    }
+
+    #[test]
+    fn test_blank_lines() {
+        let prose = "# Title  \n\n\n\nFirst paragraph.\n\nSecond.\n\n";
+        assert_eq!(
+            compact(prose, "md", CompactLevel::BlankLines),
+            "# Title\n\nFirst paragraph.\n\nSecond.\n"
+        );
+
+        // Without a parse tree, blank lines may belong to a string
+        let source = "SELECT 1;   \n\n\nSELECT 2;\n";
+        assert_eq!(compact(source, "sql", CompactLevel::BlankLines), source);
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-rust")]
+    fn test_blank_lines_with_tree_sitter() {
+        let source = "fn a() {}   \n\n\n\nfn b() {}\n\n";
+        assert_eq!(
+            compact(source, "rs", CompactLevel::BlankLines),
+            "fn a() {}\nfn b() {}\n"
+        );
+
+        let source = "fn text() -> &'static str {\n\n    r\"line1  \n\nline3\"\n}\n";
+        assert_eq!(
+            compact(source, "rs", CompactLevel::BlankLines),
+            "fn text() -> &'static str {\n    r\"line1  \n\nline3\"\n}\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-python")]
+    fn test_blank_lines_keep_python_strings() {
+        let source =
+            "QUERY = \"\"\"\nSELECT id   \n\nFROM users\n\"\"\"\n\n\ndef run():\n    pass\n";
+        assert_eq!(
+            compact(source, "py", CompactLevel::BlankLines),
+            "QUERY = \"\"\"\nSELECT id   \n\nFROM users\n\"\"\"\ndef run():\n    pass\n"
+        );
+    }
+
+    #[test]
+    fn test_blank_lines_keep_yaml_block_scalars() {
+        let source = "jobs:\n\n  build:\n    script: |\n      make   \n\n      make test\n\n    when: always  \n";
+        assert_eq!(
+            compact(source, "yaml", CompactLevel::BlankLines),
+            "jobs:\n  build:\n    script: |\n      make   \n\n      make test\n    when: always\n"
+        );
+
+        let source = "notes: >+\n  kept\n\nnext: 1\n";
+        assert_eq!(compact(source, "yml", CompactLevel::BlankLines), source);
+    }
+
+    #[test]
+    fn test_comment_lines_fallback() {
+        let source = "#!/usr/bin/env bash\n# setup\nset -e\n  # indented\necho hi\n";
+        assert_eq!(
+            strip_comment_lines(source, "sh"),
+            "#!/usr/bin/env bash\nset -e\necho hi\n"
+        );
+        // Formats without a known marker are left alone
+        assert_eq!(strip_comment_lines("; note\n", "ini"), "; note\n");
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-rust")]
+    fn test_comments_with_tree_sitter() {
+        let source = r#"//! Crate docs.
+
+/// Adds.
+pub fn add(a: i32, b: i32) -> i32 {
+    // Sum
+    a + b // trailing
+}
+
+/* block
+   comment */
+const URL: &str = "http://example.com // not a comment";
+"#;
+        assert_eq!(
+            compact(source, "rs", CompactLevel::Comments),
+            "\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\nconst URL: &str = \"http://example.com // not a comment\";\n"
+        );
+        assert_eq!(
+            compact(source, "rs", CompactLevel::All),
+            "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\nconst URL: &str = \"http://example.com // not a comment\";\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-go")]
+    fn test_go_directives_kept() {
+        let source = "//go:build linux\n\n// Package x.\npackage x\n";
+        assert_eq!(
+            compact(source, "go", CompactLevel::Comments),
+            "//go:build linux\n\npackage x\n"
+        );
+    }
+}
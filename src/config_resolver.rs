@@ -26,11 +26,30 @@ pub struct ResolvedConfig {
     pub auto_diff: bool,
     pub diff_context_lines: usize,
     pub max_tokens: Option<usize>,
+    pub tokenizer: String,
     pub init: bool,
     pub signatures: bool,
     pub structure: bool,
     pub truncate: String,
     pub visibility: String,
+    pub signature_docs: String,
+    pub strip_tests: bool,
+    pub compact: Option<String>,
+    pub focus: Vec<String>,
+    pub entry: Vec<String>,
+    pub entry_depth: Option<usize>,
+    pub format: String,
+    pub split: bool,
+    pub symbol: Vec<String>,
+    pub since: Option<String>,
+    pub staged: bool,
+    pub unstaged: bool,
+    pub diff_against: Option<String>,
+    pub diff_from: Option<String>,
+    pub snapshot_save: Option<String>,
+    pub redact: bool,
+    pub watch: bool,
+    pub token_count_format: String,
 }
 
 /// Result of configuration resolution including the final config and any warnings
@@ -54,6 +73,24 @@ pub struct ConfigResolution {
 pub fn resolve_final_config(mut args: Args, config: Option<Config>) -> ConfigResolution {
     let mut warnings = Vec::new();
 
+    // Format: CLI takes precedence if not the default ("markdown")
+    let format = if args.format != "markdown" {
+        args.format.clone()
+    } else {
+        config
+            .as_ref()
+            .and_then(|c| c.format.clone())
+            .unwrap_or_else(|| args.format.clone())
+    };
+
+    // The default output name follows the format, unless the config names the output
+    if args.output == "output.md"
+        && config.as_ref().and_then(|c| c.output.as_ref()).is_none()
+        && let Some(extension) = default_extension_for_format(&format)
+    {
+        args.output = format!("output.{}", extension);
+    }
+
     // Start with CLI defaults, then apply config file, then explicit CLI overrides
     let final_config = if let Some(config) = config {
         apply_config_to_args(&mut args, &config, &mut warnings);
@@ -77,6 +114,14 @@ pub fn resolve_final_config(mut args: Args, config: Option<Config>) -> ConfigRes
         auto_diff: final_config.auto_diff.unwrap_or(false),
         diff_context_lines: final_config.diff_context_lines.unwrap_or(3),
         max_tokens: args.max_tokens.or(final_config.max_tokens),
+        tokenizer: if args.tokenizer != "cl100k" {
+            args.tokenizer.clone()
+        } else {
+            final_config
+                .tokenizer
+                .clone()
+                .unwrap_or_else(|| args.tokenizer.clone())
+        },
         init: args.init,
         signatures: args.signatures || final_config.signatures.unwrap_or(false),
         structure: args.structure || final_config.structure.unwrap_or(false),
@@ -96,6 +141,53 @@ pub fn resolve_final_config(mut args: Args, config: Option<Config>) -> ConfigRes
                 .clone()
                 .unwrap_or_else(|| args.visibility.clone())
         },
+        signature_docs: if args.signature_docs != "first-line" {
+            args.signature_docs.clone()
+        } else {
+            final_config
+                .signature_docs
+                .clone()
+                .unwrap_or_else(|| args.signature_docs.clone())
+        },
+        strip_tests: args.strip_tests || final_config.strip_tests.unwrap_or(false),
+        compact: args
+            .compact
+            .clone()
+            .or_else(|| final_config.compact.clone()),
+        focus: if !args.focus.is_empty() {
+            args.focus.clone()
+        } else {
+            final_config.focus.clone().unwrap_or_default()
+        },
+        entry: if !args.entry.is_empty() {
+            args.entry.clone()
+        } else {
+            final_config.entry.clone().unwrap_or_default()
+        },
+        entry_depth: args.entry_depth.or(final_config.entry_depth),
+        format,
+        split: args.split || final_config.split.unwrap_or(false),
+        symbol: args.symbol.clone(),
+        since: args.since.clone(),
+        staged: args.staged,
+        unstaged: args.unstaged,
+        // A snapshot named on the command line replaces a configured revision
+        diff_against: args.diff_against.clone().or_else(|| {
+            final_config
+                .diff_against
+                .clone()
+                .filter(|_| args.diff_from.is_none())
+        }),
+        diff_from: args.diff_from.clone(),
+        snapshot_save: args.snapshot_save.clone(),
+        redact: !args.no_redact
+            && final_config
+                .redact
+                .as_ref()
+                .and_then(|r| r.enabled)
+                .unwrap_or(true),
+        watch: args.watch,
+        token_count_format: args.token_count_format.clone(),
     };
 
     ConfigResolution {
@@ -104,6 +196,15 @@ pub fn resolve_final_config(mut args: Args, config: Option<Config>) -> ConfigRes
     }
 }
 
+/// File extension used for the default output name of a non-Markdown format.
+fn default_extension_for_format(format: &str) -> Option<&'static str> {
+    match format {
+        "json" => Some("json"),
+        "xml" => Some("xml"),
+        _ => None,
+    }
+}
+
 /// Apply configuration file values to CLI arguments based on precedence rules
 fn apply_config_to_args(args: &mut Args, config: &Config, warnings: &mut Vec<String>) {
     // Output: only apply config if CLI is using default value
@@ -164,6 +265,9 @@ fn apply_config_to_args(args: &mut Args, config: &Config, warnings: &mut Vec<Str
     // Validate auto_diff configuration
     if let Some(true) = config.auto_diff
         && config.timestamped_output != Some(true)
+        && args.diff_against.is_none()
+        && config.diff_against.is_none()
+        && args.diff_from.is_none()
     {
         warnings.push(
             "auto_diff is enabled but timestamped_output is not enabled. \
@@ -175,6 +279,11 @@ fn apply_config_to_args(args: &mut Args, config: &Config, warnings: &mut Vec<Str
 
 /// Resolve output path including timestamping and output folder logic
 fn resolve_output_path(args: &mut Args, config: &Config, warnings: &mut Vec<String>) {
+    // Streaming to stdout: there is no file to place in a folder or timestamp
+    if args.output == crate::markdown::STDOUT_OUTPUT {
+        return;
+    }
+
     let mut output_folder_path: Option<PathBuf> = None;
 
     // Apply output folder first
@@ -245,6 +354,27 @@ mod tests {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         };
 
         let config = Config {
@@ -282,6 +412,27 @@ mod tests {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         };
 
         let config = Config {
@@ -330,6 +481,27 @@ mod tests {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         };
 
         let config = Config {
@@ -364,6 +536,27 @@ mod tests {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         };
 
         let config = Config {
@@ -396,6 +589,27 @@ mod tests {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         };
 
         let config = Config {
@@ -430,6 +644,27 @@ mod tests {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         };
 
         let config = Config {
@@ -464,6 +699,27 @@ mod tests {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         };
 
         let resolution = resolve_final_config(args.clone(), None);
@@ -481,4 +737,128 @@ mod tests {
         assert_eq!(resolution.config.diff_context_lines, 3);
         assert!(resolution.warnings.is_empty());
     }
+
+    #[test]
+    fn test_json_format_changes_default_output_name() {
+        let args = Args {
+            input: ".".to_string(),
+            output: "output.md".to_string(),
+            filter: vec![],
+            ignore: vec![],
+            line_numbers: false,
+            preview: false,
+            token_count: false,
+            yes: false,
+            diff_only: false,
+            clear_cache: false,
+            init: false,
+            max_tokens: None,
+            signatures: false,
+            structure: false,
+            truncate: "smart".to_string(),
+            visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
+        };
+
+        // Format from config file, default output name follows it
+        let config = Config {
+            format: Some("json".to_string()),
+            ..Default::default()
+        };
+        let resolution = resolve_final_config(args.clone(), Some(config));
+        assert_eq!(resolution.config.format, "json");
+        assert_eq!(resolution.config.output, "output.json");
+
+        // An output named in the config file is kept as-is
+        let config = Config {
+            format: Some("json".to_string()),
+            output: Some("context.md".to_string()),
+            ..Default::default()
+        };
+        let resolution = resolve_final_config(args.clone(), Some(config));
+        assert_eq!(resolution.config.output, "context.md");
+
+        // An explicit CLI format wins over the config file
+        let mut cli_args = args;
+        cli_args.format = "json".to_string();
+        let config = Config {
+            format: Some("markdown".to_string()),
+            ..Default::default()
+        };
+        let resolution = resolve_final_config(cli_args, Some(config));
+        assert_eq!(resolution.config.format, "json");
+        assert_eq!(resolution.config.output, "output.json");
+    }
+
+    #[test]
+    fn test_stdout_output_skips_folder_and_timestamp() {
+        let args = Args {
+            input: ".".to_string(),
+            output: "-".to_string(),
+            filter: vec![],
+            ignore: vec![],
+            line_numbers: false,
+            preview: false,
+            token_count: false,
+            yes: false,
+            diff_only: false,
+            clear_cache: false,
+            init: false,
+            max_tokens: None,
+            signatures: false,
+            structure: false,
+            truncate: "smart".to_string(),
+            visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
+        };
+
+        let config = Config {
+            output_folder: Some("docs".to_string()),
+            timestamped_output: Some(true),
+            ..Default::default()
+        };
+
+        let resolution = resolve_final_config(args, Some(config));
+        assert_eq!(resolution.config.output, "-");
+    }
 }
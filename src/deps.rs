@@ -0,0 +1,869 @@
+//! Import graph traversal for `--entry`.
+//!
+//! Starting from one or more entry files, follows the imports tree-sitter
+//! extracts into [`CodeStructure::imports`](crate::tree_sitter::CodeStructure)
+//! and keeps only the files reachable from them. Import specifiers are
+//! resolved against the collected files, so anything outside the project
+//! (the standard library, packages, system headers) is simply not followed:
+//!
+//! - Rust: `mod foo;`, `use crate::`/`self::`/`super::` paths, paths into
+//!   the project's own library crates, and child modules named by a bare
+//!   `use foo::..`
+//! - JavaScript/TypeScript: relative `import`, `export .. from` and
+//!   `require()` specifiers, with extension and `index` resolution
+//! - Python: `import a.b` and `from .a import b`, relative to the importing
+//!   file's package or any of its parent directories
+//! - Go: imports under the module path declared in `go.mod`; a package is
+//!   its whole directory, so the other files of the importing package come
+//!   along too
+//! - C/C++: quoted `#include`s, plus the implementation file next to an
+//!   included header
+//! - Java: `import` declarations matched by package path
+
+use ignore::DirEntry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+const C_HEADER_EXTENSIONS: &[&str] = &["h", "hh", "hpp", "hxx"];
+const C_SOURCE_EXTENSIONS: &[&str] = &["c", "cc", "cpp", "cxx"];
+
+/// Keeps only `files` reachable from the `entries` (paths relative to
+/// `base_path`) by following imports, at most `max_depth` hops away.
+pub fn retain_import_closure(
+    files: &mut Vec<DirEntry>,
+    base_path: &Path,
+    entries: &[String],
+    max_depth: Option<usize>,
+) -> io::Result<()> {
+    let known: HashSet<PathBuf> = files
+        .iter()
+        .map(|entry| {
+            entry
+                .path()
+                .strip_prefix(base_path)
+                .unwrap_or(entry.path())
+                .to_path_buf()
+        })
+        .collect();
+    let graph = ImportGraph::new(base_path, known);
+
+    let mut roots = Vec::new();
+    for entry in entries {
+        let path = Path::new(entry);
+        let relative = normalize(path.strip_prefix(base_path).unwrap_or(path));
+        match relative {
+            Some(relative) if graph.known.contains(&relative) => roots.push(relative),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Entry file '{}' is not among the collected files (paths are relative to the input directory)",
+                        entry
+                    ),
+                ));
+            }
+        }
+    }
+
+    let closure = graph.closure(&roots, max_depth, |relative| {
+        read_imports(&base_path.join(relative))
+    });
+    files.retain(|entry| {
+        let relative = entry.path().strip_prefix(base_path).unwrap_or(entry.path());
+        closure.contains(relative)
+    });
+    Ok(())
+}
+
+#[cfg(feature = "tree-sitter-base")]
+fn read_imports(path: &Path) -> Vec<String> {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|source| crate::tree_sitter::extract_structure_for_file(&source, ext))
+        .map(|structure| structure.imports)
+        .unwrap_or_default()
+}
+
+#[cfg(not(feature = "tree-sitter-base"))]
+fn read_imports(_path: &Path) -> Vec<String> {
+    Vec::new()
+}
+
+/// Resolves import specifiers to files of the project.
+struct ImportGraph {
+    known: HashSet<PathBuf>,
+    /// Directory of each `go.mod` and the module path it declares.
+    go_modules: Vec<(PathBuf, String)>,
+    /// Library crates of the project by name (as written in paths), mapped
+    /// to the directory holding their `lib.rs`.
+    rust_crates: HashMap<String, PathBuf>,
+}
+
+impl ImportGraph {
+    fn new(base_path: &Path, known: HashSet<PathBuf>) -> Self {
+        let go_modules = known
+            .iter()
+            .filter(|path| path.file_name().is_some_and(|name| name == "go.mod"))
+            .filter_map(|path| {
+                let contents = fs::read_to_string(base_path.join(path)).ok()?;
+                let module = contents.lines().find_map(|line| {
+                    line.trim()
+                        .strip_prefix("module ")
+                        .map(|m| m.trim().trim_matches('"').to_string())
+                })?;
+                Some((parent(path), module))
+            })
+            .collect();
+        let rust_crates = known
+            .iter()
+            .filter(|path| path.file_name().is_some_and(|name| name == "lib.rs"))
+            .filter_map(|lib| {
+                let src_dir = parent(lib);
+                let manifest = base_path.join(parent(&src_dir)).join("Cargo.toml");
+                let name = cargo_package_name(&fs::read_to_string(manifest).ok()?)?;
+                Some((name.replace('-', "_"), src_dir))
+            })
+            .collect();
+        Self {
+            known,
+            go_modules,
+            rust_crates,
+        }
+    }
+
+    /// Breadth-first walk from `roots`; `imports_of` yields the raw import
+    /// statements of a file.
+    ///
+    /// Rust `mod` declarations are only followed within the module trees of
+    /// the roots: reaching `lib.rs` through `use crate::Item` should not pull
+    /// in every module of the crate.
+    fn closure(
+        &self,
+        roots: &[PathBuf],
+        max_depth: Option<usize>,
+        mut imports_of: impl FnMut(&Path) -> Vec<String>,
+    ) -> HashSet<PathBuf> {
+        let mut depths: HashMap<PathBuf, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+        for root in roots {
+            if depths.insert(root.clone(), 0).is_none() {
+                queue.push_back((root.clone(), 0, true));
+            }
+        }
+
+        while let Some((file, depth, follow_mods)) = queue.pop_front() {
+            // Files of the same Go package count as the same node
+            for sibling in self.go_package_siblings(&file) {
+                if !depths.contains_key(&sibling) {
+                    depths.insert(sibling.clone(), depth);
+                    queue.push_front((sibling, depth, follow_mods));
+                }
+            }
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+
+            let is_rust = file.extension().is_some_and(|ext| ext == "rs");
+            let (declarations, uses): (Vec<String>, Vec<String>) = imports_of(&file)
+                .into_iter()
+                .partition(|import| is_rust && rust_mod_declaration(import).is_some());
+            let children = if follow_mods {
+                self.resolve(&file, &declarations)
+            } else {
+                Vec::new()
+            };
+            let dependencies = children
+                .into_iter()
+                .map(|child| (child, true))
+                .chain(self.resolve(&file, &uses).into_iter().map(|d| (d, false)));
+            for (dependency, via_mod) in dependencies {
+                if !depths.contains_key(&dependency) {
+                    depths.insert(dependency.clone(), depth + 1);
+                    queue.push_back((dependency, depth + 1, via_mod));
+                }
+            }
+        }
+
+        depths.into_keys().collect()
+    }
+
+    /// Resolves the import statements of `file` to project files.
+    fn resolve(&self, file: &Path, imports: &[String]) -> Vec<PathBuf> {
+        let ext = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let mut resolved = Vec::new();
+        for import in imports {
+            match ext {
+                "rs" => self.resolve_rust(file, import, &mut resolved),
+                "py" | "pyi" => self.resolve_python(file, import, &mut resolved),
+                "go" => self.resolve_go(file, import, &mut resolved),
+                "java" => self.resolve_java(import, &mut resolved),
+                _ if JS_EXTENSIONS.contains(&ext) => self.resolve_js(file, import, &mut resolved),
+                _ if C_HEADER_EXTENSIONS.contains(&ext) || C_SOURCE_EXTENSIONS.contains(&ext) => {
+                    self.resolve_include(file, import, &mut resolved)
+                }
+                _ => {}
+            }
+        }
+        resolved
+    }
+
+    fn first_known(&self, candidates: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+        candidates
+            .into_iter()
+            .filter_map(|candidate| normalize(&candidate))
+            .find(|candidate| self.known.contains(candidate))
+    }
+
+    // --- Rust ---
+
+    fn resolve_rust(&self, file: &Path, import: &str, resolved: &mut Vec<PathBuf>) {
+        let module_dir = self.rust_module_dir(file);
+
+        if let Some(name) = rust_mod_declaration(import) {
+            resolved.extend(self.rust_module_file(&module_dir.join(name)));
+            return;
+        }
+
+        for segments in rust_use_paths(import) {
+            let (dir, rest, explicit) = match segments.first().map(String::as_str) {
+                Some("crate") => (self.rust_crate_root(file).0, &segments[1..], true),
+                Some("self") => (module_dir.clone(), &segments[1..], true),
+                Some("super") => {
+                    let supers = segments.iter().take_while(|s| *s == "super").count();
+                    let mut dir = module_dir.clone();
+                    for _ in 0..supers {
+                        dir = parent(&dir);
+                    }
+                    (dir, &segments[supers..], true)
+                }
+                Some(name) if self.rust_crates.contains_key(name) => {
+                    (self.rust_crates[name].clone(), &segments[1..], true)
+                }
+                // A bare path may name a child module (or an external crate)
+                _ => (module_dir.clone(), &segments[..], false),
+            };
+
+            // The longest prefix naming a module file; the rest are items
+            let module = (1..=rest.len()).rev().find_map(|len| {
+                let mut path = dir.clone();
+                path.extend(rest[..len].iter().map(String::as_str));
+                self.rust_module_file(&path)
+            });
+            match module {
+                Some(module) => resolved.push(module),
+                None if explicit => resolved.extend(self.rust_dir_module_file(&dir)),
+                None => {}
+            }
+        }
+    }
+
+    /// The crate root directory and file (`lib.rs` or `main.rs`) of `file`.
+    fn rust_crate_root(&self, file: &Path) -> (PathBuf, Option<PathBuf>) {
+        for dir in file.ancestors().skip(1) {
+            for root in ["lib.rs", "main.rs"] {
+                let candidate = dir.join(root);
+                if self.known.contains(&candidate) {
+                    return (dir.to_path_buf(), Some(candidate));
+                }
+            }
+        }
+        (parent(file), None)
+    }
+
+    /// Directory holding the child modules of `file`.
+    fn rust_module_dir(&self, file: &Path) -> PathBuf {
+        let is_root = self.rust_crate_root(file).1.as_deref() == Some(file);
+        let owns_dir = is_root
+            || file
+                .file_name()
+                .is_some_and(|name| name == "mod.rs" || name == "lib.rs" || name == "main.rs");
+        match file.file_stem() {
+            Some(stem) if !owns_dir => parent(file).join(stem),
+            _ => parent(file),
+        }
+    }
+
+    /// `foo.rs` or `foo/mod.rs` for the module at `path` (`.../foo`).
+    fn rust_module_file(&self, path: &Path) -> Option<PathBuf> {
+        self.first_known([path.with_extension("rs"), path.join("mod.rs")])
+    }
+
+    /// The file defining the module whose children live in `dir`.
+    fn rust_dir_module_file(&self, dir: &Path) -> Option<PathBuf> {
+        self.first_known([dir.join("lib.rs"), dir.join("main.rs")])
+            .or_else(|| self.rust_module_file(dir))
+    }
+
+    // --- JavaScript / TypeScript ---
+
+    fn resolve_js(&self, file: &Path, import: &str, resolved: &mut Vec<PathBuf>) {
+        let Some(specifier) = quoted_strings(import).into_iter().next() else {
+            return;
+        };
+        if !specifier.starts_with("./") && !specifier.starts_with("../") {
+            return;
+        }
+        let target = parent(file).join(&specifier);
+
+        let mut candidates = vec![target.clone()];
+        // TypeScript sources import their compiled name: `./foo.js` is `./foo.ts`
+        let stem_target = match target.extension().and_then(|e| e.to_str()) {
+            Some(ext) if JS_EXTENSIONS.contains(&ext) => target.with_extension(""),
+            _ => target.clone(),
+        };
+        for ext in JS_EXTENSIONS {
+            candidates.push(append_extension(&stem_target, ext));
+        }
+        for ext in JS_EXTENSIONS {
+            candidates.push(target.join(format!("index.{}", ext)));
+        }
+        resolved.extend(self.first_known(candidates));
+    }
+
+    // --- Python ---
+
+    fn resolve_python(&self, file: &Path, import: &str, resolved: &mut Vec<PathBuf>) {
+        let import = import.trim();
+        if let Some(rest) = import.strip_prefix("from ") {
+            let Some((module, names)) = rest.split_once(" import ") else {
+                return;
+            };
+            let module = module.trim();
+            let names: Vec<&str> = names
+                .trim()
+                .trim_matches(|c| c == '(' || c == ')')
+                .split(',')
+                .filter_map(|name| name.split_whitespace().next())
+                .filter(|name| *name != "*")
+                .collect();
+
+            let dots = module.chars().take_while(|c| *c == '.').count();
+            let module = &module[dots..];
+            let roots: Vec<PathBuf> = if dots > 0 {
+                let mut dir = parent(file);
+                for _ in 1..dots {
+                    dir = parent(&dir);
+                }
+                vec![dir]
+            } else {
+                python_roots(file)
+            };
+
+            for root in &roots {
+                let package = join_dotted(root, module);
+                let found = self.python_module_file(&package);
+                // `from pkg import sub` may name submodules as well as items
+                let submodules: Vec<PathBuf> = names
+                    .iter()
+                    .filter_map(|name| self.python_module_file(&package.join(name)))
+                    .collect();
+                if found.is_some() || !submodules.is_empty() {
+                    resolved.extend(found);
+                    resolved.extend(submodules);
+                    break;
+                }
+            }
+        } else if let Some(rest) = import.strip_prefix("import ") {
+            for module in rest.split(',').filter_map(|m| m.split_whitespace().next()) {
+                resolved.extend(
+                    python_roots(file)
+                        .iter()
+                        .find_map(|root| self.python_module_file(&join_dotted(root, module))),
+                );
+            }
+        }
+    }
+
+    /// `foo.py`, `foo.pyi` or `foo/__init__.py` for the module at `path`.
+    fn python_module_file(&self, path: &Path) -> Option<PathBuf> {
+        if path.as_os_str().is_empty() {
+            return None;
+        }
+        self.first_known([
+            append_extension(path, "py"),
+            append_extension(path, "pyi"),
+            path.join("__init__.py"),
+        ])
+    }
+
+    // --- Go ---
+
+    fn resolve_go(&self, file: &Path, import: &str, resolved: &mut Vec<PathBuf>) {
+        let Some((module_dir, module)) = self
+            .go_modules
+            .iter()
+            .filter(|(dir, _)| file.starts_with(dir))
+            .max_by_key(|(dir, _)| dir.components().count())
+        else {
+            return;
+        };
+
+        for path in quoted_strings(import) {
+            let package_dir = if path == *module {
+                module_dir.clone()
+            } else if let Some(rest) = path.strip_prefix(&format!("{}/", module)) {
+                module_dir.join(rest)
+            } else {
+                continue;
+            };
+            resolved.extend(self.go_package_files(&package_dir));
+        }
+    }
+
+    fn go_package_files(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self
+            .known
+            .iter()
+            .filter(|path| parent(path) == dir)
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.ends_with(".go") && !n.ends_with("_test.go"))
+            })
+            .cloned()
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn go_package_siblings(&self, file: &Path) -> Vec<PathBuf> {
+        if file.extension().is_none_or(|ext| ext != "go") {
+            return Vec::new();
+        }
+        self.go_package_files(&parent(file))
+    }
+
+    // --- C / C++ ---
+
+    fn resolve_include(&self, file: &Path, import: &str, resolved: &mut Vec<PathBuf>) {
+        // `#include <...>` names system or library headers
+        let Some(header) = quoted_strings(import).into_iter().next() else {
+            return;
+        };
+        let found = self
+            .first_known([
+                parent(file).join(&header),
+                PathBuf::from(&header),
+                Path::new("include").join(&header),
+                Path::new("src").join(&header),
+            ])
+            .or_else(|| {
+                let mut matches: Vec<&PathBuf> = self
+                    .known
+                    .iter()
+                    .filter(|path| path.ends_with(&header))
+                    .collect();
+                matches.sort();
+                matches.first().map(|path| (*path).clone())
+            });
+        let Some(found) = found else {
+            return;
+        };
+
+        // The header's implementation usually sits next to it
+        let implementation = found
+            .extension()
+            .and_then(|e| e.to_str())
+            .filter(|ext| C_HEADER_EXTENSIONS.contains(ext))
+            .and_then(|_| {
+                self.first_known(
+                    C_SOURCE_EXTENSIONS
+                        .iter()
+                        .map(|ext| found.with_extension(ext)),
+                )
+            });
+        resolved.push(found);
+        resolved.extend(implementation);
+    }
+
+    // --- Java ---
+
+    fn resolve_java(&self, import: &str, resolved: &mut Vec<PathBuf>) {
+        let Some(name) = import.trim().strip_prefix("import").map(|rest| {
+            rest.trim()
+                .trim_start_matches("static ")
+                .trim_end_matches(';')
+        }) else {
+            return;
+        };
+        let segments: Vec<&str> = name.trim().split('.').collect();
+
+        if segments.last() == Some(&"*") {
+            let package: PathBuf = segments[..segments.len() - 1].iter().collect();
+            let mut files: Vec<PathBuf> = self
+                .known
+                .iter()
+                .filter(|path| path.extension().is_some_and(|ext| ext == "java"))
+                .filter(|path| parent(path).ends_with(&package))
+                .cloned()
+                .collect();
+            files.sort();
+            resolved.extend(files);
+            return;
+        }
+
+        // `import static a.B.member` names a member of class `B`
+        for len in (1..=segments.len()).rev() {
+            let class: PathBuf = segments[..len].iter().collect();
+            let class = class.with_extension("java");
+            let mut matches: Vec<&PathBuf> = self
+                .known
+                .iter()
+                .filter(|path| path.ends_with(&class))
+                .collect();
+            matches.sort();
+            if let Some(found) = matches.first() {
+                resolved.push((*found).clone());
+                return;
+            }
+        }
+    }
+}
+
+/// Module name of a Rust `mod foo;` declaration.
+fn rust_mod_declaration(text: &str) -> Option<&str> {
+    let text = text.trim().strip_suffix(';')?;
+    let mut words = text.split_whitespace();
+    words.by_ref().find(|word| *word == "mod")?;
+    words.next()
+}
+
+/// `name` of the `[package]` section of a `Cargo.toml`.
+fn cargo_package_name(manifest: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim() == "name"
+        {
+            return Some(value.trim().trim_matches('"').to_string());
+        }
+    }
+    None
+}
+
+/// Expands a Rust `use` declaration into its paths:
+/// `use crate::a::{b, c::d as e};` gives `crate::a::b` and `crate::a::c::d`.
+fn rust_use_paths(text: &str) -> Vec<Vec<String>> {
+    let Some(start) = text.find("use ") else {
+        return Vec::new();
+    };
+    let body = text[start + 4..].trim().trim_end_matches(';');
+
+    let mut tokens = Vec::new();
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' | ',' | '*' => tokens.push(c.to_string()),
+            ':' => {
+                chars.next_if_eq(&':');
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '$' => {
+                let mut ident = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    ident.push(c);
+                }
+                tokens.push(ident);
+            }
+            _ => {}
+        }
+    }
+
+    let mut paths = Vec::new();
+    let mut pos = 0;
+    expand_use_tree(&tokens, &mut pos, Vec::new(), &mut paths);
+    paths
+}
+
+fn expand_use_tree(
+    tokens: &[String],
+    pos: &mut usize,
+    mut prefix: Vec<String>,
+    paths: &mut Vec<Vec<String>>,
+) {
+    while let Some(token) = tokens.get(*pos) {
+        *pos += 1;
+        match token.as_str() {
+            "{" => {
+                while *pos < tokens.len() && tokens[*pos] != "}" {
+                    if tokens[*pos] == "," {
+                        *pos += 1;
+                        continue;
+                    }
+                    expand_use_tree(tokens, pos, prefix.clone(), paths);
+                }
+                *pos += 1;
+                return;
+            }
+            "}" | "," => {
+                *pos -= 1;
+                break;
+            }
+            "*" => break,
+            "as" => {
+                *pos += 1;
+                break;
+            }
+            "self" if !prefix.is_empty() => {}
+            _ => prefix.push(token.clone()),
+        }
+    }
+    if !prefix.is_empty() {
+        paths.push(prefix);
+    }
+}
+
+/// Contents of the string literals in `text`.
+fn quoted_strings(text: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if matches!(c, '"' | '\'' | '`') {
+            let literal: String = chars.by_ref().take_while(|d| *d != c).collect();
+            strings.push(literal);
+        }
+    }
+    strings
+}
+
+/// Directories a Python absolute import may be relative to: the importing
+/// file's directory and each of its parents, nearest first.
+fn python_roots(file: &Path) -> Vec<PathBuf> {
+    file.ancestors().skip(1).map(Path::to_path_buf).collect()
+}
+
+fn join_dotted(root: &Path, module: &str) -> PathBuf {
+    let mut path = root.to_path_buf();
+    path.extend(module.split('.').filter(|part| !part.is_empty()));
+    path
+}
+
+fn append_extension(path: &Path, ext: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(ext);
+    PathBuf::from(path)
+}
+
+fn parent(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// Resolves `.` and `..` lexically; `None` if the path escapes the base.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::Normal(part) => normalized.push(part),
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn graph(paths: &[&str]) -> ImportGraph {
+        ImportGraph {
+            known: paths.iter().map(PathBuf::from).collect(),
+            go_modules: Vec::new(),
+            rust_crates: HashMap::new(),
+        }
+    }
+
+    fn resolve(graph: &ImportGraph, file: &str, imports: &[&str]) -> Vec<PathBuf> {
+        let imports: Vec<String> = imports.iter().map(|s| s.to_string()).collect();
+        graph.resolve(Path::new(file), &imports)
+    }
+
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn expands_rust_use_trees() {
+        assert_eq!(
+            rust_use_paths("pub(crate) use crate::a::{b, c::{self, d as e}, *};"),
+            vec![
+                vec!["crate", "a", "b"],
+                vec!["crate", "a", "c"],
+                vec!["crate", "a", "c", "d"],
+                vec!["crate", "a"],
+            ]
+        );
+        assert_eq!(rust_mod_declaration("pub mod parser;"), Some("parser"));
+    }
+
+    #[test]
+    fn resolves_rust_modules_and_paths() {
+        let graph = graph(&[
+            "src/main.rs",
+            "src/cli.rs",
+            "src/parser/mod.rs",
+            "src/parser/lexer.rs",
+            "src/util.rs",
+        ]);
+        assert_eq!(
+            resolve(
+                &graph,
+                "src/main.rs",
+                &["mod cli;", "mod parser;", "use std::io;"]
+            ),
+            paths(&["src/cli.rs", "src/parser/mod.rs"])
+        );
+        assert_eq!(
+            resolve(
+                &graph,
+                "src/parser/lexer.rs",
+                &["use super::Token;", "use crate::{util::trim, Config};"]
+            ),
+            paths(&["src/parser/mod.rs", "src/util.rs", "src/main.rs"])
+        );
+    }
+
+    #[test]
+    fn resolves_relative_js_specifiers() {
+        let graph = graph(&[
+            "web/app.ts",
+            "web/lib/index.ts",
+            "web/util.ts",
+            "web/api.js",
+        ]);
+        assert_eq!(
+            resolve(
+                &graph,
+                "web/app.ts",
+                &[
+                    "import { a } from './lib';",
+                    "import b from \"./util.js\";",
+                    "require('./api')",
+                    "import React from 'react';",
+                ]
+            ),
+            paths(&["web/lib/index.ts", "web/util.ts", "web/api.js"])
+        );
+    }
+
+    #[test]
+    fn resolves_python_imports() {
+        let graph = graph(&[
+            "app/main.py",
+            "app/models/__init__.py",
+            "app/models/user.py",
+            "app/config.py",
+        ]);
+        assert_eq!(
+            resolve(
+                &graph,
+                "app/main.py",
+                &[
+                    "from .models import user",
+                    "import config",
+                    "import os, sys"
+                ]
+            ),
+            paths(&[
+                "app/models/__init__.py",
+                "app/models/user.py",
+                "app/config.py"
+            ])
+        );
+    }
+
+    #[test]
+    fn resolves_c_includes_with_implementation() {
+        let graph = graph(&["src/main.c", "src/util.c", "include/util.h"]);
+        assert_eq!(
+            resolve(
+                &graph,
+                "src/main.c",
+                &["#include \"util.h\"", "#include <stdio.h>"]
+            ),
+            paths(&["include/util.h"])
+        );
+
+        let graph = self::graph(&["main.cpp", "net/socket.hpp", "net/socket.cpp"]);
+        assert_eq!(
+            resolve(&graph, "main.cpp", &["#include \"net/socket.hpp\""]),
+            paths(&["net/socket.hpp", "net/socket.cpp"])
+        );
+    }
+
+    #[test]
+    fn go_closure_includes_package_and_module_imports() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("go.mod"), "module example.com/app\n").unwrap();
+        let graph = ImportGraph::new(
+            dir.path(),
+            paths(&[
+                "go.mod",
+                "main.go",
+                "flags.go",
+                "main_test.go",
+                "store/store.go",
+                "other/other.go",
+            ])
+            .into_iter()
+            .collect(),
+        );
+
+        let closure = graph.closure(&paths(&["main.go"]), None, |file| {
+            if file == Path::new("main.go") {
+                vec!["import (\n\t\"fmt\"\n\t\"example.com/app/store\"\n)".to_string()]
+            } else {
+                Vec::new()
+            }
+        });
+        let mut closure: Vec<PathBuf> = closure.into_iter().collect();
+        closure.sort();
+        assert_eq!(closure, paths(&["flags.go", "main.go", "store/store.go"]));
+    }
+
+    #[test]
+    fn closure_follows_mod_declarations_only_below_entries() {
+        let graph = graph(&["src/lib.rs", "src/a.rs", "src/b.rs", "src/c.rs"]);
+        let imports = |file: &Path| match file.to_str() {
+            Some("src/lib.rs") => vec!["mod a;".to_string(), "mod b;".to_string()],
+            Some("src/c.rs") => vec!["use crate::Config;".to_string()],
+            _ => Vec::new(),
+        };
+
+        let from_lib = graph.closure(&paths(&["src/lib.rs"]), None, imports);
+        assert_eq!(from_lib.len(), 3);
+        let from_c = graph.closure(&paths(&["src/c.rs"]), None, imports);
+        assert!(from_c.contains(Path::new("src/lib.rs")));
+        assert!(!from_c.contains(Path::new("src/a.rs")));
+    }
+
+    #[test]
+    fn closure_respects_depth_limit() {
+        let graph = graph(&["a.py", "b.py", "c.py"]);
+        let imports = |file: &Path| match file.to_str() {
+            Some("a.py") => vec!["import b".to_string()],
+            Some("b.py") => vec!["import c".to_string()],
+            _ => Vec::new(),
+        };
+
+        let full = graph.closure(&paths(&["a.py"]), None, imports);
+        assert_eq!(full.len(), 3);
+        let limited = graph.closure(&paths(&["a.py"]), Some(1), imports);
+        assert!(limited.contains(Path::new("b.py")));
+        assert!(!limited.contains(Path::new("c.py")));
+    }
+}
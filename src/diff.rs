@@ -13,7 +13,7 @@ use std::collections::HashMap;
 /// tree portion. Each file receives an isolated unified style diff.
 ///
 /// High level additions:
-/// * `PerFileStatus` – classification of the change.
+/// * `PerFileStatus` – classification of the change (including renames).
 /// * `PerFileDiff` – structured diff result for a single file.
 /// * `diff_file_contents` – core engine producing diffs per file without any
 ///   global "## File Differences" header.
@@ -99,12 +99,18 @@ pub fn generate_diff(old_content: &str, new_content: &str) -> String {
 }
 
 /// Classification of how a file changed between two snapshots.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PerFileStatus {
     Added,
     Removed,
     Modified,
     Unchanged,
+    /// Moved from `from` to `to`; `similarity` is 1.0 when the content is identical.
+    Renamed {
+        from: String,
+        to: String,
+        similarity: f32,
+    },
 }
 
 /// Structured diff result for a single file.
@@ -182,6 +188,33 @@ fn unified_no_header(old: &str, new: &str, context_lines: usize) -> String {
     out
 }
 
+/// Similarity ratio (0.0 to 1.0) of two file contents, by line.
+pub fn similarity(old: &str, new: &str) -> f32 {
+    TextDiff::from_lines(old, new).ratio()
+}
+
+/// Diff for a file moved from `from` to `to`: only the delta between the old
+/// and new content, empty when the content is unchanged.
+pub fn diff_renamed_file(
+    from: &str,
+    to: &str,
+    old_content: &str,
+    new_content: &str,
+    similarity: f32,
+    explicit_context: Option<usize>,
+) -> PerFileDiff {
+    let context_lines = resolve_context_lines(explicit_context);
+    PerFileDiff {
+        path: to.to_string(),
+        status: PerFileStatus::Renamed {
+            from: from.to_string(),
+            to: to.to_string(),
+            similarity,
+        },
+        diff: unified_no_header(old_content, new_content, context_lines),
+    }
+}
+
 /// Diff per file content sets.
 ///
 /// Inputs are maps keyed by file path (relative or absolute – caller decides)
@@ -275,13 +308,20 @@ pub fn render_per_file_diffs(diffs: &[PerFileDiff]) -> String {
     let mut out = String::new();
     for d in diffs {
         out.push_str(&format!("### Diff: `{}`\n\n", d.path));
-        match d.status {
+        match &d.status {
             PerFileStatus::Added => out.push_str("_Status: Added_\n\n"),
             PerFileStatus::Removed => out.push_str("_Status: Removed_\n\n"),
             PerFileStatus::Modified => out.push_str("_Status: Modified_\n\n"),
             PerFileStatus::Unchanged => {
                 out.push_str("_Status: Unchanged_\n\n");
             }
+            PerFileStatus::Renamed {
+                from, similarity, ..
+            } => out.push_str(&format!(
+                "_Status: Renamed from `{}` ({}% similar)_\n\n",
+                from,
+                (similarity * 100.0).floor()
+            )),
         }
         if !d.diff.is_empty() {
             out.push_str(&d.diff);
@@ -374,6 +414,24 @@ mod tests {
         assert!(out.contains("+ new file"));
     }
 
+    #[test]
+    fn render_renamed_shows_delta() {
+        let diff = diff_renamed_file(
+            "old/a.txt",
+            "new/a.txt",
+            "one\ntwo\nthree\n",
+            "one\ntwo\nfour\n",
+            similarity("one\ntwo\nthree\n", "one\ntwo\nfour\n"),
+            Some(1),
+        );
+        assert_eq!(diff.path, "new/a.txt");
+        let out = render_per_file_diffs(&[diff]);
+        assert!(out.contains("### Diff: `new/a.txt`"));
+        assert!(out.contains("_Status: Renamed from `old/a.txt` (66% similar)_"));
+        assert!(out.contains("- three\n+ four"));
+        assert!(!out.contains("  one"));
+    }
+
     #[test]
     fn test_empty_files() {
         let prev = map(&[("empty.txt", "")]);
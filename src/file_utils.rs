@@ -1,4 +1,7 @@
-use ignore::{DirEntry, WalkBuilder, overrides::OverrideBuilder};
+use crate::git::{self, ChangeFilter, Repository};
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::types::{Types, TypesBuilder};
+use ignore::{DirEntry, WalkBuilder};
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
@@ -11,7 +14,7 @@ use std::path::{Path, PathBuf};
 /// 3 = Documentation, scripts, and everything else
 /// 4 = Generated/lock files (Cargo.lock, package-lock.json, etc.)
 /// 5 = Build/CI infrastructure (.github/, .circleci/, Dockerfile, etc.)
-fn file_relevance_category(path: &Path, base_path: &Path) -> u8 {
+pub(crate) fn file_relevance_category(path: &Path, base_path: &Path) -> u8 {
     let relative = path.strip_prefix(base_path).unwrap_or(path);
     let rel_str = relative.to_string_lossy();
 
@@ -97,10 +100,11 @@ fn file_relevance_category(path: &Path, base_path: &Path) -> u8 {
             if let Some(ext) = relative.extension().and_then(|e| e.to_str()) {
                 match ext {
                     "rs" | "go" | "py" | "ts" | "js" | "java" | "c" | "cpp" | "h" | "hpp"
-                    | "rb" | "swift" | "kt" | "scala" | "ex" | "exs" | "zig" | "hs" => {
+                    | "rb" | "swift" | "kt" | "scala" | "ex" | "exs" | "zig" | "hs" | "cs"
+                    | "php" | "lua" => {
                         // Source file not in a recognized dir — check if it's a test
                         // Use path boundaries to avoid false positives (e.g., "contest.rs")
-                        if rel_str.contains("/test/")
+                        let is_test = rel_str.contains("/test/")
                             || rel_str.contains("/tests/")
                             || rel_str.contains("/spec/")
                             || rel_str.contains("/__tests__/")
@@ -110,12 +114,8 @@ fn file_relevance_category(path: &Path, base_path: &Path) -> u8 {
                             || rel_str.ends_with(".test.ts")
                             || rel_str.ends_with(".test.js")
                             || rel_str.ends_with(".spec.ts")
-                            || rel_str.starts_with("test_")
-                        {
-                            2
-                        } else {
-                            1
-                        }
+                            || rel_str.starts_with("test_");
+                        if is_test { 2 } else { 1 }
                     }
                     "md" | "txt" | "rst" | "adoc" => 3,
                     _ => 1, // Unknown extension in root — treat as source
@@ -150,109 +150,204 @@ fn file_entry_point_priority(path: &Path) -> u8 {
     }
 }
 
-/// Collects all files to be processed using `ignore` crate for efficient traversal.
+/// The filter and ignore rules applied by [`collect_files`].
 ///
-/// `auto_ignores` are runtime-computed exclusion patterns (e.g., the tool's own
-/// output file or cache directory). They are processed identically to user ignores
-/// but kept separate to avoid polluting user-facing configuration.
-pub fn collect_files(
-    base_path: &Path,
-    filters: &[String],
-    ignores: &[String],
-    auto_ignores: &[String],
-) -> io::Result<Vec<DirEntry>> {
-    let mut walker = WalkBuilder::new(base_path);
-    // By default, the "ignore" crate respects .gitignore and hidden files, so we don't need walker.hidden(false)
+/// Also usable on its own to decide whether a path that is not on disk (for
+/// example a file deleted since a git revision) would have been collected.
+pub struct PathSelector {
+    overrides: Override,
+    types: Option<Types>,
+}
 
-    // Build overrides for custom ignore patterns
-    let mut override_builder = OverrideBuilder::new(base_path);
-
-    // Hardcoded auto-ignores for common heavy directories that should NEVER be
-    // included, even when there's no .git directory (so .gitignore isn't read).
-    // Without these, projects missing .git can produce million-line outputs
-    // from dependency trees.
-    //
-    // IMPORTANT: These are added FIRST so that user ignores can override them.
-    // The ignore crate uses "last-match-wins" semantics, so a user can whitelist
-    // a legitimate "vendor" or "build" dir by passing it as a filter pattern.
-    //
-    // IMPORTANT: Patterns must NOT contain a slash — the ignore crate anchors
-    // slash-containing patterns to the root, so `!dir/**` would only match
-    // top-level dirs, missing nested ones like `apps/web/node_modules/`.
-    let default_ignores = [
-        "node_modules",
-        "__pycache__",
-        ".venv",
-        "venv",
-        ".tox",
-        ".mypy_cache",
-        ".pytest_cache",
-        ".ruff_cache",
-        "vendor",  // Go, PHP, Ruby
-        ".bundle", // Ruby
-        "bower_components",
-        ".next",       // Next.js build output
-        ".nuxt",       // Nuxt build output
-        ".svelte-kit", // SvelteKit build output
-        ".angular",    // Angular cache
-        "dist",        // Common build output
-        "build",       // Common build output
-        ".gradle",     // Gradle cache
-        ".cargo",      // Cargo registry cache
-    ];
-    for dir in &default_ignores {
-        // No slash in pattern → matches at any depth (not root-anchored)
-        let pattern = format!("!{}", dir);
-        if let Err(e) = override_builder.add(&pattern) {
-            log::warn!("Skipping invalid default-ignore '{}': {}", dir, e);
+impl PathSelector {
+    pub fn new(
+        base_path: &Path,
+        filters: &[String],
+        ignores: &[String],
+        auto_ignores: &[String],
+    ) -> io::Result<Self> {
+        // Build overrides for custom ignore patterns
+        let mut override_builder = OverrideBuilder::new(base_path);
+
+        // Hardcoded auto-ignores for common heavy directories that should NEVER be
+        // included, even when there's no .git directory (so .gitignore isn't read).
+        // Without these, projects missing .git can produce million-line outputs
+        // from dependency trees.
+        //
+        // IMPORTANT: These are added FIRST so that user ignores can override them.
+        // The ignore crate uses "last-match-wins" semantics, so a user can whitelist
+        // a legitimate "vendor" or "build" dir by passing it as a filter pattern.
+        //
+        // IMPORTANT: Patterns must NOT contain a slash — the ignore crate anchors
+        // slash-containing patterns to the root, so `!dir/**` would only match
+        // top-level dirs, missing nested ones like `apps/web/node_modules/`.
+        let default_ignores = [
+            "node_modules",
+            "__pycache__",
+            ".venv",
+            "venv",
+            ".tox",
+            ".mypy_cache",
+            ".pytest_cache",
+            ".ruff_cache",
+            "vendor",  // Go, PHP, Ruby
+            ".bundle", // Ruby
+            "bower_components",
+            ".next",       // Next.js build output
+            ".nuxt",       // Nuxt build output
+            ".svelte-kit", // SvelteKit build output
+            ".angular",    // Angular cache
+            "dist",        // Common build output
+            "build",       // Common build output
+            ".gradle",     // Gradle cache
+            ".cargo",      // Cargo registry cache
+        ];
+        for dir in &default_ignores {
+            // No slash in pattern → matches at any depth (not root-anchored)
+            let pattern = format!("!{}", dir);
+            if let Err(e) = override_builder.add(&pattern) {
+                log::warn!("Skipping invalid default-ignore '{}': {}", dir, e);
+            }
         }
-    }
 
-    // User-specified ignore patterns (added AFTER defaults so they can override)
-    for pattern in ignores {
-        // Attention: Confusing pattern ahead!
-        // Add the pattern to the override builder with ! prefix to ignore matching files.
-        // In OverrideBuilder, patterns without ! are whitelist (include) patterns,
-        // while patterns with ! are ignore patterns.
-        let ignore_pattern = format!("!{}", pattern);
-        if let Err(e) = override_builder.add(&ignore_pattern) {
+        // User-specified ignore patterns (added AFTER defaults so they can override)
+        for pattern in ignores {
+            // Attention: Confusing pattern ahead!
+            // Add the pattern to the override builder with ! prefix to ignore matching files.
+            // In OverrideBuilder, patterns without ! are whitelist (include) patterns,
+            // while patterns with ! are ignore patterns.
+            let ignore_pattern = format!("!{}", pattern);
+            if let Err(e) = override_builder.add(&ignore_pattern) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Invalid ignore pattern '{}': {}", pattern, e),
+                ));
+            }
+        }
+        // Apply auto-computed ignore patterns (output file, cache dir, etc.)
+        for pattern in auto_ignores {
+            let ignore_pattern = format!("!{}", pattern);
+            if let Err(e) = override_builder.add(&ignore_pattern) {
+                log::warn!("Skipping invalid auto-ignore pattern '{}': {}", pattern, e);
+            }
+        }
+        // Also, always ignore the config file itself
+        if let Err(e) = override_builder.add("!context-builder.toml") {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                format!("Invalid ignore pattern '{}': {}", pattern, e),
+                format!("Failed to add config ignore: {}", e),
             ));
         }
+
+        let overrides = override_builder.build().map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Failed to build overrides: {}", e),
+            )
+        })?;
+
+        let types = if filters.is_empty() {
+            None
+        } else {
+            let mut type_builder = TypesBuilder::new();
+            type_builder.add_defaults();
+            for filter in filters {
+                let _ = type_builder.add(filter, &format!("*.{}", filter));
+                type_builder.select(filter);
+            }
+            Some(type_builder.build().unwrap())
+        };
+
+        Ok(Self { overrides, types })
     }
-    // Apply auto-computed ignore patterns (output file, cache dir, etc.)
-    for pattern in auto_ignores {
-        let ignore_pattern = format!("!{}", pattern);
-        if let Err(e) = override_builder.add(&ignore_pattern) {
-            log::warn!("Skipping invalid auto-ignore pattern '{}': {}", pattern, e);
+
+    /// Returns `true` if the file at `relative_path` (relative to the base
+    /// path) passes the filters and ignore patterns. `.gitignore` rules are
+    /// not consulted.
+    pub fn is_selected(&self, relative_path: &Path) -> bool {
+        if let Some(ref types) = self.types
+            && types.matched(relative_path, false).is_ignore()
+        {
+            return false;
         }
+        // Ignore patterns also exclude everything below a matching directory
+        if self.overrides.matched(relative_path, false).is_ignore() {
+            return false;
+        }
+        relative_path
+            .ancestors()
+            .skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .all(|dir| !self.overrides.matched(dir, true).is_ignore())
     }
-    // Also, always ignore the config file itself
-    if let Err(e) = override_builder.add("!context-builder.toml") {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            format!("Failed to add config ignore: {}", e),
-        ));
-    }
+}
 
-    let overrides = override_builder.build().map_err(|e| {
-        io::Error::new(
-            io::ErrorKind::InvalidInput,
-            format!("Failed to build overrides: {}", e),
-        )
-    })?;
-    walker.overrides(overrides);
-
-    if !filters.is_empty() {
-        let mut type_builder = ignore::types::TypesBuilder::new();
-        type_builder.add_defaults();
-        for filter in filters {
-            let _ = type_builder.add(filter, &format!("*.{}", filter));
-            type_builder.select(filter);
+/// Glob patterns selecting the files `--focus` shows in full.
+///
+/// Patterns use gitignore syntax relative to the base path; a pattern naming
+/// a directory focuses everything below it.
+#[derive(Debug, Clone)]
+pub struct FocusSet {
+    base_path: PathBuf,
+    overrides: Override,
+}
+
+impl FocusSet {
+    pub fn new(base_path: &Path, patterns: &[String]) -> io::Result<Self> {
+        let mut override_builder = OverrideBuilder::new(base_path);
+        for pattern in patterns {
+            if let Err(e) = override_builder.add(pattern) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Invalid focus pattern '{}': {}", pattern, e),
+                ));
+            }
+        }
+        let overrides = override_builder.build().map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Failed to build focus patterns: {}", e),
+            )
+        })?;
+
+        Ok(Self {
+            base_path: base_path.to_path_buf(),
+            overrides,
+        })
+    }
+
+    /// Returns `true` if `path` (absolute, or relative to the base path) or
+    /// one of its parent directories matches a focus pattern.
+    pub fn contains(&self, path: &Path) -> bool {
+        let relative_path = path.strip_prefix(&self.base_path).unwrap_or(path);
+        if self.overrides.matched(relative_path, false).is_whitelist() {
+            return true;
         }
-        let types = type_builder.build().unwrap();
+        relative_path
+            .ancestors()
+            .skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .any(|dir| self.overrides.matched(dir, true).is_whitelist())
+    }
+}
+
+/// Collects all files to be processed using `ignore` crate for efficient traversal.
+///
+/// `auto_ignores` are runtime-computed exclusion patterns (e.g., the tool's own
+/// output file or cache directory). They are processed identically to user ignores
+/// but kept separate to avoid polluting user-facing configuration.
+pub fn collect_files(
+    base_path: &Path,
+    filters: &[String],
+    ignores: &[String],
+    auto_ignores: &[String],
+) -> io::Result<Vec<DirEntry>> {
+    let selector = PathSelector::new(base_path, filters, ignores, auto_ignores)?;
+
+    let mut walker = WalkBuilder::new(base_path);
+    // By default, the "ignore" crate respects .gitignore and hidden files, so we don't need walker.hidden(false)
+    walker.overrides(selector.overrides);
+    if let Some(types) = selector.types {
         walker.types(types);
     }
 
@@ -280,14 +375,43 @@ pub fn collect_files(
     Ok(files)
 }
 
+/// Restricts `files` to those changed in git according to `filter`.
+///
+/// Project manifests and key docs (relevance category 0) are always kept so
+/// the reviewer still sees what the project is. Relative order is preserved.
+pub fn retain_git_changes(
+    files: &mut Vec<DirEntry>,
+    base_path: &Path,
+    filter: &ChangeFilter,
+) -> io::Result<()> {
+    let repo = Repository::discover(base_path)?;
+    let changed = git::changed_paths(&repo, filter)?;
+
+    // Changed paths are relative to the work tree root, which may be an
+    // ancestor of base_path
+    let canonical_base = fs::canonicalize(base_path)?;
+    files.retain(|entry| {
+        if file_relevance_category(entry.path(), base_path) == 0 {
+            return true;
+        }
+        let relative = entry.path().strip_prefix(base_path).unwrap_or(entry.path());
+        canonical_base
+            .join(relative)
+            .strip_prefix(repo.work_dir())
+            .is_ok_and(|path| changed.contains(path))
+    });
+
+    Ok(())
+}
+
 /// Asks for user confirmation if the number of files is large.
 pub fn confirm_processing(file_count: usize) -> io::Result<bool> {
     if file_count > 100 {
-        print!(
+        eprint!(
             "Warning: You're about to process {} files. This might take a while. Continue? [y/N] ",
             file_count
         );
-        io::stdout().flush()?;
+        io::stderr().flush()?;
         let mut input = String::new();
         io::stdin().read_line(&mut input)?;
         if !input.trim().eq_ignore_ascii_case("y") {
@@ -299,8 +423,8 @@ pub fn confirm_processing(file_count: usize) -> io::Result<bool> {
 
 /// Asks for user confirmation to overwrite an existing file.
 pub fn confirm_overwrite(file_path: &str) -> io::Result<bool> {
-    print!("The file '{}' already exists. Overwrite? [y/N] ", file_path);
-    io::stdout().flush()?;
+    eprint!("The file '{}' already exists. Overwrite? [y/N] ", file_path);
+    io::stderr().flush()?;
     let mut input = String::new();
     io::stdin().read_line(&mut input)?;
 
@@ -838,4 +962,39 @@ mod tests {
         let files = collect_files(base, &[], &[], &[]).unwrap();
         assert!(!files.is_empty());
     }
+
+    #[test]
+    fn test_path_selector_matches_collect_rules() {
+        let dir = tempdir().unwrap();
+        let selector = PathSelector::new(
+            dir.path(),
+            &["rs".to_string()],
+            &["generated".to_string()],
+            &[],
+        )
+        .unwrap();
+
+        assert!(selector.is_selected(Path::new("src/lib.rs")));
+        assert!(!selector.is_selected(Path::new("src/notes.txt")));
+        assert!(!selector.is_selected(Path::new("src/generated/api.rs")));
+        assert!(!selector.is_selected(Path::new("web/node_modules/dep/index.rs")));
+    }
+
+    #[test]
+    fn test_focus_set_matches_globs_and_directories() {
+        let dir = tempdir().unwrap();
+        let focus = FocusSet::new(
+            dir.path(),
+            &["src/parser".to_string(), "*.toml".to_string()],
+        )
+        .unwrap();
+
+        assert!(focus.contains(Path::new("src/parser/mod.rs")));
+        assert!(focus.contains(Path::new("src/parser/expr/binary.rs")));
+        assert!(focus.contains(&dir.path().join("src/parser/mod.rs")));
+        assert!(focus.contains(Path::new("Cargo.toml")));
+        assert!(!focus.contains(Path::new("src/lexer.rs")));
+
+        assert!(FocusSet::new(dir.path(), &["src/{".to_string()]).is_err());
+    }
 }
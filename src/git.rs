@@ -0,0 +1,1243 @@
+//! Minimal read-only access to a local git repository.
+//!
+//! Reads the `.git` directory directly (HEAD, loose refs, `packed-refs`, loose
+//! and packed objects, and the index) so git-aware file selection works
+//! without a `git` binary or a libgit2 dependency. Only what context-builder
+//! needs is implemented: resolving revisions to commits, finding merge-bases,
+//! listing the blobs of a commit's tree, reading the index, and hashing
+//! working-tree files.
+
+use flate2::read::ZlibDecoder;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// A SHA-1 object id.
+pub type ObjectId = [u8; 20];
+
+/// Git object types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    Commit,
+    Tree,
+    Blob,
+    Tag,
+}
+
+/// A blob entry of a flattened tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeEntry {
+    pub mode: u32,
+    pub id: ObjectId,
+}
+
+/// An entry of the index (staging area).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub mode: u32,
+    pub id: ObjectId,
+    pub size: u32,
+    pub mtime_secs: u32,
+    pub mtime_nanos: u32,
+    /// Set when the path has unresolved merge conflicts (stages 1-3).
+    pub conflicted: bool,
+}
+
+/// Formats an object id as lowercase hex.
+pub fn to_hex(id: &ObjectId) -> String {
+    id.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Parses a full 40-character hex object id.
+pub fn parse_hex(hex: &str) -> Option<ObjectId> {
+    if hex.len() != 40 {
+        return None;
+    }
+    let mut id = [0u8; 20];
+    for (i, byte) in id.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(id)
+}
+
+/// Computes the blob id git would assign to `content`.
+pub fn hash_blob(content: &[u8]) -> ObjectId {
+    let mut hasher = sha1_smol::Sha1::new();
+    hasher.update(format!("blob {}\0", content.len()).as_bytes());
+    hasher.update(content);
+    hasher.digest().bytes()
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// A local repository opened from its `.git` directory.
+#[derive(Debug)]
+pub struct Repository {
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+    work_dir: PathBuf,
+    packs: Vec<PackIndex>,
+}
+
+impl Repository {
+    /// Finds the repository containing `start` by walking up to the first
+    /// directory with a `.git` entry (a directory, or a `gitdir:` file as
+    /// used by worktrees and submodules).
+    pub fn discover(start: &Path) -> io::Result<Self> {
+        let start = fs::canonicalize(start)?;
+        for dir in start.ancestors() {
+            let dot_git = dir.join(".git");
+            if dot_git.is_dir() {
+                return Self::open(dot_git, dir.to_path_buf());
+            }
+            if dot_git.is_file() {
+                let content = fs::read_to_string(&dot_git)?;
+                let target = content
+                    .strip_prefix("gitdir:")
+                    .map(str::trim)
+                    .ok_or_else(|| invalid_data(format!("Malformed {}", dot_git.display())))?;
+                return Self::open(dir.join(target), dir.to_path_buf());
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Not a git repository: {}", start.display()),
+        ))
+    }
+
+    fn open(git_dir: PathBuf, work_dir: PathBuf) -> io::Result<Self> {
+        // Linked worktrees keep refs and objects in a shared "common" directory
+        let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(rel) => git_dir.join(rel.trim()),
+            Err(_) => git_dir.clone(),
+        };
+
+        let mut packs = Vec::new();
+        if let Ok(entries) = fs::read_dir(common_dir.join("objects/pack")) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "idx") {
+                    packs.push(PackIndex::load(&path)?);
+                }
+            }
+        }
+
+        Ok(Self {
+            git_dir,
+            common_dir,
+            work_dir,
+            packs,
+        })
+    }
+
+    /// Root of the working tree.
+    pub fn work_dir(&self) -> &Path {
+        &self.work_dir
+    }
+
+    /// Resolves a revision such as `HEAD`, `main`, `origin/main`, `v1.0`,
+    /// an (abbreviated) commit id, optionally followed by `~N` / `^N`
+    /// suffixes, to a commit id.
+    pub fn resolve_revision(&self, spec: &str) -> io::Result<ObjectId> {
+        let split = spec.find(['~', '^']).unwrap_or(spec.len());
+        let (base, mut suffix) = spec.split_at(split);
+        let base = if base.is_empty() || base == "@" {
+            "HEAD"
+        } else {
+            base
+        };
+
+        let mut id = self
+            .resolve_name(base)?
+            .ok_or_else(|| invalid_data(format!("Unknown revision '{}'", spec)))?;
+        id = self.peel_to_commit(id)?;
+
+        while let Some(op) = suffix.chars().next() {
+            suffix = &suffix[1..];
+            let digits = suffix
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(suffix.len());
+            let count = if digits == 0 {
+                None
+            } else {
+                Some(
+                    suffix[..digits]
+                        .parse::<usize>()
+                        .map_err(|_| invalid_data(format!("Invalid revision '{}'", spec)))?,
+                )
+            };
+            suffix = &suffix[digits..];
+
+            match op {
+                '~' => {
+                    for _ in 0..count.unwrap_or(1) {
+                        id = self.nth_parent(&id, 1, spec)?;
+                    }
+                }
+                '^' => {
+                    let n = count.unwrap_or(1);
+                    if n > 0 {
+                        id = self.nth_parent(&id, n, spec)?;
+                    }
+                }
+                _ => return Err(invalid_data(format!("Invalid revision '{}'", spec))),
+            }
+        }
+
+        Ok(id)
+    }
+
+    fn nth_parent(&self, commit: &ObjectId, n: usize, spec: &str) -> io::Result<ObjectId> {
+        let (_, parents) = self.read_commit(commit)?;
+        parents
+            .get(n - 1)
+            .copied()
+            .ok_or_else(|| invalid_data(format!("Revision '{}' has no such parent", spec)))
+    }
+
+    fn resolve_name(&self, name: &str) -> io::Result<Option<ObjectId>> {
+        if let Some(id) = parse_hex(name) {
+            return Ok(Some(id));
+        }
+
+        // Same lookup order as `git rev-parse`
+        let candidates = [
+            name.to_string(),
+            format!("refs/{}", name),
+            format!("refs/tags/{}", name),
+            format!("refs/heads/{}", name),
+            format!("refs/remotes/{}", name),
+            format!("refs/remotes/{}/HEAD", name),
+        ];
+        for candidate in &candidates {
+            if let Some(id) = self.resolve_ref(candidate, 0)? {
+                return Ok(Some(id));
+            }
+        }
+
+        if name.len() >= 4 && name.len() < 40 && name.chars().all(|c| c.is_ascii_hexdigit()) {
+            return self.resolve_abbreviated(&name.to_ascii_lowercase());
+        }
+
+        Ok(None)
+    }
+
+    fn resolve_ref(&self, name: &str, depth: usize) -> io::Result<Option<ObjectId>> {
+        if depth > 8 {
+            return Err(invalid_data(format!("Symbolic ref loop at '{}'", name)));
+        }
+
+        // Per-worktree refs (HEAD) live in git_dir, shared refs in common_dir
+        for dir in [&self.git_dir, &self.common_dir] {
+            let path = dir.join(name);
+            if path.is_file() {
+                let content = fs::read_to_string(&path)?;
+                let content = content.trim();
+                if let Some(target) = content.strip_prefix("ref:") {
+                    return self.resolve_ref(target.trim(), depth + 1);
+                }
+                return Ok(parse_hex(content));
+            }
+        }
+
+        if let Ok(packed) = fs::read_to_string(self.common_dir.join("packed-refs")) {
+            for line in packed.lines() {
+                if line.starts_with('#') || line.starts_with('^') {
+                    continue;
+                }
+                if let Some((hex, ref_name)) = line.split_once(' ')
+                    && ref_name == name
+                {
+                    return Ok(parse_hex(hex));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn resolve_abbreviated(&self, prefix: &str) -> io::Result<Option<ObjectId>> {
+        let mut matches: HashSet<ObjectId> = HashSet::new();
+
+        let (dir, rest) = prefix.split_at(2);
+        if let Ok(entries) = fs::read_dir(self.common_dir.join("objects").join(dir)) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with(rest)
+                    && let Some(id) = parse_hex(&format!("{}{}", dir, name))
+                {
+                    matches.insert(id);
+                }
+            }
+        }
+        for pack in &self.packs {
+            matches.extend(pack.ids_with_prefix(prefix));
+        }
+
+        match matches.len() {
+            0 => Ok(None),
+            1 => Ok(matches.into_iter().next()),
+            _ => Err(invalid_data(format!(
+                "Abbreviated revision '{}' is ambiguous",
+                prefix
+            ))),
+        }
+    }
+
+    fn peel_to_commit(&self, mut id: ObjectId) -> io::Result<ObjectId> {
+        loop {
+            let (kind, data) = self.read_object(&id)?;
+            match kind {
+                ObjectKind::Commit => return Ok(id),
+                ObjectKind::Tag => {
+                    let target = header_field(&data, "object")
+                        .and_then(parse_hex)
+                        .ok_or_else(|| invalid_data("Malformed tag object"))?;
+                    id = target;
+                }
+                _ => {
+                    return Err(invalid_data(format!(
+                        "Object {} is not a commit",
+                        to_hex(&id)
+                    )));
+                }
+            }
+        }
+    }
+
+    /// Reads a commit and returns its tree id and parent ids.
+    pub fn read_commit(&self, id: &ObjectId) -> io::Result<(ObjectId, Vec<ObjectId>)> {
+        let (kind, data) = self.read_object(id)?;
+        if kind != ObjectKind::Commit {
+            return Err(invalid_data(format!(
+                "Object {} is not a commit",
+                to_hex(id)
+            )));
+        }
+        let text = String::from_utf8_lossy(&data);
+        let mut tree = None;
+        let mut parents = Vec::new();
+        for line in text.lines() {
+            if line.is_empty() {
+                break;
+            }
+            if let Some(hex) = line.strip_prefix("tree ") {
+                tree = parse_hex(hex);
+            } else if let Some(hex) = line.strip_prefix("parent ")
+                && let Some(parent) = parse_hex(hex)
+            {
+                parents.push(parent);
+            }
+        }
+        let tree = tree.ok_or_else(|| invalid_data("Commit without a tree"))?;
+        Ok((tree, parents))
+    }
+
+    /// Finds the best common ancestor of two commits (like `git merge-base`),
+    /// or `None` if their histories are unrelated. When there are several
+    /// (criss-cross merges), the first one found is returned.
+    pub fn merge_base(&self, a: &ObjectId, b: &ObjectId) -> io::Result<Option<ObjectId>> {
+        const FROM_A: u8 = 1;
+        const FROM_B: u8 = 2;
+        const STALE: u8 = 4;
+
+        if a == b {
+            return Ok(Some(*a));
+        }
+
+        // Paint ancestors of `a` and `b`; a commit reached from both is a
+        // candidate, and its own ancestors become stale (worse candidates)
+        let mut flags: HashMap<ObjectId, u8> = HashMap::from([(*a, FROM_A), (*b, FROM_B)]);
+        let mut queue = VecDeque::from([*a, *b]);
+        let mut candidates = Vec::new();
+        while queue.iter().any(|id| flags[id] & STALE == 0) {
+            let Some(id) = queue.pop_front() else {
+                break;
+            };
+            let mut paint = flags[&id];
+            if paint & (FROM_A | FROM_B) == FROM_A | FROM_B && paint & STALE == 0 {
+                if !candidates.contains(&id) {
+                    candidates.push(id);
+                }
+                paint |= STALE;
+            }
+            let (_, parents) = self.read_commit(&id)?;
+            for parent in parents {
+                let existing = flags.entry(parent).or_insert(0);
+                if *existing & paint != paint {
+                    *existing |= paint;
+                    queue.push_back(parent);
+                }
+            }
+        }
+
+        candidates.retain(|id| flags[id] & STALE == 0);
+        // Painting stops once only stale commits are left, so a candidate may
+        // still be an ancestor of another one
+        if candidates.len() > 1 {
+            let mut best = Vec::new();
+            for id in &candidates {
+                let mut redundant = false;
+                for other in candidates.iter().filter(|other| *other != id) {
+                    if self.is_ancestor(id, other)? {
+                        redundant = true;
+                        break;
+                    }
+                }
+                if !redundant {
+                    best.push(*id);
+                }
+            }
+            candidates = best;
+        }
+        Ok(candidates.first().copied())
+    }
+
+    /// Returns `true` if `ancestor` is reachable from `commit` through parents.
+    fn is_ancestor(&self, ancestor: &ObjectId, commit: &ObjectId) -> io::Result<bool> {
+        let mut seen = HashSet::from([*commit]);
+        let mut queue = VecDeque::from([*commit]);
+        while let Some(id) = queue.pop_front() {
+            if id == *ancestor {
+                return Ok(true);
+            }
+            for parent in self.read_commit(&id)?.1 {
+                if seen.insert(parent) {
+                    queue.push_back(parent);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Resolves `spec` and returns its merge-base with `HEAD`: the commit
+    /// `git diff <spec>...HEAD` compares against, so changes made only on
+    /// `spec` after the current branch forked from it are left out.
+    pub fn fork_point(&self, spec: &str) -> io::Result<ObjectId> {
+        let revision = self.resolve_revision(spec)?;
+        let head = self.resolve_revision("HEAD")?;
+        self.merge_base(&head, &revision)?
+            .ok_or_else(|| invalid_data(format!("'{}' has no common history with HEAD", spec)))
+    }
+
+    /// Lists every blob reachable from the tree of `commit`, keyed by its
+    /// path relative to the working tree root. Submodules are skipped.
+    pub fn commit_blobs(&self, commit: &ObjectId) -> io::Result<BTreeMap<PathBuf, TreeEntry>> {
+        let (tree, _) = self.read_commit(commit)?;
+        let mut blobs = BTreeMap::new();
+        self.collect_tree(&tree, Path::new(""), &mut blobs)?;
+        Ok(blobs)
+    }
+
+    /// Blobs of the commit `HEAD` points to, or an empty map on an unborn branch.
+    pub fn head_blobs(&self) -> io::Result<BTreeMap<PathBuf, TreeEntry>> {
+        match self.resolve_name("HEAD")? {
+            Some(id) => self.commit_blobs(&id),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    fn collect_tree(
+        &self,
+        tree: &ObjectId,
+        prefix: &Path,
+        out: &mut BTreeMap<PathBuf, TreeEntry>,
+    ) -> io::Result<()> {
+        let (kind, data) = self.read_object(tree)?;
+        if kind != ObjectKind::Tree {
+            return Err(invalid_data(format!(
+                "Object {} is not a tree",
+                to_hex(tree)
+            )));
+        }
+
+        let mut pos = 0;
+        while pos < data.len() {
+            let space = find_byte(&data[pos..], b' ')
+                .ok_or_else(|| invalid_data("Malformed tree entry"))?;
+            let mode = u32::from_str_radix(&String::from_utf8_lossy(&data[pos..pos + space]), 8)
+                .map_err(|_| invalid_data("Malformed tree entry mode"))?;
+            pos += space + 1;
+            let nul = find_byte(&data[pos..], 0)
+                .ok_or_else(|| invalid_data("Malformed tree entry name"))?;
+            let name = String::from_utf8_lossy(&data[pos..pos + nul]).to_string();
+            pos += nul + 1;
+            let id: ObjectId = data
+                .get(pos..pos + 20)
+                .and_then(|b| b.try_into().ok())
+                .ok_or_else(|| invalid_data("Truncated tree entry"))?;
+            pos += 20;
+
+            let path = prefix.join(&name);
+            match mode & 0o170000 {
+                0o040000 => self.collect_tree(&id, &path, out)?,
+                0o160000 => {} // submodule commit
+                _ => {
+                    out.insert(path, TreeEntry { mode, id });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a blob's content.
+    pub fn read_blob(&self, id: &ObjectId) -> io::Result<Vec<u8>> {
+        let (kind, data) = self.read_object(id)?;
+        if kind != ObjectKind::Blob {
+            return Err(invalid_data(format!("Object {} is not a blob", to_hex(id))));
+        }
+        Ok(data)
+    }
+
+    /// Reads an object from the loose object store or a pack.
+    pub fn read_object(&self, id: &ObjectId) -> io::Result<(ObjectKind, Vec<u8>)> {
+        let hex = to_hex(id);
+        let loose = self
+            .common_dir
+            .join("objects")
+            .join(&hex[..2])
+            .join(&hex[2..]);
+        if loose.is_file() {
+            let mut data = Vec::new();
+            ZlibDecoder::new(fs::File::open(&loose)?).read_to_end(&mut data)?;
+            let nul = find_byte(&data, 0).ok_or_else(|| invalid_data("Malformed object"))?;
+            let header = String::from_utf8_lossy(&data[..nul]).to_string();
+            let kind = match header.split(' ').next() {
+                Some("commit") => ObjectKind::Commit,
+                Some("tree") => ObjectKind::Tree,
+                Some("blob") => ObjectKind::Blob,
+                Some("tag") => ObjectKind::Tag,
+                _ => return Err(invalid_data(format!("Unknown object type in {}", hex))),
+            };
+            data.drain(..=nul);
+            return Ok((kind, data));
+        }
+
+        for pack in &self.packs {
+            if let Some(offset) = pack.find(id) {
+                return self.read_packed(pack, offset, 0);
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Object {} not found", hex),
+        ))
+    }
+
+    fn read_packed(
+        &self,
+        pack: &PackIndex,
+        offset: u64,
+        depth: usize,
+    ) -> io::Result<(ObjectKind, Vec<u8>)> {
+        if depth > 64 {
+            return Err(invalid_data("Delta chain too deep"));
+        }
+
+        let mut reader = BufReader::new(fs::File::open(&pack.pack_path)?);
+        reader.seek(SeekFrom::Start(offset))?;
+
+        let mut byte = read_u8(&mut reader)?;
+        let type_id = (byte >> 4) & 0x7;
+        let mut size = (byte & 0x0f) as usize;
+        let mut shift = 4;
+        while byte & 0x80 != 0 {
+            byte = read_u8(&mut reader)?;
+            size |= ((byte & 0x7f) as usize) << shift;
+            shift += 7;
+        }
+
+        let inflate = |reader: &mut BufReader<fs::File>| -> io::Result<Vec<u8>> {
+            let mut data = Vec::with_capacity(size);
+            ZlibDecoder::new(reader).read_to_end(&mut data)?;
+            Ok(data)
+        };
+
+        match type_id {
+            1 => Ok((ObjectKind::Commit, inflate(&mut reader)?)),
+            2 => Ok((ObjectKind::Tree, inflate(&mut reader)?)),
+            3 => Ok((ObjectKind::Blob, inflate(&mut reader)?)),
+            4 => Ok((ObjectKind::Tag, inflate(&mut reader)?)),
+            6 => {
+                // OFS_DELTA: base is at a relative offset in the same pack
+                let mut byte = read_u8(&mut reader)?;
+                let mut distance = (byte & 0x7f) as u64;
+                while byte & 0x80 != 0 {
+                    byte = read_u8(&mut reader)?;
+                    distance = ((distance + 1) << 7) | (byte & 0x7f) as u64;
+                }
+                let delta = inflate(&mut reader)?;
+                let base_offset = offset
+                    .checked_sub(distance)
+                    .ok_or_else(|| invalid_data("Invalid delta base offset"))?;
+                let (kind, base) = self.read_packed(pack, base_offset, depth + 1)?;
+                Ok((kind, apply_delta(&base, &delta)?))
+            }
+            7 => {
+                // REF_DELTA: base is named by id, possibly in another pack
+                let mut base_id = [0u8; 20];
+                reader.read_exact(&mut base_id)?;
+                let delta = inflate(&mut reader)?;
+                let (kind, base) = self.read_object(&base_id)?;
+                Ok((kind, apply_delta(&base, &delta)?))
+            }
+            _ => Err(invalid_data(format!(
+                "Unknown pack object type {}",
+                type_id
+            ))),
+        }
+    }
+
+    /// Reads the index (`.git/index`), keyed by path relative to the working
+    /// tree root. Returns an empty map when there is no index yet.
+    pub fn read_index(&self) -> io::Result<BTreeMap<PathBuf, IndexEntry>> {
+        let data = match fs::read(self.git_dir.join("index")) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(e),
+        };
+        parse_index(&data)
+    }
+
+    /// Object id of a working-tree file, reusing the index entry when the
+    /// file's size and mtime still match it (as git itself does).
+    pub fn worktree_blob_id(
+        &self,
+        rel_path: &Path,
+        index_entry: Option<&IndexEntry>,
+    ) -> io::Result<ObjectId> {
+        let path = self.work_dir.join(rel_path);
+        let metadata = fs::symlink_metadata(&path)?;
+
+        if let Some(entry) = index_entry
+            && !entry.conflicted
+            && entry.size as u64 == metadata.len()
+            && let Ok(modified) = metadata.modified()
+            && let Ok(since_epoch) = modified.duration_since(std::time::UNIX_EPOCH)
+            && since_epoch.as_secs() as u32 == entry.mtime_secs
+            && since_epoch.subsec_nanos() == entry.mtime_nanos
+        {
+            return Ok(entry.id);
+        }
+
+        if metadata.file_type().is_symlink() {
+            let target = fs::read_link(&path)?;
+            return Ok(hash_blob(target.to_string_lossy().as_bytes()));
+        }
+        Ok(hash_blob(&fs::read(&path)?))
+    }
+}
+
+/// Which changes restrict the selection; combined as a union.
+#[derive(Debug, Clone, Default)]
+pub struct ChangeFilter {
+    /// Working tree compared to the merge-base of `HEAD` and this revision
+    /// (the files touched by the current branch, like `git diff <rev>...`).
+    pub since: Option<String>,
+    /// Index compared to `HEAD` (like `git diff --staged`).
+    pub staged: bool,
+    /// Working tree compared to the index (like `git diff`).
+    pub unstaged: bool,
+}
+
+impl ChangeFilter {
+    /// Returns `true` if any git-based restriction is requested.
+    pub fn is_active(&self) -> bool {
+        self.since.is_some() || self.staged || self.unstaged
+    }
+}
+
+/// Computes the tracked paths (relative to the working tree root) that
+/// changed according to `filter`. Untracked files are not included, matching
+/// `git diff`.
+pub fn changed_paths(repo: &Repository, filter: &ChangeFilter) -> io::Result<HashSet<PathBuf>> {
+    let index = repo.read_index()?;
+    let mut changed = HashSet::new();
+
+    if let Some(ref spec) = filter.since {
+        let commit = repo.fork_point(spec)?;
+        let blobs = repo.commit_blobs(&commit)?;
+        let candidates: HashSet<&PathBuf> = blobs.keys().chain(index.keys()).collect();
+        for path in candidates {
+            if !repo.work_dir().join(path).exists() {
+                continue;
+            }
+            let current = repo.worktree_blob_id(path, index.get(path))?;
+            if blobs.get(path).map(|entry| entry.id) != Some(current) {
+                changed.insert(path.clone());
+            }
+        }
+    }
+
+    if filter.staged {
+        let head = repo.head_blobs()?;
+        for (path, entry) in &index {
+            if entry.conflicted || head.get(path).map(|e| e.id) != Some(entry.id) {
+                changed.insert(path.clone());
+            }
+        }
+    }
+
+    if filter.unstaged {
+        for (path, entry) in &index {
+            if !repo.work_dir().join(path).exists() {
+                continue;
+            }
+            if entry.conflicted || repo.worktree_blob_id(path, Some(entry))? != entry.id {
+                changed.insert(path.clone());
+            }
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Parses index versions 2, 3 and 4.
+fn parse_index(data: &[u8]) -> io::Result<BTreeMap<PathBuf, IndexEntry>> {
+    if data.len() < 12 || &data[..4] != b"DIRC" {
+        return Err(invalid_data("Malformed git index"));
+    }
+    let version = be_u32(data, 4)?;
+    if !(2..=4).contains(&version) {
+        return Err(invalid_data(format!(
+            "Unsupported git index version {}",
+            version
+        )));
+    }
+    let count = be_u32(data, 8)? as usize;
+
+    let mut entries = BTreeMap::new();
+    let mut pos = 12;
+    let mut previous_path: Vec<u8> = Vec::new();
+
+    for _ in 0..count {
+        let start = pos;
+        let mtime_secs = be_u32(data, pos + 8)?;
+        let mtime_nanos = be_u32(data, pos + 12)?;
+        let mode = be_u32(data, pos + 24)?;
+        let size = be_u32(data, pos + 36)?;
+        let id: ObjectId = data
+            .get(pos + 40..pos + 60)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| invalid_data("Truncated git index"))?;
+        let flags = be_u16(data, pos + 60)?;
+        pos += 62;
+        if version >= 3 && flags & 0x4000 != 0 {
+            pos += 2; // extended flags
+        }
+
+        let path = if version == 4 {
+            // Path is the previous path minus N trailing bytes, plus a suffix
+            let (strip, used) = read_offset_varint(&data[pos..])?;
+            pos += used;
+            let nul = find_byte(&data[pos..], 0).ok_or_else(|| invalid_data("Truncated path"))?;
+            let keep = previous_path
+                .len()
+                .checked_sub(strip as usize)
+                .ok_or_else(|| invalid_data("Malformed index path"))?;
+            let mut path = previous_path[..keep].to_vec();
+            path.extend_from_slice(&data[pos..pos + nul]);
+            pos += nul + 1;
+            path
+        } else {
+            let nul = find_byte(&data[pos..], 0).ok_or_else(|| invalid_data("Truncated path"))?;
+            let path = data[pos..pos + nul].to_vec();
+            // Entries are NUL-padded to a multiple of 8 bytes
+            pos = start + ((pos + nul - start + 8) & !7);
+            path
+        };
+
+        let stage = (flags >> 12) & 0x3;
+        let key = PathBuf::from(String::from_utf8_lossy(&path).to_string());
+        if stage == 0 {
+            entries.insert(
+                key,
+                IndexEntry {
+                    mode,
+                    id,
+                    size,
+                    mtime_secs,
+                    mtime_nanos,
+                    conflicted: false,
+                },
+            );
+        } else {
+            entries
+                .entry(key)
+                .or_insert(IndexEntry {
+                    mode,
+                    id,
+                    size,
+                    mtime_secs,
+                    mtime_nanos,
+                    conflicted: true,
+                })
+                .conflicted = true;
+        }
+        previous_path = path;
+    }
+
+    Ok(entries)
+}
+
+/// Applies a git delta to `base`.
+fn apply_delta(base: &[u8], delta: &[u8]) -> io::Result<Vec<u8>> {
+    let mut pos = 0;
+    let (base_size, used) = read_size_varint(delta)?;
+    pos += used;
+    let (result_size, used) = read_size_varint(&delta[pos..])?;
+    pos += used;
+    if base_size != base.len() {
+        return Err(invalid_data("Delta base size mismatch"));
+    }
+
+    let mut out = Vec::with_capacity(result_size);
+    while pos < delta.len() {
+        let op = delta[pos];
+        pos += 1;
+        if op & 0x80 != 0 {
+            let mut offset = 0usize;
+            let mut size = 0usize;
+            for i in 0..4 {
+                if op & (1 << i) != 0 {
+                    offset |= (*delta
+                        .get(pos)
+                        .ok_or_else(|| invalid_data("Truncated delta"))?
+                        as usize)
+                        << (8 * i);
+                    pos += 1;
+                }
+            }
+            for i in 0..3 {
+                if op & (0x10 << i) != 0 {
+                    size |= (*delta
+                        .get(pos)
+                        .ok_or_else(|| invalid_data("Truncated delta"))?
+                        as usize)
+                        << (8 * i);
+                    pos += 1;
+                }
+            }
+            if size == 0 {
+                size = 0x10000;
+            }
+            let chunk = base
+                .get(offset..offset + size)
+                .ok_or_else(|| invalid_data("Delta copy out of range"))?;
+            out.extend_from_slice(chunk);
+        } else if op != 0 {
+            let chunk = delta
+                .get(pos..pos + op as usize)
+                .ok_or_else(|| invalid_data("Truncated delta"))?;
+            out.extend_from_slice(chunk);
+            pos += op as usize;
+        } else {
+            return Err(invalid_data("Invalid delta opcode"));
+        }
+    }
+
+    if out.len() != result_size {
+        return Err(invalid_data("Delta result size mismatch"));
+    }
+    Ok(out)
+}
+
+/// The `.idx` file of a pack, loaded into memory.
+#[derive(Debug)]
+struct PackIndex {
+    pack_path: PathBuf,
+    ids: Vec<ObjectId>,
+    offsets: Vec<u64>,
+}
+
+impl PackIndex {
+    fn load(idx_path: &Path) -> io::Result<Self> {
+        let data = fs::read(idx_path)?;
+        let pack_path = idx_path.with_extension("pack");
+
+        let (ids, offsets) = if data.len() >= 8 && data[..4] == [0xff, b't', b'O', b'c'] {
+            // Version 2: fanout, ids, crc32s, 32-bit offsets, 64-bit offsets
+            if be_u32(&data, 4)? != 2 {
+                return Err(invalid_data("Unsupported pack index version"));
+            }
+            let count = be_u32(&data, 8 + 255 * 4)? as usize;
+            let ids_start = 8 + 256 * 4;
+            let offsets_start = ids_start + count * 20 + count * 4;
+            let large_start = offsets_start + count * 4;
+            let mut ids = Vec::with_capacity(count);
+            let mut offsets = Vec::with_capacity(count);
+            for i in 0..count {
+                let id: ObjectId = data
+                    .get(ids_start + i * 20..ids_start + i * 20 + 20)
+                    .and_then(|b| b.try_into().ok())
+                    .ok_or_else(|| invalid_data("Truncated pack index"))?;
+                ids.push(id);
+                let offset = be_u32(&data, offsets_start + i * 4)?;
+                let offset = if offset & 0x8000_0000 != 0 {
+                    let at = large_start + (offset & 0x7fff_ffff) as usize * 8;
+                    ((be_u32(&data, at)? as u64) << 32) | be_u32(&data, at + 4)? as u64
+                } else {
+                    offset as u64
+                };
+                offsets.push(offset);
+            }
+            (ids, offsets)
+        } else {
+            // Version 1: fanout, then (offset, id) pairs
+            let count = be_u32(&data, 255 * 4)? as usize;
+            let mut ids = Vec::with_capacity(count);
+            let mut offsets = Vec::with_capacity(count);
+            for i in 0..count {
+                let at = 256 * 4 + i * 24;
+                offsets.push(be_u32(&data, at)? as u64);
+                let id: ObjectId = data
+                    .get(at + 4..at + 24)
+                    .and_then(|b| b.try_into().ok())
+                    .ok_or_else(|| invalid_data("Truncated pack index"))?;
+                ids.push(id);
+            }
+            (ids, offsets)
+        };
+
+        Ok(Self {
+            pack_path,
+            ids,
+            offsets,
+        })
+    }
+
+    fn find(&self, id: &ObjectId) -> Option<u64> {
+        self.ids
+            .binary_search(id)
+            .ok()
+            .map(|index| self.offsets[index])
+    }
+
+    fn ids_with_prefix(&self, prefix: &str) -> Vec<ObjectId> {
+        self.ids
+            .iter()
+            .filter(|id| to_hex(id).starts_with(prefix))
+            .copied()
+            .collect()
+    }
+}
+
+fn header_field<'a>(data: &'a [u8], name: &str) -> Option<&'a str> {
+    let text = std::str::from_utf8(data).ok()?;
+    text.lines()
+        .take_while(|line| !line.is_empty())
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+}
+
+fn find_byte(data: &[u8], byte: u8) -> Option<usize> {
+    data.iter().position(|&b| b == byte)
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn be_u32(data: &[u8], at: usize) -> io::Result<u32> {
+    data.get(at..at + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| invalid_data("Unexpected end of data"))
+}
+
+fn be_u16(data: &[u8], at: usize) -> io::Result<u16> {
+    data.get(at..at + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| invalid_data("Unexpected end of data"))
+}
+
+/// Little-endian base-128 size, as used in delta headers.
+fn read_size_varint(data: &[u8]) -> io::Result<(usize, usize)> {
+    let mut value = 0usize;
+    let mut shift = 0;
+    for (i, &byte) in data.iter().enumerate() {
+        value |= ((byte & 0x7f) as usize) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(invalid_data("Truncated varint"))
+}
+
+/// Big-endian "offset" varint, as used by index v4 path compression.
+fn read_offset_varint(data: &[u8]) -> io::Result<(u64, usize)> {
+    let mut iter = data.iter().enumerate();
+    let (_, &first) = iter
+        .next()
+        .ok_or_else(|| invalid_data("Truncated varint"))?;
+    let mut value = (first & 0x7f) as u64;
+    let mut byte = first;
+    let mut used = 1;
+    while byte & 0x80 != 0 {
+        let (i, &next) = iter
+            .next()
+            .ok_or_else(|| invalid_data("Truncated varint"))?;
+        byte = next;
+        value = ((value + 1) << 7) | (byte & 0x7f) as u64;
+        used = i + 1;
+    }
+    Ok((value, used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "Test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .output()
+            .expect("git must be installed to run these tests");
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    fn init_repo(dir: &Path) {
+        git(dir, &["init", "-q", "-b", "main"]);
+        git(dir, &["config", "commit.gpgsign", "false"]);
+    }
+
+    fn commit_all(dir: &Path, message: &str) {
+        git(dir, &["add", "-A"]);
+        git(dir, &["commit", "-q", "-m", message]);
+    }
+
+    fn paths(set: &HashSet<PathBuf>) -> Vec<String> {
+        let mut v: Vec<String> = set
+            .iter()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn test_hash_blob_matches_git() {
+        // `printf 'hello\n' | git hash-object --stdin`
+        assert_eq!(
+            to_hex(&hash_blob(b"hello\n")),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+    }
+
+    #[test]
+    fn test_parse_hex_roundtrip() {
+        let hex = "ce013625030ba8dba906f756967f9e9ca394464a";
+        assert_eq!(to_hex(&parse_hex(hex).unwrap()), hex);
+        assert!(parse_hex("xyz").is_none());
+        assert!(parse_hex(&"g".repeat(40)).is_none());
+    }
+
+    #[test]
+    fn test_apply_delta_copy_and_insert() {
+        let base = b"hello world";
+        // base size 11, result size 11: copy "hello " then insert "rust!"
+        let delta = [11, 11, 0x90, 6, 5, b'r', b'u', b's', b't', b'!'];
+        assert_eq!(apply_delta(base, &delta).unwrap(), b"hello rust!");
+    }
+
+    #[test]
+    fn test_discover_outside_repository_fails() {
+        let dir = tempdir().unwrap();
+        // A temp dir is normally not inside a repository; skip otherwise
+        if Repository::discover(dir.path()).is_ok() {
+            return;
+        }
+        let err = Repository::discover(dir.path()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_resolve_revisions_and_read_trees() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        init_repo(root);
+        fs::create_dir(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub fn a() {}\n").unwrap();
+        commit_all(root, "first");
+        fs::write(root.join("src/lib.rs"), "pub fn b() {}\n").unwrap();
+        commit_all(root, "second");
+        git(root, &["tag", "-a", "v1", "-m", "tag"]);
+
+        let repo = Repository::discover(&root.join("src")).unwrap();
+        let head = git(root, &["rev-parse", "HEAD"]);
+        let first = git(root, &["rev-parse", "HEAD~1"]);
+
+        assert_eq!(to_hex(&repo.resolve_revision("HEAD").unwrap()), head);
+        assert_eq!(to_hex(&repo.resolve_revision("main").unwrap()), head);
+        assert_eq!(to_hex(&repo.resolve_revision("v1").unwrap()), head);
+        assert_eq!(to_hex(&repo.resolve_revision("HEAD~1").unwrap()), first);
+        assert_eq!(to_hex(&repo.resolve_revision("main^").unwrap()), first);
+        assert_eq!(to_hex(&repo.resolve_revision(&head[..8]).unwrap()), head);
+        assert!(repo.resolve_revision("no-such-branch").is_err());
+
+        let blobs = repo
+            .commit_blobs(&repo.resolve_revision("HEAD~1").unwrap())
+            .unwrap();
+        let entry = &blobs[Path::new("src/lib.rs")];
+        assert_eq!(repo.read_blob(&entry.id).unwrap(), b"pub fn a() {}\n");
+    }
+
+    #[test]
+    fn test_reads_packed_objects_and_refs() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        init_repo(root);
+        let body: String = (0..200).map(|i| format!("line {}\n", i)).collect();
+        fs::write(root.join("a.txt"), &body).unwrap();
+        commit_all(root, "first");
+        fs::write(root.join("a.txt"), format!("{}changed\n", body)).unwrap();
+        commit_all(root, "second");
+        // Pack everything (producing deltas) and move refs into packed-refs
+        git(root, &["gc", "-q", "--aggressive"]);
+        assert!(!root.join(".git/refs/heads/main").exists());
+
+        let repo = Repository::discover(root).unwrap();
+        let first = repo.resolve_revision("main~1").unwrap();
+        let blobs = repo.commit_blobs(&first).unwrap();
+        let content = repo.read_blob(&blobs[Path::new("a.txt")].id).unwrap();
+        assert_eq!(content, body.as_bytes());
+    }
+
+    #[test]
+    fn test_changed_paths_since_staged_unstaged() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        init_repo(root);
+        fs::write(root.join("kept.txt"), "same\n").unwrap();
+        fs::write(root.join("edited.txt"), "v1\n").unwrap();
+        commit_all(root, "base");
+        git(root, &["checkout", "-q", "-b", "feature"]);
+        fs::write(root.join("edited.txt"), "v2\n").unwrap();
+        fs::write(root.join("added.txt"), "new\n").unwrap();
+        commit_all(root, "feature work");
+        fs::write(root.join("staged.txt"), "staged\n").unwrap();
+        git(root, &["add", "staged.txt"]);
+        fs::write(root.join("kept.txt"), "dirty\n").unwrap();
+        fs::write(root.join("untracked.txt"), "nope\n").unwrap();
+
+        let repo = Repository::discover(root).unwrap();
+
+        let since = changed_paths(
+            &repo,
+            &ChangeFilter {
+                since: Some("main".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            paths(&since),
+            vec!["added.txt", "edited.txt", "kept.txt", "staged.txt"]
+        );
+
+        let staged = changed_paths(
+            &repo,
+            &ChangeFilter {
+                staged: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(paths(&staged), vec!["staged.txt"]);
+
+        let unstaged = changed_paths(
+            &repo,
+            &ChangeFilter {
+                unstaged: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(paths(&unstaged), vec!["kept.txt"]);
+    }
+
+    #[test]
+    fn test_since_ignores_commits_made_only_on_the_base_branch() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        init_repo(root);
+        fs::write(root.join("a.rs"), "a1\n").unwrap();
+        fs::write(root.join("b.txt"), "b1\n").unwrap();
+        commit_all(root, "base");
+        let fork = git(root, &["rev-parse", "HEAD"]);
+        git(root, &["checkout", "-q", "-b", "feature"]);
+        fs::write(root.join("a.rs"), "a2\n").unwrap();
+        commit_all(root, "feature work");
+        git(root, &["checkout", "-q", "main"]);
+        fs::write(root.join("b.txt"), "b2\n").unwrap();
+        commit_all(root, "main moves on");
+        git(root, &["checkout", "-q", "feature"]);
+        // Merging main in does not make its changes part of the branch
+        git(root, &["merge", "-q", "--no-edit", "main"]);
+        fs::write(root.join("c.rs"), "c\n").unwrap();
+        commit_all(root, "more feature work");
+        git(root, &["checkout", "-q", "main"]);
+        fs::write(root.join("b.txt"), "b3\n").unwrap();
+        commit_all(root, "main moves on again");
+        git(root, &["checkout", "-q", "feature"]);
+
+        let repo = Repository::discover(root).unwrap();
+        let main = repo.resolve_revision("main").unwrap();
+        let head = repo.resolve_revision("HEAD").unwrap();
+        let expected = git(root, &["merge-base", "HEAD", "main"]);
+        assert_ne!(expected, fork);
+        assert_eq!(
+            to_hex(&repo.merge_base(&head, &main).unwrap().unwrap()),
+            expected
+        );
+
+        let since = changed_paths(
+            &repo,
+            &ChangeFilter {
+                since: Some("main".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(paths(&since), vec!["a.rs", "c.rs"]);
+    }
+
+    #[test]
+    fn test_parse_index_v4() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        init_repo(root);
+        git(root, &["config", "index.version", "4"]);
+        fs::create_dir(root.join("dir")).unwrap();
+        fs::write(root.join("dir/alpha.txt"), "a").unwrap();
+        fs::write(root.join("dir/alphabet.txt"), "b").unwrap();
+        git(root, &["add", "-A"]);
+
+        let repo = Repository::discover(root).unwrap();
+        let index = repo.read_index().unwrap();
+        let keys: Vec<_> = index
+            .keys()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(keys, vec!["dir/alpha.txt", "dir/alphabet.txt"]);
+        assert_eq!(index[Path::new("dir/alpha.txt")].id, hash_blob(b"a"));
+    }
+}
@@ -0,0 +1,419 @@
+//! Structured JSON output.
+//!
+//! Emits the same information as the Markdown document (header metadata,
+//! content hash, file tree, per-file metadata and content, tree-sitter
+//! enrichment) as a single machine-readable JSON document.
+
+use ignore::DirEntry;
+use log::{error, info};
+use serde::Serialize;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::markdown::{
+    FileContent, Inclusion, TreeSitterConfig, compute_content_hash, format_modified_time,
+    has_signatures_form, language_for_extension, map_items, open_output, plan_budget,
+    read_file_content, resolve_input_dir_name,
+};
+use crate::redact::Redactor;
+use crate::token_count::estimate_tokens;
+use crate::tree::{FileNode, FileTree};
+
+#[cfg(feature = "tree-sitter-base")]
+use crate::tree_sitter::{CodeStructure, OutlineEntry, Signature};
+
+/// Version of the JSON schema, bumped on incompatible changes.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Top-level JSON document.
+#[derive(Debug, Serialize)]
+pub struct JsonDocument {
+    pub schema_version: u32,
+    pub project: String,
+    pub filters: Vec<String>,
+    pub ignores: Vec<String>,
+    pub content_hash: String,
+    pub file_tree: Vec<JsonTreeNode>,
+    pub files: Vec<JsonFile>,
+    /// Files reduced to their signatures to fit the token budget.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub signatures_only_files: Vec<String>,
+    /// Files left out because the token budget was reached.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub omitted_files: Vec<String>,
+}
+
+/// A node of the file tree, mirroring [`FileNode`].
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum JsonTreeNode {
+    File {
+        name: String,
+    },
+    Directory {
+        name: String,
+        children: Vec<JsonTreeNode>,
+    },
+}
+
+/// A single file entry.
+#[derive(Debug, Serialize)]
+pub struct JsonFile {
+    /// Relative path using `/` separators.
+    pub path: String,
+    pub size: u64,
+    pub modified: String,
+    pub language: String,
+    /// File content; `None` for binary or unreadable files, and for
+    /// signatures-only output of supported source files and outlined files.
+    pub content: Option<String>,
+    pub binary: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[cfg(feature = "tree-sitter-base")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signatures: Option<Vec<Signature>>,
+    #[cfg(feature = "tree-sitter-base")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structure: Option<CodeStructure>,
+    /// Heading tree or key shape of Markdown, TOML, YAML and JSON files.
+    #[cfg(feature = "tree-sitter-base")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outline: Option<Vec<OutlineEntry>>,
+}
+
+/// Converts a [`FileTree`] into its JSON representation.
+pub fn tree_to_json(tree: &FileTree) -> Vec<JsonTreeNode> {
+    tree.iter()
+        .map(|(name, node)| match node {
+            FileNode::File => JsonTreeNode::File { name: name.clone() },
+            FileNode::Directory(children) => JsonTreeNode::Directory {
+                name: name.clone(),
+                children: tree_to_json(children),
+            },
+        })
+        .collect()
+}
+
+/// Builds the JSON entry for a single file. Returns `None` if its metadata
+/// cannot be read (matching the Markdown output, which skips such files).
+pub fn build_json_file(
+    base_path: &Path,
+    file_path: &Path,
+    encoding_strategy: Option<&str>,
+    redactor: Option<&Redactor>,
+    ts_config: &TreeSitterConfig,
+) -> Option<JsonFile> {
+    let relative_path = file_path.strip_prefix(base_path).unwrap_or(file_path);
+    info!("Processing file: {}", relative_path.display());
+    let ts_config = &*ts_config.for_file(relative_path);
+
+    let metadata = match fs::metadata(file_path) {
+        Ok(meta) => meta,
+        Err(e) => {
+            error!(
+                "Failed to get metadata for {}: {}",
+                relative_path.display(),
+                e
+            );
+            return None;
+        }
+    };
+
+    let extension = file_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("text");
+
+    let mut entry = JsonFile {
+        path: relative_path.to_string_lossy().replace('\\', "/"),
+        size: metadata.len(),
+        modified: format_modified_time(&metadata),
+        language: language_for_extension(extension).to_string(),
+        content: None,
+        binary: false,
+        error: None,
+        #[cfg(feature = "tree-sitter-base")]
+        signatures: None,
+        #[cfg(feature = "tree-sitter-base")]
+        structure: None,
+        #[cfg(feature = "tree-sitter-base")]
+        outline: None,
+    };
+
+    match read_file_content(file_path, relative_path, encoding_strategy, redactor) {
+        FileContent::Text(content) => {
+            let content = ts_config.prepare_content(content, relative_path);
+
+            // Same rule as the Markdown output: signatures replace content only
+            // for extensions tree-sitter understands, outlines for large data
+            // and documentation files.
+            let signatures_only = ts_config.signatures_only(extension, metadata.len());
+
+            #[cfg(feature = "tree-sitter-base")]
+            {
+                use crate::tree_sitter::language_support::{SignatureDocs, Visibility};
+
+                if ts_config.structure {
+                    entry.structure =
+                        crate::tree_sitter::extract_structure_for_file(&content, extension);
+                }
+                if ts_config.signatures {
+                    let vis_filter: Visibility =
+                        ts_config.visibility.parse().unwrap_or(Visibility::All);
+                    let docs: SignatureDocs = ts_config.signature_docs.parse().unwrap_or_default();
+                    entry.signatures = crate::tree_sitter::extract_signatures_for_file(
+                        &content, extension, vis_filter,
+                    )
+                    .map(|mut signatures| {
+                        docs.apply(&mut signatures);
+                        signatures
+                    });
+                }
+                if ts_config.structure || signatures_only {
+                    entry.outline = crate::tree_sitter::extract_outline(&content, extension);
+                }
+            }
+
+            if !signatures_only {
+                entry.content = Some(content);
+            }
+        }
+        FileContent::Binary => entry.binary = true,
+        FileContent::Unreadable(placeholder) => entry.error = Some(placeholder.to_string()),
+    }
+
+    Some(entry)
+}
+
+/// Generates the JSON document and writes it to `output_path`.
+///
+/// `max_tokens` is applied with the same plan as the Markdown output
+/// ([`plan_budget`]): files that do not fit are reduced to their signatures
+/// (listed in `signatures_only_files`) or left out entirely (listed in
+/// `omitted_files`).
+#[allow(clippy::too_many_arguments)]
+pub fn generate_json(
+    output_path: &str,
+    input_dir: &str,
+    filters: &[String],
+    ignores: &[String],
+    file_tree: &FileTree,
+    files: &[DirEntry],
+    base_path: &Path,
+    encoding_strategy: Option<&str>,
+    redactor: Option<&Redactor>,
+    max_tokens: Option<usize>,
+    ts_config: &TreeSitterConfig,
+) -> io::Result<()> {
+    let output = open_output(output_path)?;
+
+    let build = |entry: &DirEntry, ts_config: &TreeSitterConfig| {
+        build_json_file(
+            base_path,
+            entry.path(),
+            encoding_strategy,
+            redactor,
+            ts_config,
+        )
+    };
+    let entry_tokens = |file: &JsonFile| {
+        serde_json::to_string(file)
+            .map(|v| estimate_tokens(&v, ts_config.tokenizer))
+            .unwrap_or(0)
+    };
+
+    let entries: Vec<(&DirEntry, JsonFile)> = files
+        .iter()
+        .zip(map_items(files, |entry| build(entry, ts_config)))
+        .filter_map(|(entry, file)| file.map(|file| (entry, file)))
+        .collect();
+
+    let mut json_files = Vec::with_capacity(entries.len());
+    let mut signatures_only_files = Vec::new();
+    let mut omitted_files = Vec::new();
+
+    let full = match max_tokens {
+        Some(_) => map_items(&entries, |(_, file)| entry_tokens(file)),
+        None => Vec::new(),
+    };
+    match max_tokens {
+        Some(max) if full.iter().sum::<usize>() > max => {
+            let signatures_config = TreeSitterConfig {
+                signatures: true,
+                ..ts_config.clone()
+            };
+            let reduced: Vec<Option<JsonFile>> = map_items(&entries, |(entry, _)| {
+                if has_signatures_form(entry.path(), ts_config) {
+                    build(entry, &signatures_config)
+                } else {
+                    None
+                }
+            });
+            let reduced_tokens: Vec<Option<usize>> = reduced
+                .iter()
+                .map(|f| f.as_ref().map(entry_tokens))
+                .collect();
+            let plan = plan_budget(&full, &reduced_tokens, max);
+
+            for (((_, file), reduced), inclusion) in entries.into_iter().zip(reduced).zip(plan) {
+                match (inclusion, reduced) {
+                    (Inclusion::Signatures, Some(reduced)) => {
+                        signatures_only_files.push(reduced.path.clone());
+                        json_files.push(reduced);
+                    }
+                    (Inclusion::TreeOnly, _) => omitted_files.push(file.path),
+                    _ => json_files.push(file),
+                }
+            }
+        }
+        _ => json_files.extend(entries.into_iter().map(|(_, file)| file)),
+    }
+
+    let document = JsonDocument {
+        schema_version: JSON_SCHEMA_VERSION,
+        project: resolve_input_dir_name(input_dir)?,
+        filters: filters.to_vec(),
+        ignores: ignores.to_vec(),
+        content_hash: format!("{:016x}", compute_content_hash(files, base_path)),
+        file_tree: tree_to_json(file_tree),
+        files: json_files,
+        signatures_only_files,
+        omitted_files,
+    };
+
+    let mut writer = BufWriter::new(output);
+    serde_json::to_writer_pretty(&mut writer, &document).map_err(io::Error::other)?;
+    writeln!(writer)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_utils::collect_files;
+    use crate::tree::build_file_tree;
+    use tempfile::tempdir;
+
+    fn generate(dir: &Path, ts_config: &TreeSitterConfig, max_tokens: Option<usize>) -> String {
+        let files = collect_files(dir, &[], &[], &[]).unwrap();
+        let file_tree = build_file_tree(&files, dir);
+        let output_dir = tempdir().unwrap();
+        let output_path = output_dir.path().join("output.json");
+        generate_json(
+            output_path.to_str().unwrap(),
+            "project",
+            &[],
+            &[],
+            &file_tree,
+            &files,
+            dir,
+            None,
+            None,
+            max_tokens,
+            ts_config,
+        )
+        .unwrap();
+        fs::read_to_string(output_path).unwrap()
+    }
+
+    #[test]
+    fn test_generate_json_document_shape() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        let png = [
+            0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48,
+            0x44, 0x52, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        fs::write(dir.path().join("data.bin"), png).unwrap();
+
+        let raw = generate(dir.path(), &TreeSitterConfig::default(), None);
+        let doc: serde_json::Value = serde_json::from_str(&raw).unwrap();
+
+        assert_eq!(doc["schema_version"], 1);
+        assert_eq!(doc["project"], "project");
+        assert_eq!(doc["content_hash"].as_str().unwrap().len(), 16);
+
+        let tree = doc["file_tree"].as_array().unwrap();
+        assert!(
+            tree.iter()
+                .any(|n| n["type"] == "directory" && n["name"] == "src")
+        );
+
+        let files = doc["files"].as_array().unwrap();
+        let main = files.iter().find(|f| f["path"] == "src/main.rs").unwrap();
+        assert_eq!(main["language"], "rust");
+        assert_eq!(main["content"], "fn main() {}\n");
+        assert_eq!(main["binary"], false);
+        assert_eq!(main["size"], 13);
+
+        let bin = files.iter().find(|f| f["path"] == "data.bin").unwrap();
+        assert_eq!(bin["binary"], true);
+        assert!(bin["content"].is_null());
+        assert!(doc.get("omitted_files").is_none());
+    }
+
+    #[test]
+    fn test_generate_json_hash_matches_markdown_helper() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "hello").unwrap();
+
+        let raw = generate(dir.path(), &TreeSitterConfig::default(), None);
+        let doc: serde_json::Value = serde_json::from_str(&raw).unwrap();
+
+        let files = collect_files(dir.path(), &[], &[], &[]).unwrap();
+        let expected = format!("{:016x}", compute_content_hash(&files, dir.path()));
+        assert_eq!(doc["content_hash"], expected);
+    }
+
+    #[test]
+    fn test_generate_json_respects_token_budget() {
+        let dir = tempdir().unwrap();
+        for i in 0..5 {
+            fs::write(dir.path().join(format!("file{}.txt", i)), "x".repeat(400)).unwrap();
+        }
+
+        let raw = generate(dir.path(), &TreeSitterConfig::default(), Some(150));
+        let doc: serde_json::Value = serde_json::from_str(&raw).unwrap();
+
+        let included = doc["files"].as_array().unwrap().len();
+        let omitted = doc["omitted_files"].as_array().unwrap().len();
+        assert!(included >= 1);
+        assert!(omitted >= 1);
+        assert_eq!(included + omitted, 5);
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-rust")]
+    fn test_generate_json_signatures_replace_content() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("lib.rs"),
+            "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+        )
+        .unwrap();
+
+        let ts_config = TreeSitterConfig {
+            signatures: true,
+            structure: true,
+            truncate: "smart".to_string(),
+            visibility: "all".to_string(),
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
+            focus: None,
+            tokenizer: crate::token_count::Tokenizer::Cl100k,
+        };
+        let raw = generate(dir.path(), &ts_config, None);
+        let doc: serde_json::Value = serde_json::from_str(&raw).unwrap();
+
+        let file = &doc["files"][0];
+        assert!(file["content"].is_null());
+        assert_eq!(file["signatures"][0]["name"], "add");
+        assert_eq!(file["signatures"][0]["kind"], "function");
+        assert_eq!(file["signatures"][0]["visibility"], "public");
+        assert_eq!(file["structure"]["functions"], 1);
+    }
+}
@@ -1,10 +1,15 @@
 use chrono::Utc;
 use ignore::DirEntry;
 use log::{error, info, warn};
+use std::borrow::Cow;
 use std::fs;
 use std::io::{self, Read, Seek, SeekFrom, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use crate::compact::CompactLevel;
+use crate::file_utils::FocusSet;
+use crate::redact::Redactor;
+use crate::token_count::{Tokenizer, estimate_tokens};
 use crate::tree::{FileTree, write_tree_to_file};
 use encoding_rs::{Encoding, UTF_8};
 
@@ -24,8 +29,69 @@ pub struct TreeSitterConfig {
     pub truncate: String,
     /// Visibility filter: "public", "private", or "all".
     pub visibility: String,
+    /// Doc comments kept in signatures: "full", "first-line", or "none".
+    pub signature_docs: String,
+    /// Remove test modules and test functions from supported source files.
+    pub strip_tests: bool,
+    /// Comments and blank lines removed from every file's content.
+    pub compact: Option<CompactLevel>,
+    /// Files shown in full; other supported source files are reduced to
+    /// signatures and a structure summary.
+    pub focus: Option<FocusSet>,
+    /// Tokenizer for budgets and token counts.
+    pub tokenizer: Tokenizer,
 }
 
+impl TreeSitterConfig {
+    /// The settings to render `path` with: outside the focus set, signatures
+    /// and structure replace the content.
+    pub fn for_file(&self, path: &Path) -> Cow<'_, TreeSitterConfig> {
+        match &self.focus {
+            Some(focus) if !focus.contains(path) => Cow::Owned(TreeSitterConfig {
+                signatures: true,
+                structure: true,
+                truncate: self.truncate.clone(),
+                visibility: self.visibility.clone(),
+                signature_docs: self.signature_docs.clone(),
+                strip_tests: self.strip_tests,
+                compact: self.compact,
+                focus: None,
+                tokenizer: self.tokenizer,
+            }),
+            _ => Cow::Borrowed(self),
+        }
+    }
+
+    /// Whether signatures replace the content of a file: source files that
+    /// tree-sitter parses, and documentation and data files of at least
+    /// [`OUTLINE_MIN_BYTES`], which are reduced to their outline.
+    pub fn signatures_only(&self, extension: &str, size: u64) -> bool {
+        self.signatures
+            && (crate::tree_sitter::is_supported_extension(extension)
+                || (crate::tree_sitter::has_outline(extension) && size >= OUTLINE_MIN_BYTES))
+    }
+
+    /// The content to render: with `strip_tests`, supported source files lose
+    /// their test modules and test functions; `compact` then removes comments
+    /// and blank lines. `path` is relative to the project root, so test
+    /// directories are matched within the project only.
+    pub fn prepare_content(&self, content: String, path: &Path) -> String {
+        let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("text");
+        let content = match self.strip_tests {
+            true => crate::tree_sitter::strip_tests_for_file(&content, path).unwrap_or(content),
+            false => content,
+        };
+        match self.compact {
+            Some(level) => crate::compact::compact(&content, extension, level),
+            None => content,
+        }
+    }
+}
+
+/// Markdown, TOML, YAML and JSON files smaller than this are shown in full
+/// under `--signatures`; larger ones are replaced by their outline.
+pub const OUTLINE_MIN_BYTES: u64 = 8 * 1024;
+
 /// Generates the final Markdown file.
 #[allow(clippy::too_many_arguments, unused_variables)]
 pub fn generate_markdown(
@@ -38,28 +104,181 @@ pub fn generate_markdown(
     base_path: &Path,
     line_numbers: bool,
     encoding_strategy: Option<&str>,
+    redactor: Option<&Redactor>,
     max_tokens: Option<usize>,
     ts_config: &TreeSitterConfig,
 ) -> io::Result<()> {
+    let mut output = open_output(output_path)?;
+
+    let input_dir_name = resolve_input_dir_name(input_dir)?;
+    let content_hash = compute_content_hash(files, base_path);
+
+    write_document_header(
+        &mut output,
+        &input_dir_name,
+        filters,
+        ignores,
+        content_hash,
+        file_tree,
+        None,
+    )?;
+
+    // (No '## Files' heading here; it will be injected later only once during final composition)
+    // (Diff section will be conditionally inserted later by the auto_diff logic in lib.rs)
+
+    write_file_sections(
+        output,
+        files,
+        max_tokens,
+        ts_config,
+        |file_path, ts_config, buf| {
+            process_file(
+                base_path,
+                file_path,
+                buf,
+                line_numbers,
+                encoding_strategy,
+                redactor,
+                ts_config,
+            )
+        },
+        |max, signatures_only, omitted| {
+            format!(
+                "---\n\n_⚠️ Token budget ({}) reached. {} files reduced to signatures, {} files omitted (listed in the file tree)._\n\n",
+                max, signatures_only, omitted
+            )
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Generates a Markdown document holding only the definitions matching
+/// `symbols`, each under a `path:start-end` anchor and preceded by the
+/// headers of its enclosing impl/class. Returns the queries nothing matched.
+#[cfg(feature = "tree-sitter-base")]
+pub fn generate_symbols_markdown(
+    output_path: &str,
+    input_dir: &str,
+    files: &[DirEntry],
+    base_path: &Path,
+    symbols: &[String],
+    redactor: Option<&Redactor>,
+) -> io::Result<Vec<String>> {
+    let found = map_items(files, |entry| {
+        let path = entry.path();
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|source| {
+                crate::tree_sitter::find_symbols_for_file(&source, extension, symbols)
+            })
+            .unwrap_or_default()
+    });
+
+    let mut output = open_output(output_path)?;
+    writeln!(
+        output,
+        "# Symbol Report
+"
+    )?;
+    writeln!(
+        output,
+        "This document contains the definitions of {} from the `{}` directory.
+",
+        symbols
+            .iter()
+            .map(|s| format!("`{}`", s))
+            .collect::<Vec<_>>()
+            .join(", "),
+        resolve_input_dir_name(input_dir)?
+    )?;
+
+    let mut matched = vec![false; symbols.len()];
+    for (entry, definitions) in files.iter().zip(&found) {
+        let path = entry.path();
+        let relative_path = path.strip_prefix(base_path).unwrap_or(path);
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        for definition in definitions {
+            let segments: Vec<String> = definition.name.split("::").map(String::from).collect();
+            for (query, matched) in symbols.iter().zip(matched.iter_mut()) {
+                *matched |= crate::tree_sitter::symbols::matches_query(&segments, query);
+            }
+
+            writeln!(
+                output,
+                "### `{}` ({})
+
+`{}:{}-{}`
+",
+                definition.name,
+                definition.kind,
+                relative_path.display(),
+                definition.start_line,
+                definition.end_line
+            )?;
+            writeln!(output, "```{}", language_for_extension(extension))?;
+            for header in &definition.context {
+                writeln!(output, "{}", header)?;
+            }
+            let source = match redactor {
+                Some(redactor) => redactor.redact_file(&definition.source, relative_path),
+                None => definition.source.clone(),
+            };
+            writeln!(output, "{}", source)?;
+            writeln!(
+                output,
+                "```
+"
+            )?;
+        }
+    }
+    output.flush()?;
+
+    Ok(symbols
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| !matched)
+        .map(|(query, _)| query.clone())
+        .collect())
+}
+
+/// Output path that streams the document to stdout instead of a file.
+pub const STDOUT_OUTPUT: &str = "-";
+
+/// Returns `true` when `output_path` designates stdout (`-o -`).
+pub fn is_stdout_output(output_path: &str) -> bool {
+    output_path == STDOUT_OUTPUT
+}
+
+/// Opens the document sink: stdout for `-`, otherwise the file at
+/// `output_path` (creating missing parent directories).
+pub fn open_output(output_path: &str) -> io::Result<Box<dyn Write + Send>> {
+    if is_stdout_output(output_path) {
+        return Ok(Box::new(io::stdout()));
+    }
+
     if let Some(parent) = Path::new(output_path).parent()
         && !parent.exists()
     {
         fs::create_dir_all(parent)?;
     }
 
-    let mut output = fs::File::create(output_path)?;
-
-    let input_dir_name = if input_dir == "." {
-        let current_dir = std::env::current_dir()?;
-        current_dir
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or_else(|| current_dir.to_str().unwrap_or("project"))
-            .to_string()
-    } else {
-        input_dir.to_string()
-    };
+    Ok(Box::new(fs::File::create(output_path)?))
+}
 
+/// Writes the report header (title, description, content hash) and the file tree.
+///
+/// `part` carries `(n, total)` when the document is one of several split parts.
+fn write_document_header(
+    output: &mut impl Write,
+    input_dir_name: &str,
+    filters: &[String],
+    ignores: &[String],
+    content_hash: u64,
+    file_tree: &FileTree,
+    part: Option<(usize, usize)>,
+) -> io::Result<()> {
     // --- Header --- //
     writeln!(output, "# Directory Structure Report\n")?;
 
@@ -82,39 +301,534 @@ pub fn generate_markdown(
         writeln!(output, "Custom ignored patterns: {}", ignores.join(", "))?;
     }
 
-    // Deterministic content hash (enables LLM prompt caching across runs)
-    // Uses xxh3 over file content bytes — stable across Rust versions and machines.
-    // Previous implementation hashed mtime (broken by git checkout, cp, etc.)
-    let mut content_hasher = xxhash_rust::xxh3::Xxh3::new();
-    for entry in files {
-        // Hash relative unix-style path for cross-OS determinism.
-        // Using absolute or OS-native paths would produce different hashes
-        // on different machines or operating systems.
-        let rel_path = entry.path().strip_prefix(base_path).unwrap_or(entry.path());
-        let normalized = rel_path.to_string_lossy().replace('\\', "/");
-        content_hasher.update(normalized.as_bytes());
-        // Null delimiter prevents collision: path="a" content="bc" vs path="ab" content="c"
-        content_hasher.update(b"\0");
-        // Hash actual file content (not mtime!) for determinism
-        if let Ok(bytes) = std::fs::read(entry.path()) {
-            content_hasher.update(&bytes);
-        }
-        content_hasher.update(b"\0");
+    if let Some((part, total)) = part {
+        writeln!(output, "Part {} of {}", part, total)?;
     }
-    writeln!(output, "Content hash: {:016x}", content_hasher.digest())?;
+
+    writeln!(output, "Content hash: {:016x}", content_hash)?;
     writeln!(output)?;
 
     // --- File Tree --- //
 
     writeln!(output, "## File Tree Structure\n")?;
 
-    write_tree_to_file(&mut output, file_tree, 0)?;
+    write_tree_to_file(output, file_tree, 0)?;
 
     writeln!(output)?;
+    Ok(())
+}
 
-    // (No '## Files' heading here; it will be injected later only once during final composition)
-    // (Diff section will be conditionally inserted later by the auto_diff logic in lib.rs)
+/// Estimates the token count of a rendered chunk.
+pub fn estimate_chunk_tokens(buf: &[u8], tokenizer: Tokenizer) -> usize {
+    estimate_tokens(&String::from_utf8_lossy(buf), tokenizer)
+}
+
+/// Path of the `part`-th file of a split document: `context.md` becomes
+/// `context_part_001.md`.
+pub fn split_part_path(output_path: &str, part: usize) -> PathBuf {
+    let path = Path::new(output_path);
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let file_name = match path.extension().and_then(|s| s.to_str()) {
+        Some(ext) => format!("{}_part_{:03}.{}", stem, part, ext),
+        None => format!("{}_part_{:03}", stem, part),
+    };
+    path.with_file_name(file_name)
+}
+
+/// Glob pattern matching every part written for `output_path`.
+pub fn split_part_glob(output_path: &str) -> String {
+    split_part_path(output_path, 0)
+        .to_string_lossy()
+        .replace("_part_000", "_part_*")
+}
+
+/// Generates the Markdown document as several files, each within `max_tokens`.
+///
+/// Files are packed into parts in input order and never split across parts,
+/// unless a single file exceeds the budget on its own; that file is cut into
+/// sections at AST boundaries (`truncate = "smart"`) or line boundaries.
+/// Every part repeats the header and file tree. Returns the written paths, or
+/// an `InvalidInput` error when `max_tokens` cannot hold the header, the tree
+/// and a minimal section.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_markdown_split(
+    output_path: &str,
+    input_dir: &str,
+    filters: &[String],
+    ignores: &[String],
+    file_tree: &FileTree,
+    files: &[DirEntry],
+    base_path: &Path,
+    line_numbers: bool,
+    encoding_strategy: Option<&str>,
+    redactor: Option<&Redactor>,
+    max_tokens: usize,
+    ts_config: &TreeSitterConfig,
+) -> io::Result<Vec<PathBuf>> {
+    let input_dir_name = resolve_input_dir_name(input_dir)?;
+    let content_hash = compute_content_hash(files, base_path);
+
+    // Every part carries the header and tree, so only the rest of the budget is
+    // available for file sections. Placeholder part numbers are as wide as real ones.
+    let mut header = Vec::new();
+    write_document_header(
+        &mut header,
+        &input_dir_name,
+        filters,
+        ignores,
+        content_hash,
+        file_tree,
+        Some((999, 999)),
+    )?;
+    let header_tokens = estimate_chunk_tokens(&header, ts_config.tokenizer);
+    let mut section_overhead = 0;
+    for entry in files {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let relative_path = entry.path().strip_prefix(base_path).unwrap_or(entry.path());
+        section_overhead = section_overhead.max(section_overhead_tokens(
+            relative_path,
+            &metadata,
+            line_numbers,
+            ts_config.tokenizer,
+        )?);
+    }
+    let needed = header_tokens + section_overhead + MIN_SECTION_CONTENT_TOKENS;
+    if max_tokens < needed {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "--max-tokens {} is too small for --split: every part repeats the header and file tree, so it needs at least {} tokens",
+                max_tokens, needed
+            ),
+        ));
+    }
+    let section_budget = max_tokens - header_tokens;
+
+    let render = |entry: &DirEntry| {
+        render_split_sections(
+            base_path,
+            entry.path(),
+            section_budget,
+            line_numbers,
+            encoding_strategy,
+            redactor,
+            ts_config,
+        )
+    };
+
+    #[cfg(feature = "parallel")]
+    let rendered: Vec<io::Result<Vec<Vec<u8>>>> = {
+        use rayon::prelude::*;
+        files.par_iter().map(render).collect()
+    };
 
+    #[cfg(not(feature = "parallel"))]
+    let rendered: Vec<io::Result<Vec<Vec<u8>>>> = files.iter().map(render).collect();
+
+    // Greedy packing in input order
+    let mut parts: Vec<Vec<u8>> = Vec::new();
+    let mut current = Vec::new();
+    let mut current_tokens = 0;
+    for sections in rendered {
+        for section in sections? {
+            let tokens = estimate_chunk_tokens(&section, ts_config.tokenizer);
+            if !current.is_empty() && current_tokens + tokens > section_budget {
+                parts.push(std::mem::take(&mut current));
+                current_tokens = 0;
+            }
+            current_tokens += tokens;
+            current.extend_from_slice(&section);
+        }
+    }
+    if !current.is_empty() || parts.is_empty() {
+        parts.push(current);
+    }
+
+    if let Some(parent) = Path::new(output_path).parent()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    let total = parts.len();
+    let mut written = Vec::with_capacity(total);
+    for (index, body) in parts.into_iter().enumerate() {
+        let part_path = split_part_path(output_path, index + 1);
+        let mut output = io::BufWriter::new(fs::File::create(&part_path)?);
+        write_document_header(
+            &mut output,
+            &input_dir_name,
+            filters,
+            ignores,
+            content_hash,
+            file_tree,
+            Some((index + 1, total)),
+        )?;
+        output.write_all(&body)?;
+        output.flush()?;
+        written.push(part_path);
+    }
+
+    Ok(written)
+}
+
+/// Renders one file for split output: a single section when it fits in
+/// `budget_tokens`, otherwise several numbered sections that each fit.
+fn render_split_sections(
+    base_path: &Path,
+    file_path: &Path,
+    budget_tokens: usize,
+    line_numbers: bool,
+    encoding_strategy: Option<&str>,
+    redactor: Option<&Redactor>,
+    ts_config: &TreeSitterConfig,
+) -> io::Result<Vec<Vec<u8>>> {
+    let mut whole = Vec::new();
+    process_file(
+        base_path,
+        file_path,
+        &mut whole,
+        line_numbers,
+        encoding_strategy,
+        redactor,
+        ts_config,
+    )?;
+    if estimate_chunk_tokens(&whole, ts_config.tokenizer) <= budget_tokens {
+        return Ok(vec![whole]);
+    }
+
+    let relative_path = file_path.strip_prefix(base_path).unwrap_or(file_path);
+    let ts_config = &*ts_config.for_file(relative_path);
+    let extension = file_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("text");
+    let metadata = fs::metadata(file_path)?;
+    let signatures_only = ts_config.signatures_only(extension, metadata.len());
+
+    let content = match read_file_content(file_path, relative_path, encoding_strategy, redactor) {
+        FileContent::Text(content) if !signatures_only => {
+            ts_config.prepare_content(content, relative_path)
+        }
+        _ => {
+            warn!(
+                "{} exceeds the token budget and cannot be split; it is written to its own part",
+                relative_path.display()
+            );
+            return Ok(vec![whole]);
+        }
+    };
+    let language = language_for_extension(extension);
+    let smart = ts_config.truncate == "smart";
+
+    // Cut the content into pieces whose rendered sections fit the budget.
+    // `generate_markdown_split` checked that the budget leaves room for content.
+    let tokenizer = ts_config.tokenizer;
+    let mut heading = Vec::new();
+    write_section_heading(&mut heading, relative_path, (999, 999), &metadata)?;
+    let heading_tokens = estimate_chunk_tokens(&heading, tokenizer);
+    let content_budget = budget_tokens
+        .saturating_sub(section_overhead_tokens(
+            relative_path,
+            &metadata,
+            line_numbers,
+            tokenizer,
+        )?)
+        .max(MIN_SECTION_CONTENT_TOKENS);
+    let mut pieces: Vec<(usize, &str)> = Vec::new();
+    let mut rest = content.as_str();
+    let mut first_line = 1;
+    while !rest.is_empty() {
+        let mut max_bytes = tokenizer.prefix_len(rest, content_budget).max(1);
+        let piece = loop {
+            let cut = split_point(rest, max_bytes, extension, smart);
+            let piece = &rest[..cut];
+            let mut probe = Vec::new();
+            write_text_content_from(&mut probe, piece, language, line_numbers, first_line)?;
+            let probe_tokens = estimate_chunk_tokens(&probe, tokenizer) + heading_tokens;
+            if probe_tokens <= budget_tokens || cut <= 1 || max_bytes <= 1 {
+                break piece;
+            }
+            // Shrink in proportion to the overshoot (line numbers, fences)
+            max_bytes = (cut * budget_tokens / probe_tokens)
+                .min(max_bytes - 1)
+                .max(1);
+        };
+        pieces.push((first_line, piece));
+        first_line += piece.matches('\n').count();
+        rest = &rest[piece.len()..];
+    }
+
+    let total = pieces.len();
+    let mut sections = Vec::with_capacity(total);
+    for (index, (first_line, piece)) in pieces.into_iter().enumerate() {
+        let mut buf = Vec::new();
+        write_section_heading(&mut buf, relative_path, (index + 1, total), &metadata)?;
+        write_text_content_from(&mut buf, piece, language, line_numbers, first_line)?;
+        if index + 1 == total {
+            write_tree_sitter_enrichment(&mut buf, &content, extension, ts_config)?;
+        }
+        sections.push(buf);
+    }
+
+    Ok(sections)
+}
+
+/// Smallest amount of file content a split section must be able to hold.
+const MIN_SECTION_CONTENT_TOKENS: usize = 32;
+
+/// Writes the heading and metadata lines of a split section.
+fn write_section_heading(
+    buf: &mut Vec<u8>,
+    relative_path: &Path,
+    (section, total): (usize, usize),
+    metadata: &fs::Metadata,
+) -> io::Result<()> {
+    writeln!(buf)?;
+    writeln!(
+        buf,
+        "### File: `{}` (section {} of {})",
+        relative_path.display(),
+        section,
+        total
+    )?;
+    writeln!(buf)?;
+    writeln!(buf, "- Size: {} bytes", metadata.len())?;
+    writeln!(buf, "- Modified: {}", format_modified_time(metadata))?;
+    writeln!(buf)?;
+    Ok(())
+}
+
+/// Tokens a split section of `relative_path` spends outside its content: the
+/// heading, the metadata lines and the code fence. Section numbers are
+/// measured at their widest.
+fn section_overhead_tokens(
+    relative_path: &Path,
+    metadata: &fs::Metadata,
+    line_numbers: bool,
+    tokenizer: Tokenizer,
+) -> io::Result<usize> {
+    let extension = relative_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("text");
+    let mut buf = Vec::new();
+    write_section_heading(&mut buf, relative_path, (999, 999), metadata)?;
+    write_text_content_from(
+        &mut buf,
+        "",
+        language_for_extension(extension),
+        line_numbers,
+        1,
+    )?;
+    Ok(estimate_chunk_tokens(&buf, tokenizer))
+}
+
+/// Finds where to cut `source` so the first piece is at most `max_bytes` long.
+///
+/// Prefers an AST boundary when `smart` is set, then the last line break, then
+/// a character boundary. Always makes progress on non-empty input.
+fn split_point(source: &str, max_bytes: usize, extension: &str, smart: bool) -> usize {
+    if source.len() <= max_bytes {
+        return source.len();
+    }
+
+    if smart
+        && let Some(cut) =
+            crate::tree_sitter::find_smart_truncation_point(source, max_bytes, extension)
+        && cut > 0
+        && cut < max_bytes
+        && source.is_char_boundary(cut)
+    {
+        return cut;
+    }
+
+    let mut limit = max_bytes;
+    while limit > 0 && !source.is_char_boundary(limit) {
+        limit -= 1;
+    }
+    if let Some(newline) = source[..limit].rfind('\n') {
+        return newline + 1;
+    }
+    if limit > 0 {
+        return limit;
+    }
+    source
+        .char_indices()
+        .nth(1)
+        .map(|(i, _)| i)
+        .unwrap_or(source.len())
+}
+
+/// How much of a file fits in the token budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inclusion {
+    /// Full content (or whatever the run's own settings render)
+    Full,
+    /// Signatures only, via `extract_signatures_for_file`
+    Signatures,
+    /// Left out of the sections; the file is still listed in the tree
+    TreeOnly,
+}
+
+/// Chooses how to include each file so that the total stays within `budget`.
+///
+/// `full` holds the token cost of every file in relevance order and
+/// `signatures` the cost of its signatures-only rendering, if it has one.
+/// Coverage comes first: every file is taken in its cheapest form while it
+/// fits, so a large file never crowds out everything after it. The remaining
+/// budget then upgrades signatures to full content, most relevant files first.
+pub fn plan_budget(full: &[usize], signatures: &[Option<usize>], budget: usize) -> Vec<Inclusion> {
+    let mut plan = vec![Inclusion::TreeOnly; full.len()];
+    let mut used = 0;
+
+    for (index, (&full_cost, signatures_cost)) in full.iter().zip(signatures).enumerate() {
+        let (inclusion, cost) = match *signatures_cost {
+            Some(cost) if cost < full_cost => (Inclusion::Signatures, cost),
+            _ => (Inclusion::Full, full_cost),
+        };
+        if used + cost <= budget {
+            plan[index] = inclusion;
+            used += cost;
+        }
+    }
+
+    for (index, &full_cost) in full.iter().enumerate() {
+        if plan[index] != Inclusion::Signatures {
+            continue;
+        }
+        let extra = full_cost - signatures[index].unwrap_or_default();
+        if used + extra <= budget {
+            plan[index] = Inclusion::Full;
+            used += extra;
+        }
+    }
+
+    plan
+}
+
+/// Whether a file has a signatures-only rendering cheaper than its full one.
+pub fn has_signatures_form(file_path: &Path, ts_config: &TreeSitterConfig) -> bool {
+    let extension = file_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("text");
+    !ts_config.for_file(file_path).signatures
+        && (crate::tree_sitter::is_supported_extension(extension)
+            || crate::tree_sitter::has_outline(extension))
+}
+
+/// Maps `f` over `items`, on the rayon pool when `parallel` is enabled.
+pub(crate) fn map_items<I, T, F>(items: &[I], f: F) -> Vec<T>
+where
+    I: Sync,
+    T: Send,
+    F: Fn(&I) -> T + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(f).collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+/// Renders every file with `render` and writes the results in input order.
+///
+/// With the `parallel` feature, files are rendered on the rayon pool and an
+/// ordered writer thread reassembles them. When `max_tokens` is set and the
+/// files do not all fit, every file is rendered once up front to measure it and
+/// [`plan_budget`] decides which files keep full content, which are reduced to
+/// signatures and which only appear in the tree; `budget_notice(max,
+/// signatures_only, omitted)` is then appended. Returns the writer so callers
+/// can append a footer.
+pub fn write_file_sections<W, R, N>(
+    output: W,
+    files: &[DirEntry],
+    max_tokens: Option<usize>,
+    ts_config: &TreeSitterConfig,
+    render: R,
+    budget_notice: N,
+) -> io::Result<W>
+where
+    W: Write + Send + 'static,
+    R: Fn(&Path, &TreeSitterConfig, &mut Vec<u8>) -> io::Result<()> + Sync,
+    N: Fn(usize, usize, usize) -> String,
+{
+    let signatures_config = TreeSitterConfig {
+        signatures: true,
+        ..ts_config.clone()
+    };
+    let cost = |path: &Path, config: &TreeSitterConfig| {
+        let mut buf = Vec::new();
+        // Failures are reported by the writing pass below
+        match render(path, config, &mut buf) {
+            Ok(()) => estimate_chunk_tokens(&buf, config.tokenizer),
+            Err(_) => 0,
+        }
+    };
+
+    let plan = match max_tokens {
+        Some(max) => {
+            let full = map_items(files, |entry| cost(entry.path(), ts_config));
+            if full.iter().sum::<usize>() <= max {
+                None
+            } else {
+                let signatures = map_items(files, |entry| {
+                    has_signatures_form(entry.path(), ts_config)
+                        .then(|| cost(entry.path(), &signatures_config))
+                });
+                // Leave room for the notice at its longest
+                let notice_tokens = estimate_tokens(
+                    &budget_notice(max, files.len(), files.len()),
+                    ts_config.tokenizer,
+                );
+                Some(plan_budget(
+                    &full,
+                    &signatures,
+                    max.saturating_sub(notice_tokens),
+                ))
+            }
+        }
+        None => None,
+    };
+
+    let mut output = write_ordered(output, files, |index, path, buf| {
+        match plan.as_ref().map_or(Inclusion::Full, |plan| plan[index]) {
+            Inclusion::Full => render(path, ts_config, buf),
+            Inclusion::Signatures => render(path, &signatures_config, buf),
+            Inclusion::TreeOnly => Ok(()),
+        }
+    })?;
+
+    if let (Some(max), Some(plan)) = (max_tokens, plan.as_ref()) {
+        let count = |inclusion| plan.iter().filter(|&&i| i == inclusion).count();
+        let signatures_only = count(Inclusion::Signatures);
+        let omitted = count(Inclusion::TreeOnly);
+        if signatures_only + omitted > 0 {
+            output.write_all(budget_notice(max, signatures_only, omitted).as_bytes())?;
+        }
+    }
+
+    Ok(output)
+}
+
+/// Renders every file with `render(index, path, buf)` and writes the buffers
+/// in input order.
+fn write_ordered<W, R>(output: W, files: &[DirEntry], render: R) -> io::Result<W>
+where
+    W: Write + Send + 'static,
+    R: Fn(usize, &Path, &mut Vec<u8>) -> io::Result<()> + Sync,
+{
     #[cfg(feature = "parallel")]
     {
         use rayon::prelude::*;
@@ -127,14 +841,11 @@ pub fn generate_markdown(
         let writer_handle = {
             let mut output = output;
             let total_files = files.len();
-            let budget = max_tokens;
 
-            thread::spawn(move || -> io::Result<()> {
+            thread::spawn(move || -> io::Result<W> {
                 let mut completed_chunks = std::collections::BTreeMap::new();
                 let mut next_index = 0;
                 let mut errors = Vec::new();
-                let mut tokens_used: usize = 0;
-                let mut budget_exceeded = false;
 
                 // Receive chunks and write them in order
                 while next_index < total_files {
@@ -144,38 +855,8 @@ pub fn generate_markdown(
 
                             // Write all consecutive chunks starting from next_index
                             while let Some(chunk_result) = completed_chunks.remove(&next_index) {
-                                if budget_exceeded {
-                                    // Already over budget — skip remaining chunks
-                                    next_index += 1;
-                                    continue;
-                                }
-
                                 match chunk_result {
                                     Ok(buf) => {
-                                        // Estimate tokens for this chunk (~4 bytes per token)
-                                        let chunk_tokens = buf.len() / 4;
-
-                                        if let Some(max) = budget
-                                            && tokens_used + chunk_tokens > max
-                                            && tokens_used > 0
-                                        {
-                                            let remaining = total_files - next_index;
-                                            let notice = format!(
-                                                "---\n\n_⚠️ Token budget ({}) reached. {} remaining files omitted._\n\n",
-                                                max, remaining
-                                            );
-                                            if let Err(e) = output.write_all(notice.as_bytes()) {
-                                                errors.push(format!(
-                                                    "Failed to write truncation notice: {}",
-                                                    e
-                                                ));
-                                            }
-                                            budget_exceeded = true;
-                                            next_index += 1;
-                                            continue;
-                                        }
-
-                                        tokens_used += chunk_tokens;
                                         if let Err(e) = output.write_all(&buf) {
                                             errors.push(format!(
                                                 "Failed to write output for file index {}: {}",
@@ -212,23 +893,14 @@ pub fn generate_markdown(
                     )));
                 }
 
-                Ok(())
+                Ok(output)
             })
         };
 
         // Process files in parallel and send results to writer
-        let ts_config_clone = ts_config.clone();
         files.par_iter().enumerate().for_each(|(index, entry)| {
             let mut buf = Vec::new();
-            let result = process_file(
-                base_path,
-                entry.path(),
-                &mut buf,
-                line_numbers,
-                encoding_strategy,
-                &ts_config_clone,
-            )
-            .map(|_| buf);
+            let result = render(index, entry.path(), &mut buf).map(|_| buf);
 
             // Send result to writer thread (ignore send errors - channel might be closed)
             let _ = sender.send((index, result));
@@ -240,96 +912,257 @@ pub fn generate_markdown(
         // Wait for writer thread to complete and propagate any errors
         writer_handle
             .join()
-            .map_err(|_| std::io::Error::other("Writer thread panicked"))??;
+            .map_err(|_| std::io::Error::other("Writer thread panicked"))?
     }
 
     #[cfg(not(feature = "parallel"))]
     {
-        let mut tokens_used: usize = 0;
-
-        for (idx, entry) in files.iter().enumerate() {
-            // Estimate tokens for this file (~4 bytes per token)
-            let file_size = std::fs::metadata(entry.path())
-                .map(|m| m.len())
-                .unwrap_or(0);
-            let estimated_file_tokens = (file_size as usize) / 4;
-
-            if let Some(budget) = max_tokens {
-                if tokens_used + estimated_file_tokens > budget && tokens_used > 0 {
-                    let remaining = files.len() - idx;
-                    writeln!(output, "---\n")?;
-                    writeln!(
-                        output,
-                        "_⚠️ Token budget ({}) reached. {} remaining files omitted._\n",
-                        budget, remaining
-                    )?;
-                    break;
-                }
-            }
+        let mut output = output;
+        for (index, entry) in files.iter().enumerate() {
+            let mut buf = Vec::new();
+            render(index, entry.path(), &mut buf)?;
+            output.write_all(&buf)?;
+        }
+        Ok(output)
+    }
+}
 
-            tokens_used += estimated_file_tokens;
-            process_file(
-                base_path,
-                entry.path(),
-                &mut output,
-                line_numbers,
-                encoding_strategy,
-                ts_config,
-            )?;
+/// Resolves the project name shown in the document header.
+///
+/// `"."` is replaced by the name of the current working directory.
+pub fn resolve_input_dir_name(input_dir: &str) -> io::Result<String> {
+    if input_dir == "." {
+        let current_dir = std::env::current_dir()?;
+        Ok(current_dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_else(|| current_dir.to_str().unwrap_or("project"))
+            .to_string())
+    } else {
+        Ok(input_dir.to_string())
+    }
+}
+
+/// Computes the deterministic content hash written into every document header.
+pub fn compute_content_hash(files: &[DirEntry], base_path: &Path) -> u64 {
+    // Deterministic content hash (enables LLM prompt caching across runs)
+    // Uses xxh3 over file content bytes — stable across Rust versions and machines.
+    // Previous implementation hashed mtime (broken by git checkout, cp, etc.)
+    let mut content_hasher = xxhash_rust::xxh3::Xxh3::new();
+    for entry in files {
+        // Hash relative unix-style path for cross-OS determinism.
+        // Using absolute or OS-native paths would produce different hashes
+        // on different machines or operating systems.
+        let rel_path = entry.path().strip_prefix(base_path).unwrap_or(entry.path());
+        let normalized = rel_path.to_string_lossy().replace('\\', "/");
+        content_hasher.update(normalized.as_bytes());
+        // Null delimiter prevents collision: path="a" content="bc" vs path="ab" content="c"
+        content_hasher.update(b"\0");
+        // Hash actual file content (not mtime!) for determinism
+        if let Ok(bytes) = std::fs::read(entry.path()) {
+            content_hasher.update(&bytes);
         }
+        content_hasher.update(b"\0");
     }
+    content_hasher.digest()
+}
 
-    Ok(())
+/// Content of a single file after binary sniffing and encoding detection.
+#[derive(Debug)]
+pub enum FileContent {
+    /// Decoded text (transcoded to UTF-8 when the file used another encoding).
+    Text(String),
+    /// Binary data or an encoding that could not be transcoded.
+    Binary,
+    /// The file could not be read; carries the placeholder shown in its place.
+    Unreadable(&'static str),
 }
 
-/// Processes a single file and writes its content to the output.
-pub fn process_file(
-    base_path: &Path,
+const UNREADABLE_PLACEHOLDER: &str =
+    "<Could not read file content (e.g., binary file or permission error)>";
+
+/// Reads a file as text, applying the same binary detection, encoding
+/// strategy and secret redaction used by every output format.
+pub fn read_file_content(
     file_path: &Path,
-    output: &mut impl Write,
-    line_numbers: bool,
+    relative_path: &Path,
     encoding_strategy: Option<&str>,
-    ts_config: &TreeSitterConfig,
-) -> io::Result<()> {
-    let relative_path = file_path.strip_prefix(base_path).unwrap_or(file_path);
-    info!("Processing file: {}", relative_path.display());
+    redactor: Option<&Redactor>,
+) -> FileContent {
+    match read_unredacted_content(file_path, relative_path, encoding_strategy) {
+        FileContent::Text(content) => match redactor {
+            Some(redactor) => FileContent::Text(redactor.redact_file(&content, relative_path)),
+            None => FileContent::Text(content),
+        },
+        other => other,
+    }
+}
 
-    let metadata = match fs::metadata(file_path) {
-        Ok(meta) => meta,
+fn read_unredacted_content(
+    file_path: &Path,
+    relative_path: &Path,
+    encoding_strategy: Option<&str>,
+) -> FileContent {
+    let mut file = match fs::File::open(file_path) {
+        Ok(file) => file,
         Err(e) => {
-            error!(
-                "Failed to get metadata for {}: {}",
+            warn!(
+                "Could not open file {}: {}. Skipping content.",
                 relative_path.display(),
                 e
             );
-            return Ok(());
+            return FileContent::Unreadable(UNREADABLE_PLACEHOLDER);
         }
     };
 
-    let modified_time = metadata
-        .modified()
-        .ok()
-        .map(|time| {
-            let system_time: chrono::DateTime<Utc> = time.into();
-            system_time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
-        })
-        .unwrap_or_else(|| "Unknown".to_string());
+    let mut sniff = [0u8; 8192];
+    let n = match file.read(&mut sniff) {
+        Ok(n) => n,
+        Err(e) => {
+            warn!(
+                "Could not read file {}: {}. Skipping content.",
+                relative_path.display(),
+                e
+            );
+            return FileContent::Unreadable(UNREADABLE_PLACEHOLDER);
+        }
+    };
+    let slice = &sniff[..n];
+
+    // Find a valid UTF-8 boundary by backtracking up to 3 bytes.
+    // If the sniff buffer cuts a multi-byte char (e.g., emoji at byte 8191),
+    // from_utf8 would falsely classify the file as non-UTF-8.
+    let check_len = if n == sniff.len() {
+        // Buffer is full — may have split a multi-byte char at the end
+        let mut end = n;
+        while end > 0 && end > n.saturating_sub(4) && sniff[end - 1] & 0xC0 == 0x80 {
+            end -= 1; // skip continuation bytes
+        }
+        // If we landed on a leading byte, check if the sequence is complete
+        if end > 0 && end < n {
+            let leading = sniff[end - 1];
+            let expected_len = if leading & 0xE0 == 0xC0 {
+                2
+            } else if leading & 0xF0 == 0xE0 {
+                3
+            } else if leading & 0xF8 == 0xF0 {
+                4
+            } else {
+                1
+            };
+            if end - 1 + expected_len > n {
+                end - 1 // incomplete char — exclude the leading byte too
+            } else {
+                n
+            }
+        } else {
+            n
+        }
+    } else {
+        n // didn't fill the buffer, so no boundary issue
+    };
 
-    writeln!(output)?;
-    writeln!(output, "### File: `{}`", relative_path.display())?;
+    // First check if it's valid UTF-8
+    let is_utf8 = std::str::from_utf8(&sniff[..check_len]).is_ok();
+
+    if !is_utf8 || slice.contains(&0) {
+        // Try encoding detection for non-UTF-8 files
+        // If it's not UTF-8, try to detect the encoding
+        let (encoding, _consumed) =
+            encoding_rs::Encoding::for_bom(slice).unwrap_or((encoding_rs::UTF_8, 0));
+
+        // If it's not UTF-8, try to detect the encoding
+        let detected_encoding = if encoding == UTF_8 {
+            // Use chardet-like detection for common encodings
+            detect_text_encoding(slice)
+        } else {
+            Some(encoding)
+        };
 
-    writeln!(output)?;
+        match detected_encoding {
+            Some(enc) if enc != UTF_8 => {
+                let strategy = encoding_strategy.unwrap_or("detect");
+                match strategy {
+                    "strict" | "skip" => {
+                        // Skip files with non-UTF-8 encoding
+                        warn!(
+                            "Skipping non-UTF-8 file {} (encoding: {}, strategy: {})",
+                            relative_path.display(),
+                            enc.name(),
+                            strategy
+                        );
+                    }
+                    _ => {
+                        // Default "detect" strategy: attempt to transcode
+                        match transcode_file_content(file_path, enc) {
+                            Ok(transcoded_content) => {
+                                info!(
+                                    "Successfully transcoded {} from {} to UTF-8",
+                                    relative_path.display(),
+                                    enc.name()
+                                );
+                                return FileContent::Text(transcoded_content);
+                            }
+                            Err(e) => {
+                                warn!(
+                                    "Failed to transcode {} from {}: {}. Treating as binary.",
+                                    relative_path.display(),
+                                    enc.name(),
+                                    e
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {
+                // Check if it's likely binary (contains null bytes)
+                if slice.contains(&0) {
+                    warn!(
+                        "Detected binary file {} (contains null bytes). Skipping content.",
+                        relative_path.display()
+                    );
+                } else {
+                    warn!(
+                        "Could not determine encoding for {}. Treating as binary.",
+                        relative_path.display()
+                    );
+                }
+            }
+        }
 
-    writeln!(output, "- Size: {} bytes", metadata.len())?;
-    writeln!(output, "- Modified: {}", modified_time)?;
-    writeln!(output)?;
+        return FileContent::Binary;
+    }
 
-    // --- File Content --- //
-    let extension = file_path
-        .extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("text");
-    let language = match extension {
+    // Reset cursor and stream the content from the already-open file handle.
+    // (Previously opened a second fd via fs::read_to_string — wasting the seek)
+    if let Err(e) = file.seek(SeekFrom::Start(0)) {
+        warn!(
+            "Could not reset file cursor for {}: {}. Skipping content.",
+            relative_path.display(),
+            e
+        );
+        return FileContent::Unreadable(UNREADABLE_PLACEHOLDER);
+    }
+
+    // Read UTF-8 content from the same file descriptor
+    let mut content = String::new();
+    match file.read_to_string(&mut content) {
+        Ok(_) => FileContent::Text(content),
+        Err(e) => {
+            warn!(
+                "Error reading file {}: {}. Output may be truncated.",
+                relative_path.display(),
+                e
+            );
+            FileContent::Unreadable("<Error reading file content>")
+        }
+    }
+}
+
+/// Maps a file extension to the language identifier used for code fences.
+pub fn language_for_extension(extension: &str) -> &str {
+    match extension {
         "rs" => "rust",
         "js" => "javascript",
         "ts" => "typescript",
@@ -347,199 +1180,86 @@ pub fn process_file(
         "c" => "c",
         "h" => "c",
         "hpp" => "cpp",
+        "rb" => "ruby",
+        "cs" => "csharp",
+        "kt" | "kts" => "kotlin",
         "sql" => "sql",
-        "sh" => "bash",
+        "sh" | "bash" => "bash",
+        "lua" => "lua",
+        "zig" => "zig",
+        "scala" | "sc" => "scala",
+        "hs" => "haskell",
         "xml" => "xml",
         "lock" => "toml",
         _ => extension,
-    };
+    }
+}
 
-    // Enhanced binary file handling with encoding detection and transcoding
-    match fs::File::open(file_path) {
-        Ok(mut file) => {
-            let mut sniff = [0u8; 8192];
-            let n = match file.read(&mut sniff) {
-                Ok(n) => n,
-                Err(e) => {
-                    warn!(
-                        "Could not read file {}: {}. Skipping content.",
-                        relative_path.display(),
-                        e
-                    );
+/// Formats a file's modification time the way it appears in file headers.
+pub fn format_modified_time(metadata: &fs::Metadata) -> String {
+    metadata
+        .modified()
+        .ok()
+        .map(|time| {
+            let system_time: chrono::DateTime<Utc> = time.into();
+            system_time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+        })
+        .unwrap_or_else(|| "Unknown".to_string())
+}
 
-                    writeln!(output, "```text")?;
+/// Processes a single file and writes its content to the output.
+pub fn process_file(
+    base_path: &Path,
+    file_path: &Path,
+    output: &mut impl Write,
+    line_numbers: bool,
+    encoding_strategy: Option<&str>,
+    redactor: Option<&Redactor>,
+    ts_config: &TreeSitterConfig,
+) -> io::Result<()> {
+    let relative_path = file_path.strip_prefix(base_path).unwrap_or(file_path);
+    info!("Processing file: {}", relative_path.display());
+    let ts_config = &*ts_config.for_file(relative_path);
 
-                    writeln!(
-                        output,
-                        "<Could not read file content (e.g., binary file or permission error)>"
-                    )?;
+    let metadata = match fs::metadata(file_path) {
+        Ok(meta) => meta,
+        Err(e) => {
+            error!(
+                "Failed to get metadata for {}: {}",
+                relative_path.display(),
+                e
+            );
+            return Ok(());
+        }
+    };
 
-                    writeln!(output, "```")?;
+    let modified_time = format_modified_time(&metadata);
 
-                    return Ok(());
-                }
-            };
-            let slice = &sniff[..n];
-
-            // Find a valid UTF-8 boundary by backtracking up to 3 bytes.
-            // If the sniff buffer cuts a multi-byte char (e.g., emoji at byte 8191),
-            // from_utf8 would falsely classify the file as non-UTF-8.
-            let check_len = if n == sniff.len() {
-                // Buffer is full — may have split a multi-byte char at the end
-                let mut end = n;
-                while end > 0 && end > n.saturating_sub(4) && sniff[end - 1] & 0xC0 == 0x80 {
-                    end -= 1; // skip continuation bytes
-                }
-                // If we landed on a leading byte, check if the sequence is complete
-                if end > 0 && end < n {
-                    let leading = sniff[end - 1];
-                    let expected_len = if leading & 0xE0 == 0xC0 {
-                        2
-                    } else if leading & 0xF0 == 0xE0 {
-                        3
-                    } else if leading & 0xF8 == 0xF0 {
-                        4
-                    } else {
-                        1
-                    };
-                    if end - 1 + expected_len > n {
-                        end - 1 // incomplete char — exclude the leading byte too
-                    } else {
-                        n
-                    }
-                } else {
-                    n
-                }
-            } else {
-                n // didn't fill the buffer, so no boundary issue
-            };
+    writeln!(output)?;
+    writeln!(output, "### File: `{}`", relative_path.display())?;
 
-            // First check if it's valid UTF-8
-            let is_utf8 = std::str::from_utf8(&sniff[..check_len]).is_ok();
+    writeln!(output)?;
 
-            if is_utf8 && !slice.contains(&0) {
-                // Valid UTF-8 text file - proceed normally
-            } else {
-                // Try encoding detection for non-UTF-8 files
-                // If it's not UTF-8, try to detect the encoding
-                let (encoding, _consumed) =
-                    encoding_rs::Encoding::for_bom(slice).unwrap_or((encoding_rs::UTF_8, 0));
-
-                // If it's not UTF-8, try to detect the encoding
-                let detected_encoding = if encoding == UTF_8 {
-                    // Use chardet-like detection for common encodings
-                    detect_text_encoding(slice)
-                } else {
-                    Some(encoding)
-                };
-
-                match detected_encoding {
-                    Some(enc) if enc != UTF_8 => {
-                        let strategy = encoding_strategy.unwrap_or("detect");
-                        match strategy {
-                            "strict" | "skip" => {
-                                // Skip files with non-UTF-8 encoding
-                                warn!(
-                                    "Skipping non-UTF-8 file {} (encoding: {}, strategy: {})",
-                                    relative_path.display(),
-                                    enc.name(),
-                                    strategy
-                                );
-                            }
-                            _ => {
-                                // Default "detect" strategy: attempt to transcode
-                                match transcode_file_content(file_path, enc) {
-                                    Ok(transcoded_content) => {
-                                        info!(
-                                            "Successfully transcoded {} from {} to UTF-8",
-                                            relative_path.display(),
-                                            enc.name()
-                                        );
-                                        write_text_content(
-                                            output,
-                                            &transcoded_content,
-                                            language,
-                                            line_numbers,
-                                        )?;
-                                        return Ok(());
-                                    }
-                                    Err(e) => {
-                                        warn!(
-                                            "Failed to transcode {} from {}: {}. Treating as binary.",
-                                            relative_path.display(),
-                                            enc.name(),
-                                            e
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    _ => {
-                        // Check if it's likely binary (contains null bytes)
-                        if slice.contains(&0) {
-                            warn!(
-                                "Detected binary file {} (contains null bytes). Skipping content.",
-                                relative_path.display()
-                            );
-                        } else {
-                            warn!(
-                                "Could not determine encoding for {}. Treating as binary.",
-                                relative_path.display()
-                            );
-                        }
-                    }
-                }
+    writeln!(output, "- Size: {} bytes", metadata.len())?;
+    writeln!(output, "- Modified: {}", modified_time)?;
+    writeln!(output)?;
 
-                // Fallback to binary file placeholder
-                writeln!(output, "```text")?;
-                writeln!(
-                    output,
-                    "<Binary file or unsupported encoding: {} bytes>",
-                    metadata.len()
-                )?;
-                writeln!(output, "```")?;
-                return Ok(());
-            }
+    // --- File Content --- //
+    let extension = file_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("text");
+    let language = language_for_extension(extension);
 
-            // Reset cursor and stream the content from the already-open file handle.
-            // (Previously opened a second fd via fs::read_to_string — wasting the seek)
-            if let Err(e) = file.seek(SeekFrom::Start(0)) {
-                warn!(
-                    "Could not reset file cursor for {}: {}. Skipping content.",
-                    relative_path.display(),
-                    e
-                );
-                writeln!(output, "```text")?;
-                writeln!(
-                    output,
-                    "<Could not read file content (e.g., binary file or permission error)>"
-                )?;
-                writeln!(output, "```")?;
-                return Ok(());
-            }
+    match read_file_content(file_path, relative_path, encoding_strategy, redactor) {
+        FileContent::Text(content) => {
+            let content = ts_config.prepare_content(content, relative_path);
 
-            // Read UTF-8 content from the same file descriptor
-            let mut content = String::new();
-            let content = match file.read_to_string(&mut content) {
-                Ok(_) => content,
-                Err(e) => {
-                    warn!(
-                        "Error reading file {}: {}. Output may be truncated.",
-                        relative_path.display(),
-                        e
-                    );
-                    writeln!(output, "```text")?;
-                    writeln!(output, "<Error reading file content>")?;
-                    writeln!(output, "```")?;
-                    return Ok(());
-                }
-            };
             // When --signatures is active, replace file content with signatures-only output
-            // ONLY for extensions that tree-sitter actually supports. Non-code files
-            // (Cargo.toml, README.md, .yaml, etc.) must always show full content.
-            let signatures_only =
-                ts_config.signatures && crate::tree_sitter::is_supported_extension(extension);
+            // ONLY for extensions that tree-sitter actually supports, and with an outline
+            // for large Markdown, TOML, YAML and JSON files. Other files (small configs,
+            // .txt, etc.) must always show full content.
+            let signatures_only = ts_config.signatures_only(extension, metadata.len());
 
             if !signatures_only {
                 // Note: Smart truncation (`truncate: "smart"`) indicates AST-boundary
@@ -552,25 +1272,27 @@ pub fn process_file(
             // Tree-sitter enrichment: signatures and/or structure
             write_tree_sitter_enrichment(output, &content, extension, ts_config)?;
         }
-        Err(e) => {
-            warn!(
-                "Could not open file {}: {}. Skipping content.",
-                relative_path.display(),
-                e
-            );
+        FileContent::Binary => {
+            // Fallback to binary file placeholder
             writeln!(output, "```text")?;
             writeln!(
                 output,
-                "<Could not read file content (e.g., binary file or permission error)>"
+                "<Binary file or unsupported encoding: {} bytes>",
+                metadata.len()
             )?;
             writeln!(output, "```")?;
         }
+        FileContent::Unreadable(placeholder) => {
+            writeln!(output, "```text")?;
+            writeln!(output, "{}", placeholder)?;
+            writeln!(output, "```")?;
+        }
     }
 
     Ok(())
 }
 
-/// Write tree-sitter enrichment (signatures, structure) after file content.
+/// Write tree-sitter enrichment (signatures, structure, outline) after file content.
 #[allow(unused_variables)]
 pub fn write_tree_sitter_enrichment(
     output: &mut impl Write,
@@ -584,7 +1306,7 @@ pub fn write_tree_sitter_enrichment(
 
     #[cfg(feature = "tree-sitter-base")]
     {
-        use crate::tree_sitter::language_support::Visibility;
+        use crate::tree_sitter::language_support::{SignatureDocs, Visibility};
 
         let vis_filter: Visibility = ts_config.visibility.parse().unwrap_or(Visibility::All);
 
@@ -600,10 +1322,12 @@ pub fn write_tree_sitter_enrichment(
         }
 
         if ts_config.signatures
-            && let Some(signatures) =
+            && let Some(mut signatures) =
                 crate::tree_sitter::extract_signatures_for_file(content, extension, vis_filter)
             && !signatures.is_empty()
         {
+            let docs: SignatureDocs = ts_config.signature_docs.parse().unwrap_or_default();
+            docs.apply(&mut signatures);
             let language = match extension {
                 "rs" => "rust",
                 "js" | "mjs" | "cjs" => "javascript",
@@ -613,6 +1337,12 @@ pub fn write_tree_sitter_enrichment(
                 "java" => "java",
                 "c" | "h" => "c",
                 "cpp" | "cxx" | "cc" | "hpp" | "hxx" | "hh" => "cpp",
+                "rb" | "rake" | "gemspec" => "ruby",
+                "cs" => "csharp",
+                "kt" | "kts" => "kotlin",
+                "sh" | "bash" => "bash",
+                "scala" | "sc" => "scala",
+                "hs" => "haskell",
                 _ => extension,
             };
             writeln!(output)?;
@@ -624,6 +1354,18 @@ pub fn write_tree_sitter_enrichment(
             );
             write!(output, "{}", formatted)?;
         }
+
+        if (ts_config.structure || ts_config.signatures_only(extension, content.len() as u64))
+            && let Some(outline) = crate::tree_sitter::extract_outline(content, extension)
+            && !outline.is_empty()
+        {
+            writeln!(output)?;
+            write!(
+                output,
+                "{}",
+                crate::tree_sitter::outline::format_outline_as_markdown(&outline)
+            )?;
+        }
     }
 
     #[cfg(not(feature = "tree-sitter-base"))]
@@ -701,12 +1443,23 @@ fn write_text_content(
     content: &str,
     language: &str,
     line_numbers: bool,
+) -> io::Result<()> {
+    write_text_content_from(output, content, language, line_numbers, 1)
+}
+
+/// Like [`write_text_content`], numbering lines from `first_line`.
+fn write_text_content_from(
+    output: &mut impl Write,
+    content: &str,
+    language: &str,
+    line_numbers: bool,
+    first_line: usize,
 ) -> io::Result<()> {
     writeln!(output, "```{}", language)?;
 
     if line_numbers {
         for (i, line) in content.lines().enumerate() {
-            writeln!(output, "{:>4} | {}", i + 1, line)?;
+            writeln!(output, "{:>4} | {}", first_line + i, line)?;
         }
     } else {
         output.write_all(content.as_bytes())?;
@@ -750,6 +1503,7 @@ mod tests {
             &mut output,
             false,
             None,
+            None,
             &TreeSitterConfig::default(),
         )
         .unwrap();
@@ -782,6 +1536,7 @@ mod tests {
             &mut output,
             false,
             None,
+            None,
             &TreeSitterConfig::default(),
         )
         .unwrap();
@@ -829,6 +1584,7 @@ mod tests {
             &mut output,
             true,
             None,
+            None,
             &TreeSitterConfig::default(),
         )
         .unwrap();
@@ -882,6 +1638,7 @@ mod tests {
             &mut output,
             false,
             None,
+            None,
             &TreeSitterConfig::default(),
         )
         .unwrap();
@@ -923,6 +1680,7 @@ mod tests {
             &mut output,
             false,
             Some("detect"),
+            None,
             &TreeSitterConfig::default(),
         )
         .unwrap();
@@ -962,6 +1720,7 @@ mod tests {
             &mut output,
             false,
             Some("strict"),
+            None,
             &TreeSitterConfig::default(),
         )
         .unwrap();
@@ -999,6 +1758,7 @@ mod tests {
             &mut output,
             false,
             Some("skip"),
+            None,
             &TreeSitterConfig::default(),
         )
         .unwrap();
@@ -1039,6 +1799,7 @@ mod tests {
             base_path,
             false,
             None,
+            None,
             None, // max_tokens
             &TreeSitterConfig::default(),
         );
@@ -1073,6 +1834,7 @@ mod tests {
             base_path,
             false,
             None,
+            None,
             None, // max_tokens
             &TreeSitterConfig::default(),
         );
@@ -1105,6 +1867,7 @@ mod tests {
             base_path,
             true,
             Some("strict"),
+            None,
             None, // max_tokens
             &TreeSitterConfig::default(),
         );
@@ -1237,6 +2000,7 @@ mod tests {
             &mut output,
             false,
             None,
+            None,
             &TreeSitterConfig::default(),
         );
         assert!(result.is_ok());
@@ -1275,6 +2039,7 @@ mod tests {
                 &mut output,
                 false,
                 None,
+                None,
                 &TreeSitterConfig::default(),
             )
             .unwrap();
@@ -1303,6 +2068,7 @@ mod tests {
             &mut output,
             false,
             None,
+            None,
             &TreeSitterConfig::default(),
         );
 
@@ -1325,6 +2091,7 @@ mod tests {
             &mut output,
             false,
             None,
+            None,
             &TreeSitterConfig::default(),
         )
         .unwrap();
@@ -1349,6 +2116,7 @@ mod tests {
             &mut output,
             false,
             None,
+            None,
             &TreeSitterConfig::default(),
         )
         .unwrap();
@@ -1392,6 +2160,7 @@ mod tests {
                 &mut output,
                 false,
                 None,
+                None,
                 &TreeSitterConfig::default(),
             )
             .unwrap();
@@ -1418,6 +2187,7 @@ mod tests {
             &mut output,
             false,
             Some("detect"),
+            None,
             &TreeSitterConfig::default(),
         )
         .unwrap();
@@ -1461,6 +2231,11 @@ mod tests {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
+            focus: None,
+            tokenizer: Tokenizer::Cl100k,
         };
 
         let result = write_tree_sitter_enrichment(&mut output, content, "rs", &ts_config);
@@ -1490,6 +2265,7 @@ mod tests {
             base_path,
             false,
             None,
+            None,
             Some(100),
             &TreeSitterConfig::default(),
         );
@@ -1499,6 +2275,275 @@ mod tests {
         assert!(content.contains("Token budget") || content.len() < 1000);
     }
 
+    #[test]
+    fn test_plan_budget_prefers_coverage_then_depth() {
+        use Inclusion::*;
+
+        // Every file gets its cheapest form first, then upgrades in order
+        let plan = plan_budget(&[100, 50, 30], &[Some(10), None, Some(5)], 120);
+        assert_eq!(plan, vec![Signatures, Full, Full]);
+
+        // A file that does not fit at all no longer hides the ones after it
+        let plan = plan_budget(&[500, 20, 30], &[None, None, None], 100);
+        assert_eq!(plan, vec![TreeOnly, Full, Full]);
+
+        // Signatures that are not cheaper than the full content are ignored
+        let plan = plan_budget(&[10], &[Some(10)], 100);
+        assert_eq!(plan, vec![Full]);
+    }
+
+    #[test]
+    fn test_generate_markdown_budget_keeps_files_after_oversized_one() {
+        let dir = tempdir().unwrap();
+        let base_path = dir.path();
+        let output_path = base_path.join("output.md");
+
+        fs::write(base_path.join("a_big.txt"), "big words ".repeat(5000)).unwrap();
+        fs::write(base_path.join("b_small.txt"), "small").unwrap();
+
+        let files = crate::file_utils::collect_files(base_path, &[], &[], &[]).unwrap();
+        let file_tree = crate::tree::build_file_tree(&files, base_path);
+
+        generate_markdown(
+            &output_path.to_string_lossy(),
+            "project",
+            &[],
+            &[],
+            &file_tree,
+            &files,
+            base_path,
+            false,
+            None,
+            None,
+            Some(400),
+            &TreeSitterConfig::default(),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output_path).unwrap();
+        assert!(content.contains("### File: `b_small.txt`"));
+        assert!(!content.contains("### File: `a_big.txt`"));
+        assert!(content.contains("0 files reduced to signatures, 1 files omitted"));
+        assert!(estimate_tokens(&content, Tokenizer::Cl100k) <= 400);
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-rust")]
+    fn test_generate_markdown_budget_reduces_to_signatures() {
+        let dir = tempdir().unwrap();
+        let base_path = dir.path();
+        let output_path = base_path.join("output.md");
+
+        let body = "    let x = 1;\n".repeat(200);
+        let source: String = (0..5)
+            .map(|i| format!("pub fn f{}() {{\n{}}}\n\n", i, body))
+            .collect();
+        fs::write(base_path.join("lib.rs"), source).unwrap();
+        fs::write(base_path.join("notes.txt"), "keep me").unwrap();
+
+        let files = crate::file_utils::collect_files(base_path, &[], &[], &[]).unwrap();
+        let file_tree = crate::tree::build_file_tree(&files, base_path);
+
+        generate_markdown(
+            &output_path.to_string_lossy(),
+            "project",
+            &[],
+            &[],
+            &file_tree,
+            &files,
+            base_path,
+            false,
+            None,
+            None,
+            Some(500),
+            &TreeSitterConfig::default(),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output_path).unwrap();
+        assert!(content.contains("### File: `lib.rs`"));
+        assert!(content.contains("**Signatures:**"));
+        assert!(content.contains("pub fn f4()"));
+        assert!(!content.contains("let x = 1;"));
+        assert!(content.contains("### File: `notes.txt`"));
+        assert!(content.contains("1 files reduced to signatures, 0 files omitted"));
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-rust")]
+    fn test_generate_markdown_focus_reduces_other_files() {
+        let dir = tempdir().unwrap();
+        let base_path = dir.path();
+        let output_path = base_path.join("output.md");
+
+        fs::create_dir_all(base_path.join("src")).unwrap();
+        fs::write(
+            base_path.join("src/focused.rs"),
+            "pub fn focused() -> u32 {\n    1 + 1\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            base_path.join("src/other.rs"),
+            "pub fn other() -> u32 {\n    2 + 2\n}\n",
+        )
+        .unwrap();
+        fs::write(base_path.join("notes.txt"), "plain notes").unwrap();
+
+        let files = crate::file_utils::collect_files(base_path, &[], &[], &[]).unwrap();
+        let file_tree = crate::tree::build_file_tree(&files, base_path);
+        let ts_config = TreeSitterConfig {
+            focus: Some(FocusSet::new(base_path, &["src/focused.rs".to_string()]).unwrap()),
+            ..TreeSitterConfig::default()
+        };
+
+        generate_markdown(
+            &output_path.to_string_lossy(),
+            "project",
+            &[],
+            &[],
+            &file_tree,
+            &files,
+            base_path,
+            false,
+            None,
+            None,
+            None,
+            &ts_config,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output_path).unwrap();
+        // The focused file keeps its body, the other one only its API surface
+        assert!(content.contains("1 + 1"));
+        assert!(!content.contains("2 + 2"));
+        assert!(content.contains("pub fn other() -> u32"));
+        assert_eq!(content.matches("**Signatures:**").count(), 1);
+        // Files tree-sitter does not understand are always shown in full
+        assert!(content.contains("plain notes"));
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-rust")]
+    fn test_process_file_strips_tests() {
+        let dir = tempdir().unwrap();
+        let base_path = dir.path();
+        let file_path = base_path.join("lib.rs");
+        fs::write(
+            &file_path,
+            "pub fn api() -> u32 {\n    1\n}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn works() {}\n}\n",
+        )
+        .unwrap();
+
+        let ts_config = TreeSitterConfig {
+            strip_tests: true,
+            structure: true,
+            ..TreeSitterConfig::default()
+        };
+        let mut output = Vec::new();
+        process_file(
+            base_path,
+            &file_path,
+            &mut output,
+            false,
+            None,
+            None,
+            &ts_config,
+        )
+        .unwrap();
+
+        let content = String::from_utf8(output).unwrap();
+        assert!(content.contains("```rust\npub fn api() -> u32 {\n    1\n}\n```"));
+        assert!(!content.contains("mod tests"));
+        // Structure is computed from what is shown
+        assert!(content.contains("1 functions"));
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-markdown")]
+    fn test_generate_markdown_outlines_large_documents() {
+        let dir = tempdir().unwrap();
+        let base_path = dir.path();
+        let output_path = base_path.join("output.md");
+
+        let section = format!("{}\n\n", "Long paragraph text. ".repeat(40));
+        let guide = format!(
+            "# Guide\n\n{}## Install\n\n{}## Usage\n\n{}",
+            section,
+            section.repeat(5),
+            section.repeat(5)
+        );
+        assert!(guide.len() as u64 >= OUTLINE_MIN_BYTES);
+        fs::write(base_path.join("guide.md"), guide).unwrap();
+        fs::write(base_path.join("short.md"), "# Short\n\nsmall body\n").unwrap();
+
+        let files = crate::file_utils::collect_files(base_path, &[], &[], &[]).unwrap();
+        let file_tree = crate::tree::build_file_tree(&files, base_path);
+        let ts_config = TreeSitterConfig {
+            signatures: true,
+            ..TreeSitterConfig::default()
+        };
+
+        generate_markdown(
+            &output_path.to_string_lossy(),
+            "project",
+            &[],
+            &[],
+            &file_tree,
+            &files,
+            base_path,
+            false,
+            None,
+            None,
+            None,
+            &ts_config,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output_path).unwrap();
+        // The large document is summarized by its heading tree
+        assert!(!content.contains("Long paragraph text."));
+        assert!(
+            content.contains("**Outline:**\n\n```text\n# Guide\n  ## Install\n  ## Usage\n```")
+        );
+        // Small ones are still shown in full
+        assert!(content.contains("small body"));
+        assert_eq!(content.matches("**Outline:**").count(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-rust")]
+    fn test_generate_symbols_markdown() {
+        let dir = tempdir().unwrap();
+        let base_path = dir.path();
+        let output_path = base_path.join("symbols.md");
+
+        fs::write(
+            base_path.join("cache.rs"),
+            "pub struct Cache;\n\nimpl Cache {\n    pub fn new() -> Self {\n        Cache\n    }\n\n    pub fn clear(&self) {}\n}\n",
+        )
+        .unwrap();
+        fs::write(base_path.join("other.rs"), "pub fn new() -> u32 { 0 }\n").unwrap();
+
+        let files = crate::file_utils::collect_files(base_path, &[], &[], &[]).unwrap();
+        let missing = generate_symbols_markdown(
+            &output_path.to_string_lossy(),
+            "project",
+            &files,
+            base_path,
+            &["Cache::new".to_string(), "Missing".to_string()],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(missing, vec!["Missing"]);
+        let content = fs::read_to_string(&output_path).unwrap();
+        assert!(content.contains("### `Cache::new` (function)"));
+        assert!(content.contains("`cache.rs:4-6`"));
+        assert!(content.contains("impl Cache {\n    pub fn new() -> Self {"));
+        assert!(!content.contains("clear"));
+        assert!(!content.contains("other.rs"));
+    }
+
     #[test]
     fn test_process_file_empty_file() {
         let dir = tempdir().unwrap();
@@ -1515,6 +2560,7 @@ mod tests {
             &mut output,
             false,
             None,
+            None,
             &TreeSitterConfig::default(),
         );
 
@@ -1541,6 +2587,7 @@ mod tests {
             &mut output,
             true,
             None,
+            None,
             &TreeSitterConfig::default(),
         );
 
@@ -1572,6 +2619,7 @@ mod tests {
             false,
             None,
             None,
+            None,
             &TreeSitterConfig::default(),
         );
 
@@ -1579,4 +2627,180 @@ mod tests {
         let content = fs::read_to_string(&output_path).unwrap();
         assert!(content.contains("Directory Structure Report"));
     }
+
+    #[test]
+    fn test_split_part_path_and_glob() {
+        assert_eq!(
+            split_part_path("context.md", 1),
+            PathBuf::from("context_part_001.md")
+        );
+        assert_eq!(
+            split_part_path("out/ctx.md", 12),
+            PathBuf::from("out/ctx_part_012.md")
+        );
+        assert_eq!(split_part_path("ctx", 2), PathBuf::from("ctx_part_002"));
+        assert_eq!(split_part_glob("out/ctx.md"), "out/ctx_part_*.md");
+    }
+
+    #[test]
+    fn test_split_point_prefers_line_breaks() {
+        let source = "line one\nline two\nline three\n";
+        assert_eq!(split_point(source, 12, "txt", false), 9);
+        assert_eq!(split_point(source, 100, "txt", false), source.len());
+        // No line break in range: cut at a character boundary
+        assert_eq!(split_point("ééééé", 3, "txt", false), 2);
+        // Always makes progress
+        assert_eq!(split_point("é", 1, "txt", false), 2);
+    }
+
+    #[test]
+    fn test_generate_markdown_split_packs_files_into_parts() {
+        let dir = tempdir().unwrap();
+        let base_path = dir.path();
+        let out_dir = tempdir().unwrap();
+        let output_path = out_dir.path().join("context.md");
+
+        for i in 0..6 {
+            fs::write(
+                base_path.join(format!("file{}.txt", i)),
+                format!("{} ", i).repeat(100),
+            )
+            .unwrap();
+        }
+
+        let files = crate::file_utils::collect_files(base_path, &[], &[], &[]).unwrap();
+        let file_tree = crate::tree::build_file_tree(&files, base_path);
+
+        let parts = generate_markdown_split(
+            &output_path.to_string_lossy(),
+            "project",
+            &[],
+            &[],
+            &file_tree,
+            &files,
+            base_path,
+            false,
+            None,
+            None,
+            400,
+            &TreeSitterConfig::default(),
+        )
+        .unwrap();
+
+        assert!(parts.len() > 1);
+        assert!(!output_path.exists());
+        let total = parts.len();
+        let mut seen = 0;
+        for (i, part) in parts.iter().enumerate() {
+            assert_eq!(
+                *part,
+                split_part_path(&output_path.to_string_lossy(), i + 1)
+            );
+            let content = fs::read_to_string(part).unwrap();
+            assert!(content.contains(&format!("Part {} of {}", i + 1, total)));
+            assert!(content.contains("## File Tree Structure"));
+            assert!(estimate_chunk_tokens(content.as_bytes(), Tokenizer::Cl100k) <= 400);
+            // Files that fit the budget are never split across parts
+            assert!(!content.contains("(section "));
+            seen += content.matches("### File:").count();
+        }
+        assert_eq!(seen, 6);
+    }
+
+    #[test]
+    fn test_generate_markdown_split_small_budget() {
+        let dir = tempdir().unwrap();
+        let base_path = dir.path();
+        let out_dir = tempdir().unwrap();
+        let output_path = out_dir.path().join("context.md");
+
+        fs::create_dir(base_path.join("src")).unwrap();
+        for i in 0..5 {
+            fs::write(
+                base_path.join(format!("src/module_{}.rs", i)),
+                "fn f() {}\n",
+            )
+            .unwrap();
+        }
+        let big: String = (1..=400).map(|i| format!("// line {}\n", i)).collect();
+        fs::write(base_path.join("src/lib.rs"), big).unwrap();
+
+        let files = crate::file_utils::collect_files(base_path, &[], &[], &[]).unwrap();
+        let file_tree = crate::tree::build_file_tree(&files, base_path);
+        let split = |max_tokens| {
+            generate_markdown_split(
+                &output_path.to_string_lossy(),
+                "project",
+                &[],
+                &[],
+                &file_tree,
+                &files,
+                base_path,
+                false,
+                None,
+                None,
+                max_tokens,
+                &TreeSitterConfig::default(),
+            )
+        };
+
+        // No room for content after the header and tree: refuse, write nothing
+        let err = split(120).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("--max-tokens 120 is too small"));
+        assert_eq!(fs::read_dir(out_dir.path()).unwrap().count(), 0);
+
+        // Just enough room: every section carries real content
+        let parts = split(200).unwrap();
+        for part in &parts {
+            let text = fs::read_to_string(part).unwrap();
+            assert!(estimate_chunk_tokens(text.as_bytes(), Tokenizer::Cl100k) <= 200);
+            if text.contains("(section ") {
+                assert!(text.matches("// line ").count() >= 3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_markdown_split_cuts_oversized_file() {
+        let dir = tempdir().unwrap();
+        let base_path = dir.path();
+        let out_dir = tempdir().unwrap();
+        let output_path = out_dir.path().join("context.md");
+
+        let content: String = (1..=400).map(|i| format!("line {}\n", i)).collect();
+        fs::write(base_path.join("big.txt"), &content).unwrap();
+
+        let files = crate::file_utils::collect_files(base_path, &[], &[], &[]).unwrap();
+        let file_tree = crate::tree::build_file_tree(&files, base_path);
+
+        let parts = generate_markdown_split(
+            &output_path.to_string_lossy(),
+            "project",
+            &[],
+            &[],
+            &file_tree,
+            &files,
+            base_path,
+            true,
+            None,
+            None,
+            600,
+            &TreeSitterConfig::default(),
+        )
+        .unwrap();
+
+        assert!(parts.len() > 1);
+        let mut numbered_lines = 0;
+        for part in &parts {
+            let text = fs::read_to_string(part).unwrap();
+            assert!(estimate_chunk_tokens(text.as_bytes(), Tokenizer::Cl100k) <= 600);
+            assert!(text.contains("### File: `big.txt` (section "));
+            numbered_lines += text.lines().filter(|l| l.contains(" | line ")).count();
+        }
+        // Every line appears exactly once, with continuous line numbers
+        assert_eq!(numbered_lines, 400);
+        let last = fs::read_to_string(parts.last().unwrap()).unwrap();
+        assert!(last.contains(" 400 | line 400"));
+    }
 }
@@ -7,12 +7,20 @@
 use chrono::Utc;
 use ignore::DirEntry;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
+use crate::api_changes::{ApiChange, compare_definitions};
 use crate::config::Config;
-use crate::diff::{PerFileDiff, PerFileStatus, diff_file_contents};
+use crate::diff::{PerFileDiff, PerFileStatus, diff_file_contents, diff_renamed_file, similarity};
+use crate::file_utils::PathSelector;
+use crate::git::Repository;
+use crate::redact::Redactor;
+
+/// Default minimum similarity for pairing a removed file with an added one
+/// as a rename when their content differs.
+pub const DEFAULT_RENAME_SIMILARITY: f32 = 0.5;
 
 /// Complete state representation of a project at a point in time
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -62,6 +70,8 @@ pub struct StateComparison {
     pub file_diffs: Vec<PerFileDiff>,
     /// Summary of changes
     pub summary: ChangeSummary,
+    /// Definitions added, removed or changed in each modified or renamed file
+    pub api_changes: BTreeMap<PathBuf, Vec<ApiChange>>,
 }
 
 /// Summary of changes between two states
@@ -73,10 +83,23 @@ pub struct ChangeSummary {
     pub removed: Vec<PathBuf>,
     /// Files that were modified
     pub modified: Vec<PathBuf>,
+    /// Files that were moved, possibly with edits
+    pub renamed: Vec<RenamedFile>,
     /// Total number of changed files
     pub total_changes: usize,
 }
 
+/// A file that was moved between two states
+#[derive(Debug, Clone)]
+pub struct RenamedFile {
+    /// Path in the previous state
+    pub from: PathBuf,
+    /// Path in the current state
+    pub to: PathBuf,
+    /// Similarity of the old and new content (1.0 when identical)
+    pub similarity: f32,
+}
+
 impl ProjectState {
     /// Create a new project state from collected files
     pub fn from_files(
@@ -84,6 +107,7 @@ impl ProjectState {
         base_path: &Path,
         config: &Config,
         line_numbers: bool,
+        redactor: Option<&Redactor>,
     ) -> std::io::Result<Self> {
         let mut file_states = BTreeMap::new();
 
@@ -107,7 +131,10 @@ impl ProjectState {
                         .unwrap_or_else(|| entry_path.to_path_buf())
                 });
 
-            let file_state = FileState::from_path(entry_path)?;
+            let mut file_state = FileState::from_path(entry_path)?;
+            if let Some(redactor) = redactor {
+                file_state.redact(redactor, &relative_path);
+            }
             file_states.insert(relative_path, file_state);
         }
 
@@ -146,11 +173,72 @@ impl ProjectState {
         })
     }
 
-    /// Compare this state with a previous state
+    /// Build the state of the same selection as it was where the current
+    /// branch forked from a git revision (the merge-base of `HEAD` and
+    /// `revision`).
+    ///
+    /// Includes every file of `current` that exists at the merge-base, plus
+    /// files that existed there but have since been deleted and would pass
+    /// `selector`. Comparing `current` against the result yields the changes
+    /// a reviewer sees in the pull request (`git diff <revision>...`), without
+    /// any cached state; commits made only on `revision` are left out.
+    pub fn from_git_revision(
+        current: &ProjectState,
+        base_path: &Path,
+        revision: &str,
+        selector: &PathSelector,
+        redactor: Option<&Redactor>,
+    ) -> std::io::Result<Self> {
+        let repo = Repository::discover(base_path)?;
+        let commit = repo.fork_point(revision)?;
+        let canonical_base = base_path.canonicalize()?;
+        // Blob paths are relative to the work tree root, which may be an
+        // ancestor of base_path
+        let base_in_repo = canonical_base
+            .strip_prefix(repo.work_dir())
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let mut file_states = BTreeMap::new();
+        for (path, entry) in repo.commit_blobs(&commit)? {
+            let Ok(relative_path) = path.strip_prefix(&base_in_repo) else {
+                continue;
+            };
+            let selected = current.files.contains_key(relative_path)
+                || (!canonical_base.join(relative_path).exists()
+                    && selector.is_selected(relative_path));
+            if !selected {
+                continue;
+            }
+
+            let mut file_state = FileState::from_bytes(repo.read_blob(&entry.id)?);
+            if let Some(redactor) = redactor {
+                file_state.redact(redactor, relative_path);
+            }
+            file_states.insert(relative_path.to_path_buf(), file_state);
+        }
+
+        Ok(ProjectState {
+            timestamp: current.timestamp.clone(),
+            config_hash: current.config_hash.clone(),
+            metadata: ProjectMetadata {
+                file_count: file_states.len(),
+                ..current.metadata.clone()
+            },
+            files: file_states,
+        })
+    }
+
+    /// Compare this state with a previous state.
+    ///
+    /// A removed file and an added file are reported as one rename when their
+    /// content hashes match, or when their content is at least
+    /// `rename_similarity` similar (default [`DEFAULT_RENAME_SIMILARITY`]).
     pub fn compare_with(
         &self,
         previous: &ProjectState,
         diff_context_lines: Option<usize>,
+        rename_similarity: Option<f32>,
     ) -> StateComparison {
         // Convert file states to content maps for diff_file_contents
         let previous_content: std::collections::HashMap<String, String> = previous
@@ -166,13 +254,40 @@ impl ProjectState {
             .collect();
 
         // Generate per-file diffs
-        let file_diffs = diff_file_contents(
+        let mut file_diffs = diff_file_contents(
             &previous_content,
             &current_content,
             true,
             diff_context_lines,
         );
 
+        // Replace each Removed + Added pair that is really a move with a
+        // single Renamed diff carrying only the delta
+        let renames = self.detect_renames(
+            previous,
+            &file_diffs,
+            rename_similarity.unwrap_or(DEFAULT_RENAME_SIMILARITY),
+        );
+        if !renames.is_empty() {
+            let moved: HashSet<String> = renames
+                .iter()
+                .flat_map(|rename| [&rename.from, &rename.to])
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            file_diffs.retain(|diff| !moved.contains(&diff.path));
+            for rename in &renames {
+                file_diffs.push(diff_renamed_file(
+                    &rename.from.to_string_lossy(),
+                    &rename.to.to_string_lossy(),
+                    &previous.files[&rename.from].content,
+                    &self.files[&rename.to].content,
+                    rename.similarity,
+                    diff_context_lines,
+                ));
+            }
+            file_diffs.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+
         // Generate summary
         let mut added = Vec::new();
         let mut removed = Vec::new();
@@ -184,23 +299,139 @@ impl ProjectState {
                 PerFileStatus::Added => added.push(path),
                 PerFileStatus::Removed => removed.push(path),
                 PerFileStatus::Modified => modified.push(path),
-                PerFileStatus::Unchanged => {}
+                PerFileStatus::Unchanged | PerFileStatus::Renamed { .. } => {}
             }
         }
 
         let summary = ChangeSummary {
-            total_changes: added.len() + removed.len() + modified.len(),
+            total_changes: added.len() + removed.len() + modified.len() + renames.len(),
             added,
             removed,
             modified,
+            renamed: renames,
         };
 
+        let api_changes = self.api_changes(previous, &file_diffs);
+
         StateComparison {
             file_diffs,
             summary,
+            api_changes,
         }
     }
 
+    /// Symbol-level changes of the modified and renamed files, for those
+    /// with any
+    fn api_changes(
+        &self,
+        previous: &ProjectState,
+        file_diffs: &[PerFileDiff],
+    ) -> BTreeMap<PathBuf, Vec<ApiChange>> {
+        file_diffs
+            .iter()
+            .filter_map(|diff| {
+                let path = PathBuf::from(&diff.path);
+                let old_path = match &diff.status {
+                    PerFileStatus::Modified => path.clone(),
+                    PerFileStatus::Renamed { from, .. } if !diff.diff.is_empty() => {
+                        PathBuf::from(from)
+                    }
+                    _ => return None,
+                };
+                let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+                let changes = compare_definitions(
+                    &previous.files.get(&old_path)?.content,
+                    &self.files.get(&path)?.content,
+                    extension,
+                );
+                (!changes.is_empty()).then_some((path, changes))
+            })
+            .collect()
+    }
+
+    /// Pair removed files with added ones: first by identical content hash,
+    /// then by content similarity, best matches first.
+    fn detect_renames(
+        &self,
+        previous: &ProjectState,
+        file_diffs: &[PerFileDiff],
+        threshold: f32,
+    ) -> Vec<RenamedFile> {
+        let paths_with = |status: PerFileStatus| -> Vec<PathBuf> {
+            file_diffs
+                .iter()
+                .filter(|diff| diff.status == status)
+                .map(|diff| PathBuf::from(&diff.path))
+                .collect()
+        };
+        // Empty files all look alike, so they are never paired
+        let mut removed: Vec<PathBuf> = paths_with(PerFileStatus::Removed)
+            .into_iter()
+            .filter(|path| !previous.files[path].content.is_empty())
+            .collect();
+        let added: Vec<PathBuf> = paths_with(PerFileStatus::Added)
+            .into_iter()
+            .filter(|path| !self.files[path].content.is_empty())
+            .collect();
+
+        let mut renames = Vec::new();
+        let mut unmatched = Vec::new();
+        for to in added {
+            let hash = &self.files[&to].content_hash;
+            match removed
+                .iter()
+                .position(|from| &previous.files[from].content_hash == hash)
+            {
+                Some(index) => renames.push(RenamedFile {
+                    from: removed.remove(index),
+                    to,
+                    similarity: 1.0,
+                }),
+                None => unmatched.push(to),
+            }
+        }
+
+        let mut candidates = Vec::new();
+        for to in &unmatched {
+            let new_content = &self.files[to].content;
+            for from in &removed {
+                let old_content = &previous.files[from].content;
+                // Files of very different sizes cannot reach the threshold;
+                // skip them before paying for a full diff
+                let (shorter, longer) = if old_content.len() < new_content.len() {
+                    (old_content.len(), new_content.len())
+                } else {
+                    (new_content.len(), old_content.len())
+                };
+                if (shorter as f32) < longer as f32 * threshold {
+                    continue;
+                }
+                let score = similarity(old_content, new_content);
+                if score >= threshold {
+                    candidates.push((score, from, to));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.2.cmp(b.2)));
+
+        let mut paired = HashSet::new();
+        for (score, from, to) in candidates {
+            if paired.contains(from) || paired.contains(to) {
+                continue;
+            }
+            paired.insert(from);
+            paired.insert(to);
+            renames.push(RenamedFile {
+                from: from.clone(),
+                to: to.clone(),
+                similarity: score,
+            });
+        }
+
+        renames.sort_by(|a, b| a.to.cmp(&b.to));
+        renames
+    }
+
     /// Check if this state has any content changes compared to another
     pub fn has_changes(&self, other: &ProjectState) -> bool {
         if self.files.len() != other.files.len() {
@@ -245,6 +476,15 @@ impl ProjectState {
             config.max_tokens,
         ));
 
+        // Redaction changes stored content, so a different setup must not be
+        // diffed against the old state
+        if let Some(ref redact) = config.redact {
+            config_str.push_str(&format!(
+                "|{:?}|{:?}|{:?}|{:?}",
+                redact.enabled, redact.allow, redact.deny, redact.disable
+            ));
+        }
+
         let hash = xxhash_rust::xxh3::xxh3_64(config_str.as_bytes());
         format!("{:x}", hash)
     }
@@ -278,6 +518,31 @@ impl FileState {
             content_hash,
         })
     }
+
+    /// Mask likely secrets in the content of the file at `path`, keeping the
+    /// hash in sync
+    pub fn redact(&mut self, redactor: &Redactor, path: &Path) {
+        self.content = redactor.redact_file(&self.content, path);
+        self.content_hash = format!(
+            "{:016x}",
+            xxhash_rust::xxh3::xxh3_64(self.content.as_bytes())
+        );
+    }
+
+    /// Create a file state from raw content that has no file on disk (e.g. a git blob)
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        let size = bytes.len() as u64;
+        let content =
+            String::from_utf8(bytes).unwrap_or_else(|_| format!("<Binary file - {} bytes>", size));
+        let content_hash = format!("{:016x}", xxhash_rust::xxh3::xxh3_64(content.as_bytes()));
+
+        FileState {
+            content,
+            size,
+            modified: SystemTime::UNIX_EPOCH,
+            content_hash,
+        }
+    }
 }
 
 impl ChangeSummary {
@@ -307,6 +572,23 @@ impl ChangeSummary {
             output.push_str(&format!("- Modified: `{}`\n", path.display()));
         }
 
+        for rename in &self.renamed {
+            if rename.similarity >= 1.0 {
+                output.push_str(&format!(
+                    "- Renamed: `{}` → `{}`\n",
+                    rename.from.display(),
+                    rename.to.display()
+                ));
+            } else {
+                output.push_str(&format!(
+                    "- Renamed: `{}` → `{}` ({}% similar)\n",
+                    rename.from.display(),
+                    rename.to.display(),
+                    (rename.similarity * 100.0).floor()
+                ));
+            }
+        }
+
         output.push('\n');
         output
     }
@@ -394,7 +676,7 @@ mod tests {
             },
         };
 
-        let comparison = state2.compare_with(&state1, None);
+        let comparison = state2.compare_with(&state1, None, None);
 
         assert_eq!(comparison.summary.added.len(), 1);
         assert_eq!(comparison.summary.modified.len(), 1);
@@ -419,6 +701,7 @@ mod tests {
             added: vec![PathBuf::from("new.txt")],
             removed: vec![PathBuf::from("old.txt")],
             modified: vec![PathBuf::from("changed.txt")],
+            renamed: vec![],
             total_changes: 3,
         };
 
@@ -631,6 +914,7 @@ mod tests {
             added: vec![],
             removed: vec![],
             modified: vec![],
+            renamed: vec![],
             total_changes: 0,
         };
 
@@ -658,7 +942,7 @@ mod tests {
             ..Default::default()
         };
 
-        let state = ProjectState::from_files(&entries, base_path, &config, true).unwrap();
+        let state = ProjectState::from_files(&entries, base_path, &config, true, None).unwrap();
 
         assert_eq!(state.files.len(), 2);
         assert_eq!(state.metadata.file_count, 2);
@@ -684,7 +968,7 @@ mod tests {
 
         let entries = vec![create_mock_dir_entry(&base_path.join("test.txt"))];
         let config = Config::default();
-        let state = ProjectState::from_files(&entries, base_path, &config, false).unwrap();
+        let state = ProjectState::from_files(&entries, base_path, &config, false, None).unwrap();
 
         assert!(state.timestamp.contains("UTC"));
         let year = chrono::Utc::now().format("%Y").to_string();
@@ -710,7 +994,7 @@ mod tests {
             ..Default::default()
         };
 
-        let state = ProjectState::from_files(&entries, base_path, &config, true).unwrap();
+        let state = ProjectState::from_files(&entries, base_path, &config, true, None).unwrap();
 
         assert_eq!(state.metadata.filters, vec!["rs"]);
         assert_eq!(state.metadata.ignores, vec!["target"]);
@@ -726,7 +1010,7 @@ mod tests {
 
         let entries = vec![create_mock_dir_entry(&base_path.join("test.txt"))];
         let config = Config::default();
-        let state = ProjectState::from_files(&entries, base_path, &config, false).unwrap();
+        let state = ProjectState::from_files(&entries, base_path, &config, false, None).unwrap();
 
         assert!(!state.metadata.project_name.is_empty());
         assert_ne!(state.metadata.project_name, "unknown");
@@ -738,6 +1022,7 @@ mod tests {
             added: vec![],
             removed: vec![],
             modified: vec![],
+            renamed: vec![],
             total_changes: 0,
         };
 
@@ -797,7 +1082,7 @@ mod tests {
             },
         };
 
-        let comparison = state2.compare_with(&state1, None);
+        let comparison = state2.compare_with(&state1, None, None);
         assert_eq!(comparison.summary.removed.len(), 1);
     }
 
@@ -843,6 +1128,116 @@ mod tests {
         assert_ne!(hash1, hash2);
     }
 
+    fn state_with(files: &[(&str, &str)]) -> ProjectState {
+        ProjectState {
+            timestamp: "2023-01-01T00:00:00Z".to_string(),
+            config_hash: "hash".to_string(),
+            files: files
+                .iter()
+                .map(|(path, content)| {
+                    (
+                        PathBuf::from(path),
+                        FileState::from_bytes(content.as_bytes().to_vec()),
+                    )
+                })
+                .collect(),
+            metadata: ProjectMetadata {
+                project_name: "test".to_string(),
+                file_count: files.len(),
+                filters: vec![],
+                ignores: vec![],
+                line_numbers: false,
+            },
+        }
+    }
+
+    #[test]
+    fn test_comparison_detects_renames() {
+        let body = "fn one() {}\nfn two() {}\nfn three() {}\nfn four() {}\n";
+        let previous = state_with(&[
+            ("src/old.rs", body),
+            ("src/util.rs", "pub fn helper() {}\n"),
+            ("gone.txt", "unrelated content\n"),
+        ]);
+        let current = state_with(&[
+            ("src/new.rs", body),
+            (
+                "src/helpers/util.rs",
+                "pub fn helper() {}\npub fn other() {}\n",
+            ),
+            ("fresh.txt", "something else entirely\n"),
+        ]);
+
+        let comparison = current.compare_with(&previous, None, None);
+        let summary = &comparison.summary;
+        assert_eq!(summary.added, vec![PathBuf::from("fresh.txt")]);
+        assert_eq!(summary.removed, vec![PathBuf::from("gone.txt")]);
+        assert_eq!(summary.renamed.len(), 2);
+        assert_eq!(summary.total_changes, 4);
+
+        let exact = &summary.renamed[1];
+        assert_eq!(exact.from, PathBuf::from("src/old.rs"));
+        assert_eq!(exact.to, PathBuf::from("src/new.rs"));
+        assert_eq!(exact.similarity, 1.0);
+        let fuzzy = &summary.renamed[0];
+        assert_eq!(fuzzy.from, PathBuf::from("src/util.rs"));
+        assert!(fuzzy.similarity < 1.0);
+
+        // An exact move carries no diff; an edited one only its delta
+        let diff_of = |path: &str| {
+            comparison
+                .file_diffs
+                .iter()
+                .find(|diff| diff.path == path)
+                .unwrap()
+        };
+        assert!(diff_of("src/new.rs").diff.is_empty());
+        let delta = &diff_of("src/helpers/util.rs").diff;
+        assert!(delta.contains("+ pub fn other() {}"));
+        assert!(!delta.contains("+ pub fn helper() {}"));
+        assert_eq!(comparison.file_diffs.len(), 4);
+
+        let markdown = summary.to_markdown();
+        assert!(markdown.contains("- Renamed: `src/old.rs` → `src/new.rs`\n"));
+        assert!(
+            markdown.contains("- Renamed: `src/util.rs` → `src/helpers/util.rs` (66% similar)")
+        );
+
+        // Only identical files are paired at 1.0
+        let strict = current.compare_with(&previous, None, Some(1.0));
+        assert_eq!(strict.summary.renamed.len(), 1);
+        assert_eq!(strict.summary.added.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-rust")]
+    fn test_comparison_reports_api_changes() {
+        let previous = state_with(&[
+            (
+                "src/lib.rs",
+                "pub fn run() {}\n\nfn helper() -> u8 {\n    1\n}\n",
+            ),
+            ("notes.txt", "one\n"),
+        ]);
+        let current = state_with(&[
+            (
+                "src/lib.rs",
+                "pub fn run(verbose: bool) {}\n\nfn helper() -> u8 {\n    2\n}\n",
+            ),
+            ("notes.txt", "two\n"),
+        ]);
+
+        let comparison = current.compare_with(&previous, None, None);
+        assert_eq!(comparison.api_changes.len(), 1);
+        let changes = &comparison.api_changes[&PathBuf::from("src/lib.rs")];
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].name, "run");
+        assert_eq!(
+            changes[1].change,
+            crate::api_changes::ApiChangeKind::BodyChanged
+        );
+    }
+
     // Helper function to create a mock DirEntry for testing
     fn create_mock_dir_entry(path: &std::path::Path) -> ignore::DirEntry {
         let walker = ignore::WalkBuilder::new(path.parent().unwrap());
@@ -1,37 +1,199 @@
 use ignore::DirEntry;
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
+
+use crate::markdown::TreeSitterConfig;
 /// Token counting utilities for estimating LLM token usage
-use tiktoken_rs::{CoreBPE, cl100k_base};
+use tiktoken_rs::{CoreBPE, cl100k_base, o200k_base, p50k_base};
+
+// Initialize each BPE once, on first use, and reuse it
+static CL100K: Lazy<CoreBPE> = Lazy::new(|| cl100k_base().unwrap());
+static O200K: Lazy<CoreBPE> = Lazy::new(|| o200k_base().unwrap());
+static P50K: Lazy<CoreBPE> = Lazy::new(|| p50k_base().unwrap());
+
+/// Characters per token assumed by the bare `chars` estimator.
+const DEFAULT_CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Longest slice handed to a BPE at once. Byte-pair merging is quadratic in
+/// the length of a single pre-token, so minified or base64 content is
+/// encoded in slices, cut just before whitespace where possible.
+const MAX_BPE_SLICE: usize = 2048;
+
+/// How token counts are estimated.
+///
+/// The BPE variants count exactly what the OpenAI encodings produce. For model
+/// families without a public tokenizer, `CharRatio` divides the character
+/// count by a characters-per-token ratio (`chars:3.5`).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Tokenizer {
+    /// `cl100k_base` (GPT-4, GPT-3.5)
+    #[default]
+    Cl100k,
+    /// `o200k_base` (GPT-4o and later)
+    O200k,
+    /// `p50k_base` (Codex, text-davinci)
+    P50k,
+    /// Estimate of one token per this many characters
+    CharRatio(f64),
+}
+
+impl Tokenizer {
+    /// Number of tokens in `text`.
+    pub fn count(&self, text: &str) -> usize {
+        match self.bpe() {
+            Ok(bpe) => bpe_slices(text)
+                .map(|slice| bpe.encode_with_special_tokens(slice).len())
+                .sum(),
+            Err(ratio) => (text.chars().count() as f64 / ratio).ceil() as usize,
+        }
+    }
+
+    /// Length in bytes of the longest prefix of `text` that fits in
+    /// `max_tokens`, always on a character boundary.
+    pub fn prefix_len(&self, text: &str, max_tokens: usize) -> usize {
+        let bpe = match self.bpe() {
+            Ok(bpe) => bpe,
+            Err(ratio) => {
+                let max_chars = (max_tokens as f64 * ratio) as usize;
+                return text
+                    .char_indices()
+                    .nth(max_chars)
+                    .map(|(i, _)| i)
+                    .unwrap_or(text.len());
+            }
+        };
+
+        let mut used = 0;
+        let mut offset = 0;
+        for slice in bpe_slices(text) {
+            let tokens = bpe.encode_with_special_tokens(slice);
+            if used + tokens.len() <= max_tokens {
+                used += tokens.len();
+                offset += slice.len();
+                continue;
+            }
+            offset += bpe
+                ._decode_native_and_split(tokens.into_iter().take(max_tokens - used).collect())
+                .map(|bytes| bytes.len())
+                .sum::<usize>();
+            while !text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+            return offset;
+        }
+        text.len()
+    }
+
+    /// The BPE behind this tokenizer, or the characters-per-token ratio.
+    fn bpe(&self) -> Result<&'static CoreBPE, f64> {
+        match self {
+            Tokenizer::Cl100k => Ok(&CL100K),
+            Tokenizer::O200k => Ok(&O200K),
+            Tokenizer::P50k => Ok(&P50K),
+            Tokenizer::CharRatio(ratio) => Err(*ratio),
+        }
+    }
+}
 
-// Initialize the tokenizer once and reuse it
-static TOKENIZER: Lazy<CoreBPE> = Lazy::new(|| cl100k_base().unwrap());
+/// Splits `text` into slices of at most `MAX_BPE_SLICE` bytes, preferring to
+/// cut just before whitespace so pre-tokens stay intact.
+fn bpe_slices(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let mut cut = rest.len().min(MAX_BPE_SLICE);
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        if cut < rest.len()
+            && let Some(space) = rest[..cut].rfind(char::is_whitespace).filter(|&i| i > 0)
+        {
+            cut = space;
+        }
+        let (slice, tail) = rest.split_at(cut);
+        rest = tail;
+        Some(slice)
+    })
+}
 
-/// Estimates the number of tokens in a text string using a real tokenizer
-pub fn estimate_tokens(text: &str) -> usize {
-    TOKENIZER.encode_with_special_tokens(text).len()
+impl FromStr for Tokenizer {
+    type Err = String;
+
+    /// Parses `cl100k`, `o200k`, `p50k` (optionally with a `_base` suffix),
+    /// `chars`, or `chars:<characters per token>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cl100k" | "cl100k_base" => Ok(Tokenizer::Cl100k),
+            "o200k" | "o200k_base" => Ok(Tokenizer::O200k),
+            "p50k" | "p50k_base" => Ok(Tokenizer::P50k),
+            "chars" => Ok(Tokenizer::CharRatio(DEFAULT_CHARS_PER_TOKEN)),
+            other => other
+                .strip_prefix("chars:")
+                .and_then(|ratio| ratio.parse::<f64>().ok())
+                .filter(|ratio| ratio.is_finite() && *ratio > 0.0)
+                .map(Tokenizer::CharRatio)
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown tokenizer '{}' (expected \"cl100k\", \"o200k\", \"p50k\", \"chars\" or \"chars:<ratio>\")",
+                        s
+                    )
+                }),
+        }
+    }
+}
+
+impl fmt::Display for Tokenizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tokenizer::Cl100k => write!(f, "cl100k"),
+            Tokenizer::O200k => write!(f, "o200k"),
+            Tokenizer::P50k => write!(f, "p50k"),
+            Tokenizer::CharRatio(ratio) => write!(f, "chars:{}", ratio),
+        }
+    }
 }
 
-/// Counts the tokens that would be generated for a file
-pub fn count_file_tokens(base_path: &Path, entry: &DirEntry, line_numbers: bool) -> usize {
+/// Estimates the number of tokens in a text string using `tokenizer`
+pub fn estimate_tokens(text: &str, tokenizer: Tokenizer) -> usize {
+    tokenizer.count(text)
+}
+
+/// Counts the tokens that would be generated for a file, after the content
+/// changes `ts_config` makes (`--strip-tests`, `--compact`), with its tokenizer
+pub fn count_file_tokens(
+    base_path: &Path,
+    entry: &DirEntry,
+    line_numbers: bool,
+    ts_config: &TreeSitterConfig,
+) -> usize {
     let file_path = entry.path();
     let relative_path = file_path.strip_prefix(base_path).unwrap_or(file_path);
+    let tokenizer = ts_config.tokenizer;
 
     // Start with tokens for the file header (path, size, modified time)
-    let mut token_count = estimate_tokens(&format!(
-        "\n### File: `{}`\n\n- Size: {} bytes\n- Modified: {}\n\n",
-        relative_path.display(),
-        entry.metadata().map(|m| m.len()).unwrap_or(0),
-        "Unknown"
-    )); // Using "Unknown" as placeholder for modified time in estimation
+    let mut token_count = estimate_tokens(
+        &format!(
+            "\n### File: `{}`\n\n- Size: {} bytes\n- Modified: {}\n\n",
+            relative_path.display(),
+            entry.metadata().map(|m| m.len()).unwrap_or(0),
+            "Unknown"
+        ),
+        tokenizer,
+    ); // Using "Unknown" as placeholder for modified time in estimation
 
     // Add tokens for the code fences
-    token_count += estimate_tokens("```\n```");
+    token_count += estimate_tokens("```\n```", tokenizer);
 
     // Try to read file content
     if let Ok(content) = fs::read_to_string(file_path) {
+        let content = ts_config.prepare_content(content, relative_path);
         if line_numbers {
             // When line numbers are enabled, we add the line number prefix to each line
             let lines_with_numbers: String = content
@@ -39,9 +201,9 @@ pub fn count_file_tokens(base_path: &Path, entry: &DirEntry, line_numbers: bool)
                 .enumerate()
                 .map(|(i, line)| format!("{:>4} | {}\n", i + 1, line))
                 .collect();
-            token_count += estimate_tokens(&lines_with_numbers);
+            token_count += estimate_tokens(&lines_with_numbers, tokenizer);
         } else {
-            token_count += estimate_tokens(&content);
+            token_count += estimate_tokens(&content, tokenizer);
         }
     }
 
@@ -49,7 +211,11 @@ pub fn count_file_tokens(base_path: &Path, entry: &DirEntry, line_numbers: bool)
 }
 
 /// Counts the tokens that would be generated for the entire file tree section
-pub fn count_tree_tokens(tree: &BTreeMap<String, crate::tree::FileNode>, depth: usize) -> usize {
+pub fn count_tree_tokens(
+    tree: &BTreeMap<String, crate::tree::FileNode>,
+    depth: usize,
+    tokenizer: Tokenizer,
+) -> usize {
     let mut token_count = 0;
 
     // Add tokens for indentation
@@ -58,11 +224,11 @@ pub fn count_tree_tokens(tree: &BTreeMap<String, crate::tree::FileNode>, depth:
     for (name, node) in tree {
         match node {
             crate::tree::FileNode::File => {
-                token_count += estimate_tokens(&format!("{}- 📄 {}\n", indent, name));
+                token_count += estimate_tokens(&format!("{}- 📄 {}\n", indent, name), tokenizer);
             }
             crate::tree::FileNode::Directory(children) => {
-                token_count += estimate_tokens(&format!("{}- 📁 {}\n", indent, name));
-                token_count += count_tree_tokens(children, depth + 1);
+                token_count += estimate_tokens(&format!("{}- 📁 {}\n", indent, name), tokenizer);
+                token_count += count_tree_tokens(children, depth + 1, tokenizer);
             }
         }
     }
@@ -70,6 +236,213 @@ pub fn count_tree_tokens(tree: &BTreeMap<String, crate::tree::FileNode>, depth:
     token_count
 }
 
+/// Human-readable names for the `file_relevance_category` values.
+pub fn relevance_category_name(category: u8) -> &'static str {
+    match category {
+        0 => "config",
+        1 => "source",
+        2 => "tests",
+        3 => "docs",
+        4 => "build",
+        _ => "lockfiles",
+    }
+}
+
+/// Per-file, per-directory and per-category token breakdown of the document.
+#[derive(Debug, Serialize)]
+pub struct TokenReport {
+    /// Tokenizer the counts were estimated with (e.g. "cl100k").
+    pub tokenizer: String,
+    pub total_tokens: usize,
+    pub header_tokens: usize,
+    pub tree_tokens: usize,
+    pub file_tokens: usize,
+    /// Files, heaviest first.
+    pub files: Vec<FileTokens>,
+    /// Cumulative subtotals for every directory containing files, heaviest
+    /// first. Files at the project root are counted under `./`, so that row
+    /// and the top-level directories add up to `file_tokens`.
+    pub directories: Vec<GroupTokens>,
+    /// Subtotals per relevance category, heaviest first.
+    pub categories: Vec<GroupTokens>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileTokens {
+    pub path: String,
+    pub category: &'static str,
+    pub tokens: usize,
+    /// Percentage of the total document tokens.
+    pub share: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupTokens {
+    pub name: String,
+    pub files: usize,
+    pub tokens: usize,
+    /// Percentage of the total document tokens.
+    pub share: f64,
+}
+
+/// Number of files and directories listed in the text report.
+const TEXT_REPORT_ROWS: usize = 25;
+
+impl TokenReport {
+    /// Counts every file with `count_file_tokens` and aggregates the results.
+    pub fn build(
+        files: &[DirEntry],
+        base_path: &Path,
+        line_numbers: bool,
+        ts_config: &TreeSitterConfig,
+        header_tokens: usize,
+        tree_tokens: usize,
+    ) -> Self {
+        let counted: Vec<(String, u8, usize)> = files
+            .iter()
+            .map(|entry| {
+                let relative = entry.path().strip_prefix(base_path).unwrap_or(entry.path());
+                (
+                    relative.to_string_lossy().replace('\\', "/"),
+                    crate::file_utils::file_relevance_category(entry.path(), base_path),
+                    count_file_tokens(base_path, entry, line_numbers, ts_config),
+                )
+            })
+            .collect();
+
+        let file_tokens: usize = counted.iter().map(|(_, _, tokens)| tokens).sum();
+        let total_tokens = header_tokens + tree_tokens + file_tokens;
+        let share = |tokens: usize| {
+            if total_tokens == 0 {
+                0.0
+            } else {
+                (tokens as f64 * 10000.0 / total_tokens as f64).round() / 100.0
+            }
+        };
+
+        let mut directories: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        let mut categories: BTreeMap<u8, (usize, usize)> = BTreeMap::new();
+        for (path, category, tokens) in &counted {
+            let mut dir = Path::new(path).parent();
+            if dir.is_none_or(|d| d.as_os_str().is_empty()) {
+                let subtotal = directories.entry("./".to_string()).or_default();
+                subtotal.0 += 1;
+                subtotal.1 += tokens;
+            }
+            while let Some(d) = dir.filter(|d| !d.as_os_str().is_empty()) {
+                let subtotal = directories
+                    .entry(format!("{}/", d.to_string_lossy()))
+                    .or_default();
+                subtotal.0 += 1;
+                subtotal.1 += tokens;
+                dir = d.parent();
+            }
+            let subtotal = categories.entry(*category).or_default();
+            subtotal.0 += 1;
+            subtotal.1 += tokens;
+        }
+
+        let mut files: Vec<FileTokens> = counted
+            .into_iter()
+            .map(|(path, category, tokens)| FileTokens {
+                path,
+                category: relevance_category_name(category),
+                tokens,
+                share: share(tokens),
+            })
+            .collect();
+        files.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.path.cmp(&b.path)));
+
+        let group = |name: String, (files, tokens): (usize, usize)| GroupTokens {
+            name,
+            files,
+            tokens,
+            share: share(tokens),
+        };
+        let mut directories: Vec<GroupTokens> = directories
+            .into_iter()
+            .map(|(name, subtotal)| group(name, subtotal))
+            .collect();
+        directories.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.name.cmp(&b.name)));
+        let mut categories: Vec<GroupTokens> = categories
+            .into_iter()
+            .map(|(category, subtotal)| {
+                group(relevance_category_name(category).to_string(), subtotal)
+            })
+            .collect();
+        categories.sort_by_key(|c| std::cmp::Reverse(c.tokens));
+
+        Self {
+            tokenizer: ts_config.tokenizer.to_string(),
+            total_tokens,
+            header_tokens,
+            tree_tokens,
+            file_tokens,
+            files,
+            directories,
+            categories,
+        }
+    }
+
+    /// Renders the aggregate numbers followed by the heaviest files,
+    /// directories and categories as aligned tables.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!(
+            "Estimated total tokens: {} ({})\n",
+            self.total_tokens, self.tokenizer
+        ));
+        out.push_str(&format!("File tree tokens: {}\n", self.tree_tokens));
+        out.push_str(&format!("File content tokens: {}\n", self.file_tokens));
+
+        out.push_str("\n## Heaviest files\n\n");
+        out.push_str(&format!(
+            "{:>10}  {:>6}  {:<9}  {}\n",
+            "Tokens", "Share", "Category", "Path"
+        ));
+        for file in self.files.iter().take(TEXT_REPORT_ROWS) {
+            out.push_str(&format!(
+                "{:>10}  {:>5.1}%  {:<9}  {}\n",
+                file.tokens, file.share, file.category, file.path
+            ));
+        }
+        if self.files.len() > TEXT_REPORT_ROWS {
+            out.push_str(&format!(
+                "... and {} more (use --token-count-format json for the full list)\n",
+                self.files.len() - TEXT_REPORT_ROWS
+            ));
+        }
+
+        if !self.directories.is_empty() {
+            out.push_str("\n## Directories\n\n");
+            write_group_table(&mut out, "Directory", &self.directories);
+        }
+
+        out.push_str("\n## Categories\n\n");
+        write_group_table(&mut out, "Category", &self.categories);
+        out
+    }
+}
+
+fn write_group_table(out: &mut String, label: &str, groups: &[GroupTokens]) {
+    out.push_str(&format!(
+        "{:>10}  {:>6}  {:>5}  {}\n",
+        "Tokens", "Share", "Files", label
+    ));
+    for group in groups.iter().take(TEXT_REPORT_ROWS) {
+        out.push_str(&format!(
+            "{:>10}  {:>5.1}%  {:>5}  {}\n",
+            group.tokens, group.share, group.files, group.name
+        ));
+    }
+    if groups.len() > TEXT_REPORT_ROWS {
+        out.push_str(&format!(
+            "... and {} more\n",
+            groups.len() - TEXT_REPORT_ROWS
+        ));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,17 +452,79 @@ mod tests {
     fn test_estimate_tokens() {
         // Test with a simple string
         let text = "Hello, world!";
-        let tokens = estimate_tokens(text);
+        let tokens = estimate_tokens(text, Tokenizer::Cl100k);
         // "Hello, world!" is 4 tokens with cl100k_base
         assert_eq!(tokens, 4);
 
         // Test with code-like content
         let code_text = "fn main() {\n    println!(\"Hello, world!\");\n}";
-        let tokens = estimate_tokens(code_text);
+        let tokens = estimate_tokens(code_text, Tokenizer::Cl100k);
         // This specific code snippet is 12 tokens with cl100k_base
         assert_eq!(tokens, 12);
     }
 
+    #[test]
+    fn test_parse_tokenizer() {
+        assert_eq!("cl100k".parse::<Tokenizer>(), Ok(Tokenizer::Cl100k));
+        assert_eq!("o200k_base".parse::<Tokenizer>(), Ok(Tokenizer::O200k));
+        assert_eq!("P50K".parse::<Tokenizer>(), Ok(Tokenizer::P50k));
+        assert_eq!("chars".parse::<Tokenizer>(), Ok(Tokenizer::CharRatio(4.0)));
+        assert_eq!(
+            "chars:3.5".parse::<Tokenizer>(),
+            Ok(Tokenizer::CharRatio(3.5))
+        );
+        assert!("chars:0".parse::<Tokenizer>().is_err());
+        assert!("gpt2".parse::<Tokenizer>().is_err());
+        assert_eq!(Tokenizer::CharRatio(3.5).to_string(), "chars:3.5");
+    }
+
+    #[test]
+    fn test_tokenizer_counts() {
+        let text = "fn main() {\n    println!(\"Hello, world!\");\n}";
+        assert_eq!(Tokenizer::Cl100k.count(text), 12);
+        assert!(Tokenizer::O200k.count(text) > 0);
+        assert!(Tokenizer::P50k.count(text) > 0);
+        // 44 characters at 4 characters per token, rounded up
+        assert_eq!(Tokenizer::CharRatio(4.0).count(text), 11);
+        assert_eq!(Tokenizer::CharRatio(4.0).count("héllo"), 2);
+    }
+
+    #[test]
+    fn test_tokenizer_counts_long_text_in_slices() {
+        // Slices are cut before whitespace, so prose counts exactly
+        let prose = "hello world, counting tokens ".repeat(500);
+        assert_eq!(
+            Tokenizer::Cl100k.count(&prose),
+            CL100K.encode_with_special_tokens(&prose).len()
+        );
+
+        // A long run without whitespace stays cheap to count
+        let run = "x".repeat(100_000);
+        assert!(Tokenizer::Cl100k.count(&run) > 0);
+        assert_eq!(Tokenizer::Cl100k.prefix_len(&run, 1_000_000), run.len());
+    }
+
+    #[test]
+    fn test_tokenizer_prefix_len() {
+        let text = "Hello, world! ".repeat(100);
+        for tokenizer in [
+            Tokenizer::Cl100k,
+            Tokenizer::O200k,
+            Tokenizer::P50k,
+            Tokenizer::CharRatio(3.5),
+        ] {
+            let len = tokenizer.prefix_len(&text, 50);
+            assert!(len > 0 && len < text.len());
+            assert!(tokenizer.count(&text[..len]) <= 50);
+            assert_eq!(tokenizer.prefix_len(&text, 100_000), text.len());
+        }
+
+        // Never cuts inside a multi-byte character
+        let unicode = "世界🌍".repeat(50);
+        let len = Tokenizer::Cl100k.prefix_len(&unicode, 7);
+        assert!(unicode.is_char_boundary(len));
+    }
+
     #[test]
     fn test_count_tree_tokens() {
         // Create a simple tree structure
@@ -100,7 +535,7 @@ mod tests {
         subdir.insert("file2.md".to_string(), crate::tree::FileNode::File);
         tree.insert("src".to_string(), crate::tree::FileNode::Directory(subdir));
 
-        let tokens = count_tree_tokens(&tree, 0);
+        let tokens = count_tree_tokens(&tree, 0, Tokenizer::Cl100k);
         // "- 📄 file1.rs\n" -> 8 tokens
         // "- 📁 src\n" -> 6 tokens
         // "  - 📄 file2.md\n" -> 9 tokens
@@ -123,7 +558,8 @@ mod tests {
             .unwrap();
 
         // Estimate tokens for the file
-        let estimated_tokens = count_file_tokens(dir.path(), &entry, false);
+        let estimated_tokens =
+            count_file_tokens(dir.path(), &entry, false, &TreeSitterConfig::default());
 
         // Generate actual markdown content
         let mut actual_content = Vec::new();
@@ -133,13 +569,14 @@ mod tests {
             &mut actual_content,
             false,
             None,
+            None,
             &crate::markdown::TreeSitterConfig::default(),
         )
         .unwrap();
         let actual_content_str = String::from_utf8(actual_content).unwrap();
 
         // Count actual tokens
-        let actual_tokens = estimate_tokens(&actual_content_str);
+        let actual_tokens = estimate_tokens(&actual_content_str, Tokenizer::Cl100k);
 
         // The estimation should be close to actual (within a reasonable margin)
         // Allow for some variance due to timestamp differences and minor formatting
@@ -159,19 +596,19 @@ mod tests {
 
     #[test]
     fn test_estimate_tokens_empty_string() {
-        let tokens = estimate_tokens("");
+        let tokens = estimate_tokens("", Tokenizer::Cl100k);
         assert_eq!(tokens, 0);
     }
 
     #[test]
     fn test_estimate_tokens_whitespace_only() {
-        let tokens = estimate_tokens("   \n\t  ");
+        let tokens = estimate_tokens("   \n\t  ", Tokenizer::Cl100k);
         assert!(tokens > 0); // Whitespace still counts as tokens
     }
 
     #[test]
     fn test_estimate_tokens_unicode() {
-        let tokens = estimate_tokens("Hello 世界! 🌍");
+        let tokens = estimate_tokens("Hello 世界! 🌍", Tokenizer::Cl100k);
         assert!(tokens > 0);
         // Unicode characters may be encoded as multiple tokens
         assert!(tokens >= 4);
@@ -191,13 +628,49 @@ mod tests {
             .unwrap()
             .unwrap();
 
-        let tokens_without_line_numbers = count_file_tokens(dir.path(), &entry, false);
-        let tokens_with_line_numbers = count_file_tokens(dir.path(), &entry, true);
+        let tokens_without_line_numbers =
+            count_file_tokens(dir.path(), &entry, false, &TreeSitterConfig::default());
+        let tokens_with_line_numbers =
+            count_file_tokens(dir.path(), &entry, true, &TreeSitterConfig::default());
 
         // With line numbers should have more tokens due to line number prefixes
         assert!(tokens_with_line_numbers > tokens_without_line_numbers);
     }
 
+    #[test]
+    fn test_count_file_tokens_after_compaction() {
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let test_file = dir.path().join("script.py");
+        std::fs::write(
+            &test_file,
+            "# Explains what the next line does, at some length.\nprint(1)\n",
+        )
+        .unwrap();
+
+        let entry = ignore::WalkBuilder::new(&test_file)
+            .build()
+            .next()
+            .unwrap()
+            .unwrap();
+
+        let full = count_file_tokens(dir.path(), &entry, false, &TreeSitterConfig::default());
+        let ts_config = TreeSitterConfig {
+            compact: Some(crate::compact::CompactLevel::All),
+            ..TreeSitterConfig::default()
+        };
+        let compacted = count_file_tokens(dir.path(), &entry, false, &ts_config);
+        assert!(compacted < full);
+        assert_eq!(
+            full - compacted,
+            estimate_tokens(
+                "# Explains what the next line does, at some length.\nprint(1)\n",
+                Tokenizer::Cl100k
+            ) - estimate_tokens("print(1)\n", Tokenizer::Cl100k)
+        );
+    }
+
     #[test]
     fn test_count_file_tokens_unreadable_file() {
         use tempfile::tempdir;
@@ -225,7 +698,7 @@ mod tests {
         std::fs::remove_file(&test_file).unwrap();
 
         if let Some(entry) = found_entry {
-            let tokens = count_file_tokens(dir.path(), &entry, false);
+            let tokens = count_file_tokens(dir.path(), &entry, false, &TreeSitterConfig::default());
             // Should still return some tokens for the file header even if content can't be read
             assert!(tokens > 0);
         }
@@ -234,7 +707,7 @@ mod tests {
     #[test]
     fn test_count_tree_tokens_empty_tree() {
         let tree = BTreeMap::new();
-        let tokens = count_tree_tokens(&tree, 0);
+        let tokens = count_tree_tokens(&tree, 0, Tokenizer::Cl100k);
         assert_eq!(tokens, 0);
     }
 
@@ -263,11 +736,11 @@ mod tests {
             crate::tree::FileNode::Directory(level1),
         );
 
-        let tokens = count_tree_tokens(&tree, 0);
+        let tokens = count_tree_tokens(&tree, 0, Tokenizer::Cl100k);
         assert!(tokens > 0);
 
         // Should account for indentation at different levels
-        let tokens_with_depth = count_tree_tokens(&tree, 2);
+        let tokens_with_depth = count_tree_tokens(&tree, 2, Tokenizer::Cl100k);
         assert!(tokens_with_depth > tokens); // More indentation = more tokens
     }
 
@@ -290,10 +763,74 @@ mod tests {
             crate::tree::FileNode::Directory(subdir),
         );
 
-        let tokens = count_tree_tokens(&tree, 0);
+        let tokens = count_tree_tokens(&tree, 0, Tokenizer::Cl100k);
         assert!(tokens > 0);
 
         // Verify it handles unicode filenames without crashing
         assert!(tokens > 20); // Should be substantial given the content
     }
+
+    #[test]
+    fn test_token_report_breakdown() {
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let base = dir.path();
+        std::fs::create_dir_all(base.join("src/nested")).unwrap();
+        std::fs::create_dir_all(base.join("tests")).unwrap();
+        std::fs::write(base.join("Cargo.toml"), "[package]\nname = \"x\"\n").unwrap();
+        std::fs::write(base.join("src/lib.rs"), "pub fn lib() {}\n".repeat(50)).unwrap();
+        std::fs::write(base.join("src/nested/util.rs"), "fn util() {}\n").unwrap();
+        std::fs::write(base.join("tests/it.rs"), "#[test]\nfn it() {}\n").unwrap();
+        let files = crate::file_utils::collect_files(base, &[], &[], &[]).unwrap();
+
+        let report = TokenReport::build(&files, base, false, &TreeSitterConfig::default(), 10, 5);
+
+        assert_eq!(report.files.len(), 4);
+        assert_eq!(report.files[0].path, "src/lib.rs");
+        assert_eq!(report.files[0].category, "source");
+        assert!(report.files.windows(2).all(|w| w[0].tokens >= w[1].tokens));
+        assert_eq!(
+            report.total_tokens,
+            10 + 5 + report.files.iter().map(|f| f.tokens).sum::<usize>()
+        );
+
+        // Directory subtotals are cumulative over nested directories
+        let src = report
+            .directories
+            .iter()
+            .find(|d| d.name == "src/")
+            .unwrap();
+        let nested = report
+            .directories
+            .iter()
+            .find(|d| d.name == "src/nested/")
+            .unwrap();
+        assert_eq!(src.files, 2);
+        assert!(src.tokens > nested.tokens);
+
+        // Files at the root get their own row; with the top-level directories
+        // it covers every file exactly once
+        let root = report.directories.iter().find(|d| d.name == "./").unwrap();
+        assert_eq!(root.files, 1);
+        let top_level: usize = report
+            .directories
+            .iter()
+            .filter(|d| d.name == "./" || d.name.matches('/').count() == 1)
+            .map(|d| d.tokens)
+            .sum();
+        assert_eq!(top_level, report.file_tokens);
+
+        let categories: Vec<&str> = report.categories.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(categories[0], "source");
+        assert!(categories.contains(&"config"));
+        assert!(categories.contains(&"tests"));
+
+        let text = report.to_text();
+        assert!(text.contains("## Heaviest files"));
+        assert!(text.contains("src/nested/"));
+        assert!(text.contains("  ./\n"));
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["files"][0]["path"], "src/lib.rs");
+    }
 }
@@ -1,10 +1,12 @@
 //! Core types and traits for language support.
 
+use serde::Serialize;
 use std::fmt;
 use std::str::FromStr;
 
 /// The kind of signature extracted from source code.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SignatureKind {
     Function,
     Method,
@@ -40,7 +42,8 @@ impl fmt::Display for SignatureKind {
 }
 
 /// Visibility level of a signature.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Visibility {
     #[default]
     All,
@@ -73,7 +76,7 @@ impl Visibility {
 }
 
 /// A signature extracted from source code (function, class, etc.).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Signature {
     pub kind: SignatureKind,
     pub name: String,
@@ -81,9 +84,49 @@ pub struct Signature {
     pub return_type: Option<String>,
     pub visibility: Visibility,
     pub line_number: usize,
+    /// Doc comment or docstring, with comment markers stripped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
     pub full_signature: String,
 }
 
+/// How much of each signature's documentation to keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignatureDocs {
+    Full,
+    #[default]
+    FirstLine,
+    Hidden,
+}
+
+impl FromStr for SignatureDocs {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "full" => SignatureDocs::Full,
+            "none" => SignatureDocs::Hidden,
+            _ => SignatureDocs::FirstLine,
+        })
+    }
+}
+
+impl SignatureDocs {
+    /// Trims or drops the `doc` of each signature according to the mode.
+    pub fn apply(self, signatures: &mut [Signature]) {
+        for signature in signatures {
+            signature.doc = match self {
+                SignatureDocs::Full => signature.doc.take(),
+                SignatureDocs::FirstLine => signature
+                    .doc
+                    .take()
+                    .and_then(|doc| doc.lines().next().map(str::to_string)),
+                SignatureDocs::Hidden => None,
+            };
+        }
+    }
+}
+
 impl fmt::Display for Signature {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.full_signature)
@@ -112,8 +155,61 @@ pub fn slice_signature_before_body(
     None
 }
 
+/// Whether a JavaScript/TypeScript `call_expression` is a CommonJS `require("...")`.
+pub fn is_require_call(source: &str, node: &tree_sitter::Node) -> bool {
+    node.child_by_field_name("function")
+        .is_some_and(|f| f.kind() == "identifier" && &source[f.byte_range()] == "require")
+}
+
+/// Text of the comments directly above `node`, with no blank line in
+/// between, skipping sibling nodes of the `skip` kinds (attributes).
+pub fn preceding_comments<'a>(
+    source: &'a str,
+    node: &tree_sitter::Node,
+    skip: &[&str],
+) -> Vec<&'a str> {
+    let mut comments = Vec::new();
+    let mut anchor = *node;
+    while let Some(sibling) = anchor.prev_sibling() {
+        if sibling.end_position().row + 1 < anchor.start_position().row {
+            break;
+        }
+        if sibling.kind().contains("comment") {
+            comments.push(&source[sibling.byte_range()]);
+        } else if !skip.contains(&sibling.kind()) {
+            break;
+        }
+        anchor = sibling;
+    }
+    comments.reverse();
+    comments
+}
+
+/// Joins comment texts into plain documentation: strips `///`, `//!`, `//`,
+/// `/** */`, `/* */`, `#`, `--` (with Haddock's `-- |` and `-- ^`) and leading
+/// `*` markers. `None` if nothing is left.
+pub fn clean_doc_comment<'a>(comments: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut lines = Vec::new();
+    for comment in comments {
+        let comment = comment.trim();
+        let comment = comment.strip_suffix("*/").unwrap_or(comment);
+        for line in comment.lines() {
+            let line = line.trim();
+            let line = [
+                "///", "//!", "//", "/**", "/*", "*", "#", "-- |", "-- ^", "---", "--",
+            ]
+            .iter()
+            .find_map(|marker| line.strip_prefix(marker))
+            .unwrap_or(line);
+            lines.push(line.strip_prefix(' ').unwrap_or(line).trim_end());
+        }
+    }
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
 /// Structure information extracted from a source file.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct CodeStructure {
     pub imports: Vec<String>,
     pub exports: Vec<String>,
@@ -0,0 +1,279 @@
+//! Bash/sh language support for tree-sitter.
+
+#[cfg(feature = "tree-sitter-bash")]
+use tree_sitter::{Node, Parser, Tree};
+
+#[cfg(feature = "tree-sitter-bash")]
+use crate::tree_sitter::language_support::{
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments,
+};
+
+pub struct BashSupport;
+
+#[cfg(feature = "tree-sitter-bash")]
+impl BashSupport {
+    fn get_language() -> tree_sitter::Language {
+        tree_sitter_bash::LANGUAGE.into()
+    }
+}
+
+#[cfg(feature = "tree-sitter-bash")]
+impl LanguageSupport for BashSupport {
+    fn file_extensions(&self) -> &[&'static str] {
+        &["sh", "bash"]
+    }
+
+    fn parse(&self, source: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&Self::get_language()).ok()?;
+        parser.parse(source, None)
+    }
+
+    fn extract_signatures(&self, source: &str, visibility: Visibility) -> Vec<Signature> {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut signatures = Vec::new();
+
+        self.extract_signatures_from_node(source, &root, visibility, &mut signatures);
+
+        signatures.sort_by_key(|s| s.line_number);
+        signatures
+    }
+
+    fn extract_structure(&self, source: &str) -> CodeStructure {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return CodeStructure::default(),
+        };
+
+        let root = tree.root_node();
+        let mut structure = CodeStructure {
+            total_lines: source.lines().count(),
+            ..Default::default()
+        };
+
+        self.extract_structure_from_node(source, &root, &mut structure);
+        structure
+    }
+
+    fn find_truncation_point(&self, source: &str, max_bytes: usize) -> usize {
+        if source.len() <= max_bytes {
+            return source.len();
+        }
+
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return max_bytes,
+        };
+
+        let root = tree.root_node();
+        let mut best_end = 0;
+
+        // Top-level commands and functions are the safe cut points
+        let mut cursor = root.walk();
+        for child in root.children(&mut cursor) {
+            if child.end_byte() <= max_bytes && child.kind() != "comment" {
+                best_end = child.end_byte();
+            }
+        }
+
+        if best_end == 0 { max_bytes } else { best_end }
+    }
+}
+
+#[cfg(feature = "tree-sitter-bash")]
+impl BashSupport {
+    fn extract_signatures_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        visibility: Visibility,
+        signatures: &mut Vec<Signature>,
+    ) {
+        let signature = match node.kind() {
+            "function_definition" => self.extract_function_signature(source, node),
+            "declaration_command" if self.is_readonly(source, node) => {
+                self.extract_constant_signature(source, node)
+            }
+            _ => None,
+        };
+        if let Some(sig) = signature
+            && sig.visibility.matches_filter(visibility)
+        {
+            signatures.push(sig);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_signatures_from_node(source, &child, visibility, signatures);
+        }
+    }
+
+    /// The `#` comments directly above a function, without the shebang.
+    fn doc_comment(&self, source: &str, node: &Node) -> Option<String> {
+        let comments = preceding_comments(source, node, &[]);
+        clean_doc_comment(comments.into_iter().filter(|c| !c.starts_with("#!")))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        structure: &mut CodeStructure,
+    ) {
+        match node.kind() {
+            "function_definition" => structure.functions += 1,
+            "declaration_command" if self.is_readonly(source, node) => structure.constants += 1,
+            "command" if self.is_source(source, node) => {
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_structure_from_node(source, &child, structure);
+        }
+    }
+
+    /// `source file` or `. file`.
+    fn is_source(&self, source: &str, node: &Node) -> bool {
+        node.child_by_field_name("name")
+            .is_some_and(|name| matches!(&source[name.byte_range()], "source" | "."))
+    }
+
+    /// `readonly NAME=...` or `declare -r NAME=...`.
+    fn is_readonly(&self, source: &str, node: &Node) -> bool {
+        let mut words = source[node.byte_range()].split_whitespace();
+        match words.next() {
+            Some("readonly") => true,
+            Some("declare" | "typeset") => words
+                .take_while(|word| word.starts_with('-'))
+                .any(|flags| flags.contains('r')),
+            _ => false,
+        }
+    }
+
+    /// Functions named with a leading underscore are private by convention.
+    fn get_visibility(&self, name: &str) -> Visibility {
+        if name.starts_with('_') {
+            Visibility::Private
+        } else {
+            Visibility::Public
+        }
+    }
+
+    fn extract_function_signature(&self, source: &str, node: &Node) -> Option<Signature> {
+        let name = source[node.child_by_field_name("name")?.byte_range()].to_string();
+        let end = node
+            .child_by_field_name("body")
+            .map_or(node.end_byte(), |body| body.start_byte());
+        let full_signature = source[node.start_byte()..end].trim_end().to_string();
+
+        Some(Signature {
+            kind: SignatureKind::Function,
+            visibility: self.get_visibility(&name),
+            name,
+            params: None,
+            return_type: None,
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature,
+        })
+    }
+
+    fn extract_constant_signature(&self, source: &str, node: &Node) -> Option<Signature> {
+        let assignment = node
+            .named_children(&mut node.walk())
+            .find(|child| child.kind() == "variable_assignment")?;
+        let name = source[assignment.child_by_field_name("name")?.byte_range()].to_string();
+        let text = &source[node.byte_range()];
+
+        Some(Signature {
+            kind: SignatureKind::Constant,
+            visibility: self.get_visibility(&name),
+            name,
+            params: None,
+            return_type: None,
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature: text.lines().next().unwrap_or(text).trim_end().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"#!/usr/bin/env bash
+source ./lib.sh
+. "$HOME/.env"
+readonly VERSION="1.0"
+
+# Prints usage.
+usage() {
+  echo "usage"
+}
+
+function deploy {
+  local target=$1
+  _helper "$target"
+}
+
+_helper() { :; }
+"#;
+
+    #[test]
+    fn test_extract_signatures() {
+        let signatures = BashSupport.extract_signatures(SOURCE, Visibility::All);
+        let names: Vec<_> = signatures.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["VERSION", "usage", "deploy", "_helper"]);
+
+        assert_eq!(signatures[0].kind, SignatureKind::Constant);
+        assert_eq!(signatures[0].full_signature, "readonly VERSION=\"1.0\"");
+        assert_eq!(signatures[1].full_signature, "usage()");
+        assert_eq!(signatures[1].doc.as_deref(), Some("Prints usage."));
+        assert_eq!(signatures[2].full_signature, "function deploy");
+        assert_eq!(signatures[2].doc, None);
+    }
+
+    #[test]
+    fn test_visibility() {
+        let private = BashSupport.extract_signatures(SOURCE, Visibility::Private);
+        let names: Vec<_> = private.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["_helper"]);
+    }
+
+    #[test]
+    fn test_extract_structure() {
+        let structure = BashSupport.extract_structure(SOURCE);
+        assert_eq!(structure.functions, 3);
+        assert_eq!(structure.constants, 1);
+        assert_eq!(
+            structure.imports,
+            vec!["source ./lib.sh", ". \"$HOME/.env\""]
+        );
+    }
+
+    #[test]
+    fn test_find_truncation_point() {
+        let point = BashSupport.find_truncation_point(SOURCE, 130);
+        assert!(point <= 130);
+        assert!(SOURCE[..point].ends_with('}'));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        assert!(BashSupport.supports_extension("sh"));
+        assert!(BashSupport.supports_extension("bash"));
+        assert!(!BashSupport.supports_extension("zsh"));
+    }
+}
@@ -5,8 +5,8 @@ use tree_sitter::{Parser, Tree};
 
 #[cfg(feature = "tree-sitter-c")]
 use crate::tree_sitter::language_support::{
-    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility,
-    slice_signature_before_body,
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments, slice_signature_before_body,
 };
 
 pub struct CSupport;
@@ -57,7 +57,7 @@ impl LanguageSupport for CSupport {
             ..Default::default()
         };
 
-        self.extract_structure_from_node(&root, &mut structure);
+        self.extract_structure_from_node(source, &root, &mut structure);
         structure
     }
 
@@ -132,20 +132,32 @@ impl CSupport {
         }
     }
 
-    fn extract_structure_from_node(&self, node: &tree_sitter::Node, structure: &mut CodeStructure) {
+    /// The comment block directly above a declaration.
+    fn doc_comment(&self, source: &str, node: &tree_sitter::Node) -> Option<String> {
+        clean_doc_comment(preceding_comments(source, node, &[]))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &tree_sitter::Node,
+        structure: &mut CodeStructure,
+    ) {
         match node.kind() {
             "function_definition" => structure.functions += 1,
             "struct_specifier" => structure.structs += 1,
             "enum_specifier" => structure.enums += 1,
             "preproc_include" => {
-                structure.imports.push("include".to_string());
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
             }
             _ => {}
         }
 
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            self.extract_structure_from_node(&child, structure);
+            self.extract_structure_from_node(source, &child, structure);
         }
     }
 
@@ -182,6 +194,7 @@ impl CSupport {
             return_type,
             visibility: Visibility::All, // C has no visibility
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -223,6 +236,7 @@ impl CSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -243,6 +257,7 @@ impl CSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -259,6 +274,7 @@ impl CSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -279,6 +295,7 @@ impl CSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -295,6 +312,7 @@ impl CSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
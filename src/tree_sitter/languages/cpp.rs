@@ -5,8 +5,8 @@ use tree_sitter::{Parser, Tree};
 
 #[cfg(feature = "tree-sitter-cpp")]
 use crate::tree_sitter::language_support::{
-    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility,
-    slice_signature_before_body,
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments, slice_signature_before_body,
 };
 
 pub struct CppSupport;
@@ -57,7 +57,7 @@ impl LanguageSupport for CppSupport {
             ..Default::default()
         };
 
-        self.extract_structure_from_node(&root, &mut structure);
+        self.extract_structure_from_node(source, &root, &mut structure);
         structure
     }
 
@@ -190,21 +190,37 @@ impl CppSupport {
         }
     }
 
-    fn extract_structure_from_node(&self, node: &tree_sitter::Node, structure: &mut CodeStructure) {
+    /// The comment block directly above a declaration (or its `template<..>`).
+    fn doc_comment(&self, source: &str, node: &tree_sitter::Node) -> Option<String> {
+        let anchor = match node.parent() {
+            Some(parent) if parent.kind() == "template_declaration" => parent,
+            _ => *node,
+        };
+        clean_doc_comment(preceding_comments(source, &anchor, &[]))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &tree_sitter::Node,
+        structure: &mut CodeStructure,
+    ) {
         match node.kind() {
             "function_definition" => structure.functions += 1,
             "class_specifier" => structure.classes += 1,
             "struct_specifier" => structure.structs += 1,
             "enum_specifier" => structure.enums += 1,
             "preproc_include" => {
-                structure.imports.push("include".to_string());
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
             }
             _ => {}
         }
 
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            self.extract_structure_from_node(&child, structure);
+            self.extract_structure_from_node(source, &child, structure);
         }
     }
 
@@ -250,6 +266,7 @@ impl CppSupport {
             return_type,
             visibility,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -286,6 +303,7 @@ impl CppSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -309,6 +327,7 @@ impl CppSupport {
             return_type: None,
             visibility,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -329,6 +348,7 @@ impl CppSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -345,6 +365,7 @@ impl CppSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -361,6 +382,7 @@ impl CppSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -377,6 +399,7 @@ impl CppSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
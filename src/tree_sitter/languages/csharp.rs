@@ -0,0 +1,395 @@
+//! C# language support for tree-sitter.
+
+#[cfg(feature = "tree-sitter-csharp")]
+use tree_sitter::{Node, Parser, Tree};
+
+#[cfg(feature = "tree-sitter-csharp")]
+use crate::tree_sitter::language_support::{
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments,
+};
+
+pub struct CSharpSupport;
+
+#[cfg(feature = "tree-sitter-csharp")]
+impl CSharpSupport {
+    fn get_language() -> tree_sitter::Language {
+        tree_sitter_c_sharp::LANGUAGE.into()
+    }
+}
+
+#[cfg(feature = "tree-sitter-csharp")]
+impl LanguageSupport for CSharpSupport {
+    fn file_extensions(&self) -> &[&'static str] {
+        &["cs"]
+    }
+
+    fn parse(&self, source: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&Self::get_language()).ok()?;
+        parser.parse(source, None)
+    }
+
+    fn extract_signatures(&self, source: &str, visibility: Visibility) -> Vec<Signature> {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut signatures = Vec::new();
+
+        self.extract_signatures_from_node(source, &root, visibility, &mut signatures);
+
+        signatures.sort_by_key(|s| s.line_number);
+        signatures
+    }
+
+    fn extract_structure(&self, source: &str) -> CodeStructure {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return CodeStructure::default(),
+        };
+
+        let root = tree.root_node();
+        let mut structure = CodeStructure {
+            total_lines: source.lines().count(),
+            ..Default::default()
+        };
+
+        self.extract_structure_from_node(source, &root, &mut structure);
+        structure
+    }
+
+    fn find_truncation_point(&self, source: &str, max_bytes: usize) -> usize {
+        if source.len() <= max_bytes {
+            return source.len();
+        }
+
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return max_bytes,
+        };
+
+        let root = tree.root_node();
+        let mut best_end = 0;
+
+        let mut cursor = root.walk();
+        self.find_best_boundary(&mut cursor, max_bytes, &mut best_end);
+        drop(cursor);
+
+        if best_end == 0 { max_bytes } else { best_end }
+    }
+}
+
+#[cfg(feature = "tree-sitter-csharp")]
+impl CSharpSupport {
+    fn extract_signatures_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        visibility: Visibility,
+        signatures: &mut Vec<Signature>,
+    ) {
+        let kind = match node.kind() {
+            "class_declaration" => Some(SignatureKind::Class),
+            "record_declaration" => Some(SignatureKind::Class),
+            "struct_declaration" => Some(SignatureKind::Struct),
+            "interface_declaration" => Some(SignatureKind::Interface),
+            "enum_declaration" => Some(SignatureKind::Enum),
+            "method_declaration" | "constructor_declaration" => Some(SignatureKind::Method),
+            "delegate_declaration" => Some(SignatureKind::TypeAlias),
+            "namespace_declaration" | "file_scoped_namespace_declaration" => {
+                Some(SignatureKind::Module)
+            }
+            "field_declaration" if self.has_modifier(source, node, "const") => {
+                Some(SignatureKind::Constant)
+            }
+            _ => None,
+        };
+        if let Some(kind) = kind
+            && let Some(sig) = self.extract_signature(source, node, kind)
+            && sig.visibility.matches_filter(visibility)
+        {
+            signatures.push(sig);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_signatures_from_node(source, &child, visibility, signatures);
+        }
+    }
+
+    /// The `///` XML doc comment above a declaration, without `<summary>` tags.
+    fn doc_comment(&self, source: &str, node: &Node) -> Option<String> {
+        let comments = preceding_comments(source, node, &[]);
+        let doc = clean_doc_comment(comments.into_iter().filter(|c| c.starts_with("///")))?;
+        let doc = doc.replace("<summary>", "").replace("</summary>", "");
+        let lines: Vec<&str> = doc
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        structure: &mut CodeStructure,
+    ) {
+        match node.kind() {
+            "method_declaration" | "constructor_declaration" | "local_function_statement" => {
+                structure.functions += 1
+            }
+            "class_declaration" | "record_declaration" => structure.classes += 1,
+            "struct_declaration" => structure.structs += 1,
+            "interface_declaration" => structure.interfaces += 1,
+            "enum_declaration" => structure.enums += 1,
+            "delegate_declaration" => structure.type_aliases += 1,
+            "field_declaration" if self.has_modifier(source, node, "const") => {
+                structure.constants += 1
+            }
+            "using_directive" => {
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_structure_from_node(source, &child, structure);
+        }
+    }
+
+    fn has_modifier(&self, source: &str, node: &Node, modifier: &str) -> bool {
+        let mut cursor = node.walk();
+        node.children(&mut cursor)
+            .any(|child| child.kind() == "modifier" && &source[child.byte_range()] == modifier)
+    }
+
+    /// `public` members, and interface members without a modifier, are public.
+    fn get_visibility(&self, source: &str, node: &Node) -> Visibility {
+        if node.kind().contains("namespace") || self.has_modifier(source, node, "public") {
+            return Visibility::Public;
+        }
+        let mut cursor = node.walk();
+        let has_access_modifier = node.children(&mut cursor).any(|child| {
+            child.kind() == "modifier"
+                && matches!(
+                    &source[child.byte_range()],
+                    "private" | "protected" | "internal"
+                )
+        });
+        let in_interface = node
+            .parent()
+            .and_then(|body| body.parent())
+            .is_some_and(|parent| parent.kind() == "interface_declaration");
+        if in_interface && !has_access_modifier {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        }
+    }
+
+    fn extract_signature(
+        &self,
+        source: &str,
+        node: &Node,
+        kind: SignatureKind,
+    ) -> Option<Signature> {
+        let name = if kind == SignatureKind::Constant {
+            let declaration = node
+                .named_children(&mut node.walk())
+                .find(|child| child.kind() == "variable_declaration")?;
+            let declarator = declaration
+                .named_children(&mut declaration.walk())
+                .find(|child| child.kind() == "variable_declarator")?;
+            source[declarator.child_by_field_name("name")?.byte_range()].to_string()
+        } else {
+            source[node.child_by_field_name("name")?.byte_range()].to_string()
+        };
+        let params = node
+            .child_by_field_name("parameters")
+            .map(|p| source[p.byte_range()].to_string());
+        let return_type = node
+            .child_by_field_name("returns")
+            .map(|r| source[r.byte_range()].to_string());
+
+        // Everything up to the body: attributes, modifiers, generics, bases and constraints
+        let end = node
+            .child_by_field_name("body")
+            .map_or(node.end_byte(), |body| body.start_byte());
+        let full_signature = source[node.start_byte()..end]
+            .trim_end()
+            .trim_end_matches(';')
+            .trim_end()
+            .to_string();
+
+        Some(Signature {
+            kind,
+            name,
+            params,
+            return_type,
+            visibility: self.get_visibility(source, node),
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature,
+        })
+    }
+
+    fn find_best_boundary(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        max_bytes: usize,
+        best_end: &mut usize,
+    ) {
+        loop {
+            let node = cursor.node();
+            let end_byte = node.end_byte();
+
+            if end_byte <= max_bytes && end_byte > *best_end {
+                let is_item = matches!(
+                    node.kind(),
+                    "method_declaration"
+                        | "constructor_declaration"
+                        | "property_declaration"
+                        | "class_declaration"
+                        | "record_declaration"
+                        | "struct_declaration"
+                        | "interface_declaration"
+                        | "enum_declaration"
+                        | "namespace_declaration"
+                );
+                if is_item {
+                    *best_end = end_byte;
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.find_best_boundary(cursor, max_bytes, best_end);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+using System;
+using System.Collections.Generic;
+
+namespace App.Models
+{
+    /// <summary>
+    /// A user.
+    /// </summary>
+    public class User : Entity
+    {
+        private const int Max = 10;
+
+        public User(string name) { Name = name; }
+
+        /// <summary>Compares users.</summary>
+        public int CompareTo(object other) => 0;
+
+        internal static void Helper<T>(T x) where T : class { }
+    }
+
+    public interface IRepo
+    {
+        void Save(User u);
+    }
+
+    public enum Color { Red, Green }
+    public delegate void Handler(int x);
+}
+"#;
+
+    #[test]
+    fn test_extract_signatures() {
+        let signatures = CSharpSupport.extract_signatures(SOURCE, Visibility::All);
+        let names: Vec<_> = signatures.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "App.Models",
+                "User",
+                "Max",
+                "User",
+                "CompareTo",
+                "Helper",
+                "IRepo",
+                "Save",
+                "Color",
+                "Handler"
+            ]
+        );
+
+        let class = &signatures[1];
+        assert_eq!(class.kind, SignatureKind::Class);
+        assert_eq!(class.full_signature, "public class User : Entity");
+        assert_eq!(class.doc.as_deref(), Some("A user."));
+
+        let compare = &signatures[4];
+        assert_eq!(compare.full_signature, "public int CompareTo(object other)");
+        assert_eq!(compare.return_type.as_deref(), Some("int"));
+        assert_eq!(compare.doc.as_deref(), Some("Compares users."));
+
+        assert_eq!(
+            signatures[5].full_signature,
+            "internal static void Helper<T>(T x) where T : class"
+        );
+        assert_eq!(signatures[7].full_signature, "void Save(User u)");
+        assert_eq!(signatures[9].kind, SignatureKind::TypeAlias);
+    }
+
+    #[test]
+    fn test_visibility() {
+        let public = CSharpSupport.extract_signatures(SOURCE, Visibility::Public);
+        assert!(public.iter().any(|s| s.name == "Save"), "interface members");
+        assert!(public.iter().all(|s| s.name != "Helper" && s.name != "Max"));
+
+        let private = CSharpSupport.extract_signatures(SOURCE, Visibility::Private);
+        let names: Vec<_> = private.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Max", "Helper"]);
+    }
+
+    #[test]
+    fn test_extract_structure() {
+        let structure = CSharpSupport.extract_structure(SOURCE);
+        assert_eq!(structure.functions, 4);
+        assert_eq!(structure.classes, 1);
+        assert_eq!(structure.interfaces, 1);
+        assert_eq!(structure.enums, 1);
+        assert_eq!(structure.constants, 1);
+        assert_eq!(structure.type_aliases, 1);
+        assert_eq!(
+            structure.imports,
+            vec!["using System;", "using System.Collections.Generic;"]
+        );
+    }
+
+    #[test]
+    fn test_find_truncation_point() {
+        let point = CSharpSupport.find_truncation_point(SOURCE, 400);
+        assert!(point <= 400);
+        assert!(SOURCE[..point].ends_with('}') || SOURCE[..point].ends_with(';'));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        assert!(CSharpSupport.supports_extension("cs"));
+        assert!(!CSharpSupport.supports_extension("java"));
+    }
+}
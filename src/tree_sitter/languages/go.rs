@@ -5,8 +5,8 @@ use tree_sitter::{Parser, Tree};
 
 #[cfg(feature = "tree-sitter-go")]
 use crate::tree_sitter::language_support::{
-    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility,
-    slice_signature_before_body,
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments, slice_signature_before_body,
 };
 
 pub struct GoSupport;
@@ -57,7 +57,7 @@ impl LanguageSupport for GoSupport {
             ..Default::default()
         };
 
-        self.extract_structure_from_node(&root, &mut structure);
+        self.extract_structure_from_node(source, &root, &mut structure);
         structure
     }
 
@@ -114,7 +114,27 @@ impl GoSupport {
         }
     }
 
-    fn extract_structure_from_node(&self, node: &tree_sitter::Node, structure: &mut CodeStructure) {
+    /// The `//` comment block directly above a declaration.
+    fn doc_comment(&self, source: &str, node: &tree_sitter::Node) -> Option<String> {
+        // `type Foo struct` documents the declaration, not the spec inside it
+        let anchor = match node.parent() {
+            Some(parent)
+                if parent.kind() == "type_declaration"
+                    && parent.start_position().row == node.start_position().row =>
+            {
+                parent
+            }
+            _ => *node,
+        };
+        clean_doc_comment(preceding_comments(source, &anchor, &[]))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &tree_sitter::Node,
+        structure: &mut CodeStructure,
+    ) {
         match node.kind() {
             "function_declaration" | "method_declaration" => structure.functions += 1,
             "type_spec" => {
@@ -135,14 +155,16 @@ impl GoSupport {
                 }
             }
             "import_declaration" => {
-                structure.imports.push("import".to_string());
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
             }
             _ => {}
         }
 
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            self.extract_structure_from_node(&child, structure);
+            self.extract_structure_from_node(source, &child, structure);
         }
     }
 
@@ -199,6 +221,7 @@ impl GoSupport {
                 Visibility::Private
             },
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -253,6 +276,7 @@ impl GoSupport {
                 Visibility::Private
             },
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -292,6 +316,7 @@ impl GoSupport {
                         Visibility::Private
                     },
                     line_number: child.start_position().row + 1,
+                    doc: self.doc_comment(source, &child),
                     full_signature: full_sig,
                 });
             }
@@ -537,4 +562,28 @@ func helper() {}
         assert!(GoSupport.supports_extension("go"));
         assert!(!GoSupport.supports_extension("rs"));
     }
+
+    #[test]
+    fn test_doc_comments() {
+        let source = r#"package main
+
+// Server handles requests.
+type Server struct{}
+
+// Start runs the server.
+// It blocks until shutdown.
+func (s *Server) Start() error {
+    return nil
+}
+"#;
+
+        let signatures = GoSupport.extract_signatures(source, Visibility::All);
+        let server = signatures.iter().find(|s| s.name == "Server").unwrap();
+        assert_eq!(server.doc.as_deref(), Some("Server handles requests."));
+        let start = signatures.iter().find(|s| s.name == "Start").unwrap();
+        assert_eq!(
+            start.doc.as_deref(),
+            Some("Start runs the server.\nIt blocks until shutdown.")
+        );
+    }
 }
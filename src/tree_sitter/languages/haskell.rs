@@ -0,0 +1,484 @@
+//! Haskell language support for tree-sitter.
+
+#[cfg(feature = "tree-sitter-haskell")]
+use std::collections::HashSet;
+
+#[cfg(feature = "tree-sitter-haskell")]
+use tree_sitter::{Node, Parser, Point, Tree};
+
+#[cfg(feature = "tree-sitter-haskell")]
+use crate::tree_sitter::language_support::{
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+};
+
+pub struct HaskellSupport;
+
+#[cfg(feature = "tree-sitter-haskell")]
+impl HaskellSupport {
+    fn get_language() -> tree_sitter::Language {
+        tree_sitter_haskell::LANGUAGE.into()
+    }
+}
+
+#[cfg(feature = "tree-sitter-haskell")]
+impl LanguageSupport for HaskellSupport {
+    fn file_extensions(&self) -> &[&'static str] {
+        &["hs"]
+    }
+
+    fn parse(&self, source: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&Self::get_language()).ok()?;
+        parser.parse(source, None)
+    }
+
+    fn extract_signatures(&self, source: &str, visibility: Visibility) -> Vec<Signature> {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let exports = self.exported_names(source, &root);
+        let mut signatures = Vec::new();
+
+        if let Some(declarations) = root.child_by_field_name("declarations") {
+            self.extract_declarations(source, &root, &declarations, &exports, &mut signatures);
+        }
+
+        signatures.retain(|sig| sig.visibility.matches_filter(visibility));
+        signatures.sort_by_key(|s| s.line_number);
+        signatures
+    }
+
+    fn extract_structure(&self, source: &str) -> CodeStructure {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return CodeStructure::default(),
+        };
+
+        let root = tree.root_node();
+        let mut structure = CodeStructure {
+            total_lines: source.lines().count(),
+            ..Default::default()
+        };
+
+        if let Some(imports) = root.child_by_field_name("imports") {
+            let mut cursor = imports.walk();
+            for import in imports.children_by_field_name("import", &mut cursor) {
+                structure
+                    .imports
+                    .push(source[import.start_byte()..import.end_byte()].to_string());
+            }
+        }
+        if let Some(exports) = self.export_list(&root) {
+            let mut cursor = exports.walk();
+            for export in exports.children_by_field_name("export", &mut cursor) {
+                structure
+                    .exports
+                    .push(source[export.start_byte()..export.end_byte()].to_string());
+            }
+        }
+
+        for sig in self.extract_signatures(source, Visibility::All) {
+            match sig.kind {
+                SignatureKind::Function | SignatureKind::Method => structure.functions += 1,
+                SignatureKind::Struct => structure.structs += 1,
+                SignatureKind::Trait => structure.traits += 1,
+                SignatureKind::TypeAlias => structure.type_aliases += 1,
+                SignatureKind::Constant => structure.constants += 1,
+                _ => {}
+            }
+        }
+
+        structure
+    }
+
+    fn find_truncation_point(&self, source: &str, max_bytes: usize) -> usize {
+        if source.len() <= max_bytes {
+            return source.len();
+        }
+
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return max_bytes,
+        };
+
+        let root = tree.root_node();
+        let mut best_end = 0;
+
+        // The header, the import block and each top-level declaration are safe cut points
+        let mut cursor = root.walk();
+        for section in root.named_children(&mut cursor) {
+            if section.end_byte() <= max_bytes {
+                best_end = section.end_byte();
+                continue;
+            }
+            let mut inner = section.walk();
+            for child in section.named_children(&mut inner) {
+                if child.end_byte() <= max_bytes && child.kind() != "haddock" {
+                    best_end = child.end_byte();
+                }
+            }
+            break;
+        }
+
+        if best_end == 0 { max_bytes } else { best_end }
+    }
+}
+
+#[cfg(feature = "tree-sitter-haskell")]
+impl HaskellSupport {
+    /// Top-level declarations, plus the method signatures of type classes.
+    /// A function's type signature stands in for its equations; equations
+    /// without one are reported once, under their first clause.
+    fn extract_declarations(
+        &self,
+        source: &str,
+        root: &Node,
+        declarations: &Node,
+        exports: &Option<HashSet<String>>,
+        signatures: &mut Vec<Signature>,
+    ) {
+        let mut seen = HashSet::new();
+        let mut cursor = declarations.walk();
+        for node in declarations.named_children(&mut cursor) {
+            let signature = match node.kind() {
+                "signature" => self.extract_value_signature(source, root, &node, exports),
+                "function" | "bind" => {
+                    let name = node
+                        .child_by_field_name("name")
+                        .map(|n| source[n.byte_range()].to_string());
+                    if name.as_ref().is_some_and(|name| seen.contains(name)) {
+                        None
+                    } else {
+                        self.extract_equation_signature(source, root, &node, exports)
+                    }
+                }
+                "data_type" | "newtype" => {
+                    self.extract_type_signature(source, root, &node, SignatureKind::Struct, exports)
+                }
+                "type_synonym" => self.extract_type_signature(
+                    source,
+                    root,
+                    &node,
+                    SignatureKind::TypeAlias,
+                    exports,
+                ),
+                "class" => {
+                    let class = self.extract_type_signature(
+                        source,
+                        root,
+                        &node,
+                        SignatureKind::Trait,
+                        exports,
+                    );
+                    if let Some(class) = &class {
+                        self.extract_class_methods(
+                            source,
+                            root,
+                            &node,
+                            class.visibility,
+                            signatures,
+                        );
+                    }
+                    class
+                }
+                "instance" => {
+                    self.extract_type_signature(source, root, &node, SignatureKind::Impl, exports)
+                }
+                _ => None,
+            };
+            if let Some(sig) = signature {
+                seen.insert(sig.name.clone());
+                signatures.push(sig);
+            }
+        }
+    }
+
+    fn extract_class_methods(
+        &self,
+        source: &str,
+        root: &Node,
+        class: &Node,
+        visibility: Visibility,
+        signatures: &mut Vec<Signature>,
+    ) {
+        let Some(body) = class.child_by_field_name("declarations") else {
+            return;
+        };
+        let mut cursor = body.walk();
+        for node in body.named_children(&mut cursor) {
+            if node.kind() == "signature"
+                && let Some(mut sig) = self.extract_value_signature(source, root, &node, &None)
+            {
+                sig.kind = SignatureKind::Method;
+                sig.visibility = visibility;
+                signatures.push(sig);
+            }
+        }
+    }
+
+    /// The `(...)` after `module Name`, if any.
+    fn export_list<'a>(&self, root: &Node<'a>) -> Option<Node<'a>> {
+        root.named_children(&mut root.walk())
+            .find(|child| child.kind() == "header")?
+            .child_by_field_name("exports")
+    }
+
+    /// Names listed in the module's export list; `None` when the module
+    /// has no export list and so exports everything.
+    fn exported_names(&self, source: &str, root: &Node) -> Option<HashSet<String>> {
+        let exports = self.export_list(root)?;
+        let mut cursor = exports.walk();
+        let names = exports
+            .children_by_field_name("export", &mut cursor)
+            .filter_map(|export| {
+                export
+                    .child_by_field_name("variable")
+                    .or_else(|| export.child_by_field_name("type"))
+            })
+            .map(|name| source[name.byte_range()].to_string())
+            .collect();
+        Some(names)
+    }
+
+    fn get_visibility(&self, name: &str, exports: &Option<HashSet<String>>) -> Visibility {
+        match exports {
+            Some(names) if !names.contains(name) => Visibility::Private,
+            _ => Visibility::Public,
+        }
+    }
+
+    /// The Haddock comment for a declaration: a `-- |` block on the lines
+    /// above it, or a `-- ^` block on the line below. Haddock nodes are not
+    /// always siblings of what they document, so look them up by position.
+    fn doc_comment(&self, source: &str, root: &Node, node: &Node) -> Option<String> {
+        let column = node.start_position().column;
+        let comment_at = |row: usize| {
+            let point = Point { row, column };
+            root.named_descendant_for_point_range(point, point)
+                .filter(|comment| comment.kind() == "haddock")
+                .map(|comment| &source[comment.byte_range()])
+        };
+        let row = node.start_position().row;
+        let before = row
+            .checked_sub(1)
+            .and_then(comment_at)
+            .filter(|comment| comment.starts_with("-- |"));
+        let after =
+            comment_at(node.end_position().row + 1).filter(|comment| comment.starts_with("-- ^"));
+        clean_doc_comment(before.or(after))
+    }
+
+    /// `name :: Type`.
+    fn extract_value_signature(
+        &self,
+        source: &str,
+        root: &Node,
+        node: &Node,
+        exports: &Option<HashSet<String>>,
+    ) -> Option<Signature> {
+        let name = source[node.child_by_field_name("name")?.byte_range()].to_string();
+        let return_type = node
+            .child_by_field_name("type")
+            .map(|t| source[t.byte_range()].to_string());
+
+        Some(Signature {
+            kind: SignatureKind::Function,
+            visibility: self.get_visibility(&name, exports),
+            name,
+            params: None,
+            return_type,
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, root, node),
+            full_signature: source[node.byte_range()].trim_end().to_string(),
+        })
+    }
+
+    /// An equation without a type signature: its left-hand side.
+    fn extract_equation_signature(
+        &self,
+        source: &str,
+        root: &Node,
+        node: &Node,
+        exports: &Option<HashSet<String>>,
+    ) -> Option<Signature> {
+        let name = source[node.child_by_field_name("name")?.byte_range()].to_string();
+        let params = node
+            .child_by_field_name("patterns")
+            .map(|p| source[p.byte_range()].to_string());
+        let end = node
+            .child_by_field_name("match")
+            .map_or(node.end_byte(), |m| m.start_byte());
+
+        Some(Signature {
+            kind: if node.kind() == "bind" {
+                SignatureKind::Constant
+            } else {
+                SignatureKind::Function
+            },
+            visibility: self.get_visibility(&name, exports),
+            name,
+            params,
+            return_type: None,
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, root, node),
+            full_signature: source[node.start_byte()..end].trim_end().to_string(),
+        })
+    }
+
+    /// `data`, `newtype`, `type`, `class` and `instance` heads, up to their
+    /// constructors or `where`.
+    fn extract_type_signature(
+        &self,
+        source: &str,
+        root: &Node,
+        node: &Node,
+        kind: SignatureKind,
+        exports: &Option<HashSet<String>>,
+    ) -> Option<Signature> {
+        let name = source[node.child_by_field_name("name")?.byte_range()].to_string();
+        let text = &source[node.byte_range()];
+        let head = match kind {
+            SignatureKind::TypeAlias => text.lines().next().unwrap_or(text),
+            _ => {
+                let end = ["constructors", "constructor", "declarations", "deriving"]
+                    .iter()
+                    .filter_map(|field| node.child_by_field_name(field))
+                    .map(|child| child.start_byte())
+                    .min()
+                    .unwrap_or(node.end_byte());
+                &source[node.start_byte()..end]
+            }
+        };
+        let head = head.trim_end();
+        let head = head.strip_suffix("where").unwrap_or(head).trim_end();
+        let head = head.strip_suffix('=').unwrap_or(head).trim_end();
+
+        Some(Signature {
+            visibility: if kind == SignatureKind::Impl {
+                Visibility::All
+            } else {
+                self.get_visibility(&name, exports)
+            },
+            kind,
+            name,
+            params: None,
+            return_type: None,
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, root, node),
+            full_signature: head.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"module Data.User (User(..), Greet(..), greet) where
+
+import qualified Data.Map as M
+import Data.List (sortBy)
+
+-- | A user.
+data User = User { name :: String }
+  deriving (Show)
+
+newtype Id = Id Int
+
+type Name = String
+
+class Greet a where
+  hello :: a -> String
+  -- ^ Says hello.
+
+instance Greet User where
+  hello u = "hi"
+
+-- | Greets a user.
+-- Politely.
+greet :: User -> String
+greet u = "hi " ++ name u
+
+helper 0 = 0
+helper x = x + 1
+"#;
+
+    #[test]
+    fn test_extract_signatures() {
+        let signatures = HaskellSupport.extract_signatures(SOURCE, Visibility::All);
+        let summary: Vec<_> = signatures
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("User", SignatureKind::Struct),
+                ("Id", SignatureKind::Struct),
+                ("Name", SignatureKind::TypeAlias),
+                ("Greet", SignatureKind::Trait),
+                ("hello", SignatureKind::Method),
+                ("Greet", SignatureKind::Impl),
+                ("greet", SignatureKind::Function),
+                ("helper", SignatureKind::Function),
+            ]
+        );
+
+        assert_eq!(signatures[0].full_signature, "data User");
+        assert_eq!(signatures[0].doc.as_deref(), Some("A user."));
+        assert_eq!(signatures[3].full_signature, "class Greet a");
+        assert_eq!(signatures[4].full_signature, "hello :: a -> String");
+        assert_eq!(signatures[4].doc.as_deref(), Some("Says hello."));
+        assert_eq!(signatures[5].full_signature, "instance Greet User");
+        assert_eq!(signatures[6].full_signature, "greet :: User -> String");
+        assert_eq!(
+            signatures[6].doc.as_deref(),
+            Some("Greets a user.\nPolitely.")
+        );
+        assert_eq!(signatures[7].full_signature, "helper 0");
+    }
+
+    #[test]
+    fn test_visibility_follows_export_list() {
+        let private = HaskellSupport.extract_signatures(SOURCE, Visibility::Private);
+        let names: Vec<_> = private.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Id", "Name", "helper"]);
+
+        let all_public = "module Main where\n\nhelper x = x\n";
+        let public = HaskellSupport.extract_signatures(all_public, Visibility::Public);
+        assert_eq!(public.len(), 1);
+    }
+
+    #[test]
+    fn test_extract_structure() {
+        let structure = HaskellSupport.extract_structure(SOURCE);
+        assert_eq!(structure.functions, 3);
+        assert_eq!(structure.structs, 2);
+        assert_eq!(structure.traits, 1);
+        assert_eq!(structure.type_aliases, 1);
+        assert_eq!(
+            structure.imports,
+            vec![
+                "import qualified Data.Map as M",
+                "import Data.List (sortBy)"
+            ]
+        );
+        assert_eq!(structure.exports, vec!["User(..)", "Greet(..)", "greet"]);
+    }
+
+    #[test]
+    fn test_find_truncation_point() {
+        let point = HaskellSupport.find_truncation_point(SOURCE, 200);
+        assert!(point <= 200);
+        assert!(SOURCE[..point].ends_with("Int") || SOURCE[..point].ends_with(')'));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        assert!(HaskellSupport.supports_extension("hs"));
+        assert!(!HaskellSupport.supports_extension("lhs"));
+    }
+}
@@ -5,8 +5,8 @@ use tree_sitter::{Parser, Tree};
 
 #[cfg(feature = "tree-sitter-java")]
 use crate::tree_sitter::language_support::{
-    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility,
-    slice_signature_before_body,
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments, slice_signature_before_body,
 };
 
 pub struct JavaSupport;
@@ -57,7 +57,7 @@ impl LanguageSupport for JavaSupport {
             ..Default::default()
         };
 
-        self.extract_structure_from_node(&root, &mut structure);
+        self.extract_structure_from_node(source, &root, &mut structure);
         structure
     }
 
@@ -126,21 +126,34 @@ impl JavaSupport {
         }
     }
 
-    fn extract_structure_from_node(&self, node: &tree_sitter::Node, structure: &mut CodeStructure) {
+    /// The Javadoc block above a declaration.
+    fn doc_comment(&self, source: &str, node: &tree_sitter::Node) -> Option<String> {
+        let comments = preceding_comments(source, node, &[]);
+        clean_doc_comment(comments.into_iter().rev().find(|c| c.starts_with("/**")))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &tree_sitter::Node,
+        structure: &mut CodeStructure,
+    ) {
         match node.kind() {
             "method_declaration" => structure.functions += 1,
             "class_declaration" => structure.classes += 1,
             "interface_declaration" => structure.interfaces += 1,
             "enum_declaration" => structure.enums += 1,
             "import_declaration" => {
-                structure.imports.push("import".to_string());
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
             }
             _ => {}
         }
 
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            self.extract_structure_from_node(&child, structure);
+            self.extract_structure_from_node(source, &child, structure);
         }
     }
 
@@ -198,6 +211,7 @@ impl JavaSupport {
             return_type,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -233,6 +247,7 @@ impl JavaSupport {
             return_type: None,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -268,6 +283,7 @@ impl JavaSupport {
             return_type: None,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -303,6 +319,7 @@ impl JavaSupport {
             return_type: None,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -332,6 +349,7 @@ impl JavaSupport {
             return_type: None,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature,
         })
     }
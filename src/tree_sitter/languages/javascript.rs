@@ -3,8 +3,8 @@
 use tree_sitter::{Parser, Tree};
 
 use crate::tree_sitter::language_support::{
-    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility,
-    slice_signature_before_body,
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    is_require_call, preceding_comments, slice_signature_before_body,
 };
 
 pub struct JavaScriptSupport;
@@ -53,7 +53,7 @@ impl LanguageSupport for JavaScriptSupport {
             ..Default::default()
         };
 
-        self.extract_structure_from_node(&root, &mut structure);
+        self.extract_structure_from_node(source, &root, &mut structure);
         structure
     }
 
@@ -116,7 +116,22 @@ impl JavaScriptSupport {
         }
     }
 
-    fn extract_structure_from_node(&self, node: &tree_sitter::Node, structure: &mut CodeStructure) {
+    /// The JSDoc block above a declaration, or above the `export` wrapping it.
+    fn doc_comment(&self, source: &str, node: &tree_sitter::Node) -> Option<String> {
+        let anchor = match node.parent() {
+            Some(parent) if parent.kind() == "export_statement" => parent,
+            _ => *node,
+        };
+        let comments = preceding_comments(source, &anchor, &[]);
+        clean_doc_comment(comments.into_iter().rev().find(|c| c.starts_with("/**")))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &tree_sitter::Node,
+        structure: &mut CodeStructure,
+    ) {
         match node.kind() {
             "function_declaration" | "generator_function_declaration" | "function_expression" => {
                 structure.functions += 1;
@@ -125,17 +140,30 @@ impl JavaScriptSupport {
                 structure.classes += 1;
             }
             "import_statement" => {
-                structure.imports.push("import".to_string());
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
             }
             "export_statement" => {
                 structure.exports.push("export".to_string());
+                // `export { x } from "./y"` depends on another module as well.
+                if node.child_by_field_name("source").is_some() {
+                    structure
+                        .imports
+                        .push(source[node.start_byte()..node.end_byte()].to_string());
+                }
+            }
+            "call_expression" if is_require_call(source, node) => {
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
             }
             _ => {}
         }
 
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            self.extract_structure_from_node(&child, structure);
+            self.extract_structure_from_node(source, &child, structure);
         }
     }
 
@@ -161,6 +189,7 @@ impl JavaScriptSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -179,6 +208,7 @@ impl JavaScriptSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -243,6 +273,7 @@ impl JavaScriptSupport {
                         return_type: None,
                         visibility: Visibility::All,
                         line_number: child.start_position().row + 1,
+                        doc: self.doc_comment(source, &child),
                         full_signature,
                     });
                 } else if let Some(name) = self.find_child_text(&child, "identifier", source) {
@@ -254,6 +285,7 @@ impl JavaScriptSupport {
                         return_type: None,
                         visibility: Visibility::All,
                         line_number: child.start_position().row + 1,
+                        doc: self.doc_comment(source, &child),
                         full_signature,
                     });
                 }
@@ -389,10 +421,56 @@ class User {
         assert_eq!(classes[0].name, "User");
     }
 
+    #[test]
+    fn test_extract_structure_imports() {
+        let source = r#"
+import { a } from './a';
+export { b } from './b';
+export function c() {}
+const d = require('./d');
+"#;
+
+        let structure = JavaScriptSupport.extract_structure(source);
+        assert_eq!(
+            structure.imports,
+            vec![
+                "import { a } from './a';",
+                "export { b } from './b';",
+                "require('./d')"
+            ]
+        );
+        assert_eq!(structure.exports.len(), 2);
+    }
+
     #[test]
     fn test_file_extensions() {
         assert!(JavaScriptSupport.supports_extension("js"));
         assert!(JavaScriptSupport.supports_extension("mjs"));
         assert!(!JavaScriptSupport.supports_extension("ts"));
     }
+
+    #[test]
+    fn test_jsdoc_comments() {
+        let source = r#"
+/**
+ * Adds two numbers.
+ * @param {number} a
+ */
+export function add(a, b) {
+    return a + b;
+}
+
+// plain comment
+function helper() {}
+"#;
+
+        let signatures = JavaScriptSupport.extract_signatures(source, Visibility::All);
+        let add = signatures.iter().find(|s| s.name == "add").unwrap();
+        assert_eq!(
+            add.doc.as_deref(),
+            Some("Adds two numbers.\n@param {number} a")
+        );
+        let helper = signatures.iter().find(|s| s.name == "helper").unwrap();
+        assert_eq!(helper.doc, None);
+    }
 }
@@ -0,0 +1,389 @@
+//! Kotlin language support for tree-sitter.
+
+#[cfg(feature = "tree-sitter-kotlin")]
+use tree_sitter::{Node, Parser, Tree};
+
+#[cfg(feature = "tree-sitter-kotlin")]
+use crate::tree_sitter::language_support::{
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments, slice_signature_before_body,
+};
+
+pub struct KotlinSupport;
+
+#[cfg(feature = "tree-sitter-kotlin")]
+impl KotlinSupport {
+    fn get_language() -> tree_sitter::Language {
+        tree_sitter_kotlin_ng::LANGUAGE.into()
+    }
+}
+
+#[cfg(feature = "tree-sitter-kotlin")]
+impl LanguageSupport for KotlinSupport {
+    fn file_extensions(&self) -> &[&'static str] {
+        &["kt", "kts"]
+    }
+
+    fn parse(&self, source: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&Self::get_language()).ok()?;
+        parser.parse(source, None)
+    }
+
+    fn extract_signatures(&self, source: &str, visibility: Visibility) -> Vec<Signature> {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut signatures = Vec::new();
+
+        self.extract_signatures_from_node(source, &root, visibility, &mut signatures);
+
+        signatures.sort_by_key(|s| s.line_number);
+        signatures
+    }
+
+    fn extract_structure(&self, source: &str) -> CodeStructure {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return CodeStructure::default(),
+        };
+
+        let root = tree.root_node();
+        let mut structure = CodeStructure {
+            total_lines: source.lines().count(),
+            ..Default::default()
+        };
+
+        self.extract_structure_from_node(source, &root, &mut structure);
+        structure
+    }
+
+    fn find_truncation_point(&self, source: &str, max_bytes: usize) -> usize {
+        if source.len() <= max_bytes {
+            return source.len();
+        }
+
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return max_bytes,
+        };
+
+        let root = tree.root_node();
+        let mut best_end = 0;
+
+        let mut cursor = root.walk();
+        self.find_best_boundary(&mut cursor, max_bytes, &mut best_end);
+        drop(cursor);
+
+        if best_end == 0 { max_bytes } else { best_end }
+    }
+}
+
+#[cfg(feature = "tree-sitter-kotlin")]
+impl KotlinSupport {
+    fn extract_signatures_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        visibility: Visibility,
+        signatures: &mut Vec<Signature>,
+    ) {
+        if let Some(kind) = self.signature_kind(source, node)
+            && let Some(sig) = self.extract_signature(source, node, kind)
+            && sig.visibility.matches_filter(visibility)
+        {
+            signatures.push(sig);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_signatures_from_node(source, &child, visibility, signatures);
+        }
+    }
+
+    fn signature_kind(&self, source: &str, node: &Node) -> Option<SignatureKind> {
+        match node.kind() {
+            "class_declaration" if self.has_child_kind(node, "interface") => {
+                Some(SignatureKind::Interface)
+            }
+            "class_declaration" if self.has_modifier(source, node, "enum") => {
+                Some(SignatureKind::Enum)
+            }
+            "class_declaration" | "object_declaration" => Some(SignatureKind::Class),
+            "function_declaration" if self.is_member(node) => Some(SignatureKind::Method),
+            "function_declaration" => Some(SignatureKind::Function),
+            "type_alias" => Some(SignatureKind::TypeAlias),
+            "property_declaration" if self.is_constant(source, node) => {
+                Some(SignatureKind::Constant)
+            }
+            _ => None,
+        }
+    }
+
+    /// The KDoc block above a declaration.
+    fn doc_comment(&self, source: &str, node: &Node) -> Option<String> {
+        let comments = preceding_comments(source, node, &[]);
+        clean_doc_comment(comments.into_iter().rev().find(|c| c.starts_with("/**")))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        structure: &mut CodeStructure,
+    ) {
+        match self.signature_kind(source, node) {
+            Some(SignatureKind::Function | SignatureKind::Method) => structure.functions += 1,
+            Some(SignatureKind::Class) => structure.classes += 1,
+            Some(SignatureKind::Interface) => structure.interfaces += 1,
+            Some(SignatureKind::Enum) => structure.enums += 1,
+            Some(SignatureKind::TypeAlias) => structure.type_aliases += 1,
+            Some(SignatureKind::Constant) => structure.constants += 1,
+            _ if node.kind() == "import" && node.is_named() => {
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_structure_from_node(source, &child, structure);
+        }
+    }
+
+    fn has_child_kind(&self, node: &Node, kind: &str) -> bool {
+        let mut cursor = node.walk();
+        node.children(&mut cursor).any(|child| child.kind() == kind)
+    }
+
+    /// Whether the `modifiers` of `node` include `modifier` (`enum`, `const`, `private`, ...).
+    fn has_modifier(&self, source: &str, node: &Node, modifier: &str) -> bool {
+        node.named_children(&mut node.walk())
+            .filter(|child| child.kind() == "modifiers")
+            .any(|modifiers| {
+                modifiers
+                    .named_children(&mut modifiers.walk())
+                    .any(|m| &source[m.byte_range()] == modifier)
+            })
+    }
+
+    fn is_member(&self, node: &Node) -> bool {
+        node.parent()
+            .is_some_and(|parent| matches!(parent.kind(), "class_body" | "enum_class_body"))
+    }
+
+    /// A `const val`, or a top-level `val`.
+    fn is_constant(&self, source: &str, node: &Node) -> bool {
+        self.has_modifier(source, node, "const")
+            || (node.parent().is_some_and(|p| p.kind() == "source_file")
+                && self.has_child_kind(node, "val"))
+    }
+
+    /// Declarations are public unless `private`, `protected` or `internal`.
+    fn get_visibility(&self, source: &str, node: &Node) -> Visibility {
+        if ["private", "protected", "internal"]
+            .iter()
+            .any(|modifier| self.has_modifier(source, node, modifier))
+        {
+            Visibility::Private
+        } else {
+            Visibility::Public
+        }
+    }
+
+    fn extract_signature(
+        &self,
+        source: &str,
+        node: &Node,
+        kind: SignatureKind,
+    ) -> Option<Signature> {
+        let name_node = match kind {
+            SignatureKind::TypeAlias => node.child_by_field_name("type")?,
+            SignatureKind::Constant => node
+                .named_children(&mut node.walk())
+                .find(|child| child.kind() == "variable_declaration")?,
+            _ => node.child_by_field_name("name")?,
+        };
+        let name = source[name_node.byte_range()].to_string();
+        let params = node
+            .named_children(&mut node.walk())
+            .find(|child| child.kind() == "function_value_parameters")
+            .map(|p| source[p.byte_range()].to_string());
+
+        // Annotations, modifiers, generics, parameters, supertypes and the return type
+        let full_signature = match kind {
+            SignatureKind::Constant | SignatureKind::TypeAlias => {
+                let text = &source[node.byte_range()];
+                text.lines().next().unwrap_or(text).trim_end().to_string()
+            }
+            _ => slice_signature_before_body(
+                source,
+                node,
+                &["class_body", "enum_class_body", "function_body"],
+            )
+            .unwrap_or_else(|| source[node.byte_range()].trim_end().to_string()),
+        };
+
+        Some(Signature {
+            kind,
+            name,
+            params,
+            return_type: None,
+            visibility: self.get_visibility(source, node),
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature,
+        })
+    }
+
+    fn find_best_boundary(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        max_bytes: usize,
+        best_end: &mut usize,
+    ) {
+        loop {
+            let node = cursor.node();
+            let end_byte = node.end_byte();
+
+            if end_byte <= max_bytes && end_byte > *best_end {
+                let is_item = matches!(
+                    node.kind(),
+                    "function_declaration" | "class_declaration" | "object_declaration"
+                );
+                if is_item {
+                    *best_end = end_byte;
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.find_best_boundary(cursor, max_bytes, best_end);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+package com.example.app
+
+import kotlin.math.max
+import com.example.util.*
+
+/**
+ * A user.
+ */
+data class User(val name: String) : Base(), Comparable<User> {
+    private val secret = 1
+
+    /** Greets. */
+    fun greet(other: User): String = "hi"
+
+    internal fun helper() {}
+
+    companion object {
+        const val MAX = 10
+    }
+}
+
+interface Repo {
+    fun save(u: User)
+}
+
+enum class Color { RED, GREEN }
+object Registry { fun register() {} }
+typealias Id = String
+val limit = 42
+fun <T> identity(x: T): T { return x }
+"#;
+
+    #[test]
+    fn test_extract_signatures() {
+        let signatures = KotlinSupport.extract_signatures(SOURCE, Visibility::All);
+        let summary: Vec<_> = signatures
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("User", SignatureKind::Class),
+                ("greet", SignatureKind::Method),
+                ("helper", SignatureKind::Method),
+                ("MAX", SignatureKind::Constant),
+                ("Repo", SignatureKind::Interface),
+                ("save", SignatureKind::Method),
+                ("Color", SignatureKind::Enum),
+                ("Registry", SignatureKind::Class),
+                ("register", SignatureKind::Method),
+                ("Id", SignatureKind::TypeAlias),
+                ("limit", SignatureKind::Constant),
+                ("identity", SignatureKind::Function),
+            ]
+        );
+
+        let user = &signatures[0];
+        assert_eq!(
+            user.full_signature,
+            "data class User(val name: String) : Base(), Comparable<User>"
+        );
+        assert_eq!(user.doc.as_deref(), Some("A user."));
+        assert_eq!(
+            signatures[1].full_signature,
+            "fun greet(other: User): String"
+        );
+        assert_eq!(signatures[1].params.as_deref(), Some("(other: User)"));
+        assert_eq!(signatures[1].doc.as_deref(), Some("Greets."));
+        assert_eq!(signatures[5].full_signature, "fun save(u: User)");
+        assert_eq!(signatures[11].full_signature, "fun <T> identity(x: T): T");
+    }
+
+    #[test]
+    fn test_visibility() {
+        let private = KotlinSupport.extract_signatures(SOURCE, Visibility::Private);
+        let names: Vec<_> = private.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["helper"]);
+    }
+
+    #[test]
+    fn test_extract_structure() {
+        let structure = KotlinSupport.extract_structure(SOURCE);
+        assert_eq!(structure.functions, 5);
+        assert_eq!(structure.classes, 2);
+        assert_eq!(structure.interfaces, 1);
+        assert_eq!(structure.enums, 1);
+        assert_eq!(structure.constants, 2);
+        assert_eq!(structure.type_aliases, 1);
+        assert_eq!(
+            structure.imports,
+            vec!["import kotlin.math.max", "import com.example.util.*"]
+        );
+    }
+
+    #[test]
+    fn test_find_truncation_point() {
+        let point = KotlinSupport.find_truncation_point(SOURCE, 400);
+        assert!(point <= 400);
+        assert!(SOURCE[..point].ends_with('}'));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        assert!(KotlinSupport.supports_extension("kt"));
+        assert!(KotlinSupport.supports_extension("kts"));
+        assert!(!KotlinSupport.supports_extension("java"));
+    }
+}
@@ -0,0 +1,296 @@
+//! Lua language support for tree-sitter.
+
+#[cfg(feature = "tree-sitter-lua")]
+use tree_sitter::{Node, Parser, Tree};
+
+#[cfg(feature = "tree-sitter-lua")]
+use crate::tree_sitter::language_support::{
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments,
+};
+
+pub struct LuaSupport;
+
+#[cfg(feature = "tree-sitter-lua")]
+impl LuaSupport {
+    fn get_language() -> tree_sitter::Language {
+        tree_sitter_lua::LANGUAGE.into()
+    }
+}
+
+#[cfg(feature = "tree-sitter-lua")]
+impl LanguageSupport for LuaSupport {
+    fn file_extensions(&self) -> &[&'static str] {
+        &["lua"]
+    }
+
+    fn parse(&self, source: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&Self::get_language()).ok()?;
+        parser.parse(source, None)
+    }
+
+    fn extract_signatures(&self, source: &str, visibility: Visibility) -> Vec<Signature> {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut signatures = Vec::new();
+
+        self.extract_signatures_from_node(source, &root, visibility, &mut signatures);
+
+        signatures.sort_by_key(|s| s.line_number);
+        signatures
+    }
+
+    fn extract_structure(&self, source: &str) -> CodeStructure {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return CodeStructure::default(),
+        };
+
+        let root = tree.root_node();
+        let mut structure = CodeStructure {
+            total_lines: source.lines().count(),
+            ..Default::default()
+        };
+
+        self.extract_structure_from_node(source, &root, &mut structure);
+        structure
+    }
+
+    fn find_truncation_point(&self, source: &str, max_bytes: usize) -> usize {
+        if source.len() <= max_bytes {
+            return source.len();
+        }
+
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return max_bytes,
+        };
+
+        let root = tree.root_node();
+        let mut best_end = 0;
+
+        // Top-level statements are the safe cut points
+        let mut cursor = root.walk();
+        for child in root.children(&mut cursor) {
+            if child.end_byte() <= max_bytes && child.kind() != "comment" {
+                best_end = child.end_byte();
+            }
+        }
+
+        if best_end == 0 { max_bytes } else { best_end }
+    }
+}
+
+#[cfg(feature = "tree-sitter-lua")]
+impl LuaSupport {
+    fn extract_signatures_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        visibility: Visibility,
+        signatures: &mut Vec<Signature>,
+    ) {
+        let signature = match node.kind() {
+            "function_declaration" => self.extract_function_signature(source, node),
+            "assignment_statement" => self.extract_assigned_function(source, node),
+            _ => None,
+        };
+        if let Some(sig) = signature
+            && sig.visibility.matches_filter(visibility)
+        {
+            signatures.push(sig);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_signatures_from_node(source, &child, visibility, signatures);
+        }
+    }
+
+    /// The `--`/`---` comments directly above a function.
+    fn doc_comment(&self, source: &str, node: &Node) -> Option<String> {
+        clean_doc_comment(preceding_comments(source, node, &[]))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        structure: &mut CodeStructure,
+    ) {
+        match node.kind() {
+            "function_declaration" => structure.functions += 1,
+            "assignment_statement" if self.assigned_function(node).is_some() => {
+                structure.functions += 1
+            }
+            "function_call" if self.is_require(source, node) => {
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_structure_from_node(source, &child, structure);
+        }
+    }
+
+    fn is_require(&self, source: &str, node: &Node) -> bool {
+        node.child_by_field_name("name")
+            .is_some_and(|name| &source[name.byte_range()] == "require")
+    }
+
+    /// `local` functions are private to their chunk.
+    fn get_visibility(&self, source: &str, node: &Node) -> Visibility {
+        let statement = match node.parent() {
+            Some(parent) if parent.kind() == "variable_declaration" => parent,
+            _ => *node,
+        };
+        if source[statement.byte_range()].starts_with("local") {
+            Visibility::Private
+        } else {
+            Visibility::Public
+        }
+    }
+
+    /// The function in `name = function(...) ... end`, if that is what is assigned.
+    fn assigned_function<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        let values = node
+            .named_children(&mut node.walk())
+            .find(|child| child.kind() == "expression_list")?;
+        let value = values.named_child(0)?;
+        (value.kind() == "function_definition").then_some(value)
+    }
+
+    fn extract_function_signature(&self, source: &str, node: &Node) -> Option<Signature> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = source[name_node.byte_range()].to_string();
+        let parameters = node.child_by_field_name("parameters");
+        let kind = if name_node.kind() == "method_index_expression" {
+            SignatureKind::Method
+        } else {
+            SignatureKind::Function
+        };
+        let statement = match node.parent() {
+            Some(parent) if parent.kind() == "variable_declaration" => parent,
+            _ => *node,
+        };
+        let end = parameters.map_or(name_node.end_byte(), |p| p.end_byte());
+
+        Some(Signature {
+            kind,
+            name,
+            params: parameters.map(|p| source[p.byte_range()].to_string()),
+            return_type: None,
+            visibility: self.get_visibility(source, node),
+            line_number: statement.start_position().row + 1,
+            doc: self.doc_comment(source, &statement),
+            full_signature: source[statement.start_byte()..end].to_string(),
+        })
+    }
+
+    fn extract_assigned_function(&self, source: &str, node: &Node) -> Option<Signature> {
+        let function = self.assigned_function(node)?;
+        let target = node
+            .named_children(&mut node.walk())
+            .find(|child| child.kind() == "variable_list")?;
+        let parameters = function.child_by_field_name("parameters")?;
+        let statement = match node.parent() {
+            Some(parent) if parent.kind() == "variable_declaration" => parent,
+            _ => *node,
+        };
+
+        Some(Signature {
+            kind: SignatureKind::Function,
+            name: source[target.byte_range()].to_string(),
+            params: Some(source[parameters.byte_range()].to_string()),
+            return_type: None,
+            visibility: self.get_visibility(source, node),
+            line_number: statement.start_position().row + 1,
+            doc: self.doc_comment(source, &statement),
+            full_signature: source[statement.start_byte()..parameters.end_byte()].to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"local json = require("json")
+local util = require "util"
+
+local M = {}
+
+--- Creates a user.
+-- Second line.
+function M.new(name)
+  return { name = name }
+end
+
+function M:greet(other)
+  return "hi"
+end
+
+local function helper(x)
+  return x
+end
+
+M.handler = function(evt) end
+
+return M
+"#;
+
+    #[test]
+    fn test_extract_signatures() {
+        let signatures = LuaSupport.extract_signatures(SOURCE, Visibility::All);
+        let names: Vec<_> = signatures.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["M.new", "M:greet", "helper", "M.handler"]);
+
+        assert_eq!(signatures[0].full_signature, "function M.new(name)");
+        assert_eq!(
+            signatures[0].doc.as_deref(),
+            Some("Creates a user.\nSecond line.")
+        );
+        assert_eq!(signatures[1].kind, SignatureKind::Method);
+        assert_eq!(signatures[2].full_signature, "local function helper(x)");
+        assert_eq!(signatures[3].full_signature, "M.handler = function(evt)");
+    }
+
+    #[test]
+    fn test_visibility() {
+        let private = LuaSupport.extract_signatures(SOURCE, Visibility::Private);
+        let names: Vec<_> = private.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["helper"]);
+    }
+
+    #[test]
+    fn test_extract_structure() {
+        let structure = LuaSupport.extract_structure(SOURCE);
+        assert_eq!(structure.functions, 4);
+        assert_eq!(
+            structure.imports,
+            vec!["require(\"json\")", "require \"util\""]
+        );
+    }
+
+    #[test]
+    fn test_find_truncation_point() {
+        let point = LuaSupport.find_truncation_point(SOURCE, 160);
+        assert!(point <= 160);
+        assert!(SOURCE[..point].ends_with("end"));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        assert!(LuaSupport.supports_extension("lua"));
+        assert!(!LuaSupport.supports_extension("luac"));
+    }
+}
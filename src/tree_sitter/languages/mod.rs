@@ -29,6 +29,36 @@ mod c;
 #[cfg(feature = "tree-sitter-cpp")]
 mod cpp;
 
+#[cfg(feature = "tree-sitter-ruby")]
+mod ruby;
+
+#[cfg(feature = "tree-sitter-csharp")]
+mod csharp;
+
+#[cfg(feature = "tree-sitter-php")]
+mod php;
+
+#[cfg(feature = "tree-sitter-kotlin")]
+mod kotlin;
+
+#[cfg(feature = "tree-sitter-swift")]
+mod swift;
+
+#[cfg(feature = "tree-sitter-bash")]
+mod bash;
+
+#[cfg(feature = "tree-sitter-lua")]
+mod lua;
+
+#[cfg(feature = "tree-sitter-zig")]
+mod zig;
+
+#[cfg(feature = "tree-sitter-scala")]
+mod scala;
+
+#[cfg(feature = "tree-sitter-haskell")]
+mod haskell;
+
 #[cfg(feature = "tree-sitter-rust")]
 static RUST_SUPPORT: rust::RustSupport = rust::RustSupport;
 
@@ -56,6 +86,36 @@ static C_SUPPORT: c::CSupport = c::CSupport;
 #[cfg(feature = "tree-sitter-cpp")]
 static CPP_SUPPORT: cpp::CppSupport = cpp::CppSupport;
 
+#[cfg(feature = "tree-sitter-ruby")]
+static RUBY_SUPPORT: ruby::RubySupport = ruby::RubySupport;
+
+#[cfg(feature = "tree-sitter-csharp")]
+static CSHARP_SUPPORT: csharp::CSharpSupport = csharp::CSharpSupport;
+
+#[cfg(feature = "tree-sitter-php")]
+static PHP_SUPPORT: php::PhpSupport = php::PhpSupport;
+
+#[cfg(feature = "tree-sitter-kotlin")]
+static KOTLIN_SUPPORT: kotlin::KotlinSupport = kotlin::KotlinSupport;
+
+#[cfg(feature = "tree-sitter-swift")]
+static SWIFT_SUPPORT: swift::SwiftSupport = swift::SwiftSupport;
+
+#[cfg(feature = "tree-sitter-bash")]
+static BASH_SUPPORT: bash::BashSupport = bash::BashSupport;
+
+#[cfg(feature = "tree-sitter-lua")]
+static LUA_SUPPORT: lua::LuaSupport = lua::LuaSupport;
+
+#[cfg(feature = "tree-sitter-zig")]
+static ZIG_SUPPORT: zig::ZigSupport = zig::ZigSupport;
+
+#[cfg(feature = "tree-sitter-scala")]
+static SCALA_SUPPORT: scala::ScalaSupport = scala::ScalaSupport;
+
+#[cfg(feature = "tree-sitter-haskell")]
+static HASKELL_SUPPORT: haskell::HaskellSupport = haskell::HaskellSupport;
+
 #[cfg(feature = "tree-sitter-base")]
 pub fn get_language_support(ext: &str) -> Option<&'static dyn LanguageSupport> {
     match ext.to_lowercase().as_str() {
@@ -86,6 +146,36 @@ pub fn get_language_support(ext: &str) -> Option<&'static dyn LanguageSupport> {
         #[cfg(feature = "tree-sitter-cpp")]
         "cpp" | "cxx" | "cc" | "hpp" | "hxx" | "hh" => Some(&CPP_SUPPORT),
 
+        #[cfg(feature = "tree-sitter-ruby")]
+        "rb" | "rake" | "gemspec" => Some(&RUBY_SUPPORT),
+
+        #[cfg(feature = "tree-sitter-csharp")]
+        "cs" => Some(&CSHARP_SUPPORT),
+
+        #[cfg(feature = "tree-sitter-php")]
+        "php" => Some(&PHP_SUPPORT),
+
+        #[cfg(feature = "tree-sitter-kotlin")]
+        "kt" | "kts" => Some(&KOTLIN_SUPPORT),
+
+        #[cfg(feature = "tree-sitter-swift")]
+        "swift" => Some(&SWIFT_SUPPORT),
+
+        #[cfg(feature = "tree-sitter-bash")]
+        "sh" | "bash" => Some(&BASH_SUPPORT),
+
+        #[cfg(feature = "tree-sitter-lua")]
+        "lua" => Some(&LUA_SUPPORT),
+
+        #[cfg(feature = "tree-sitter-zig")]
+        "zig" => Some(&ZIG_SUPPORT),
+
+        #[cfg(feature = "tree-sitter-scala")]
+        "scala" | "sc" => Some(&SCALA_SUPPORT),
+
+        #[cfg(feature = "tree-sitter-haskell")]
+        "hs" => Some(&HASKELL_SUPPORT),
+
         _ => None,
     }
 }
@@ -126,6 +216,36 @@ pub fn supported_extensions() -> Vec<&'static str> {
     #[cfg(feature = "tree-sitter-cpp")]
     extensions.extend(CPP_SUPPORT.file_extensions());
 
+    #[cfg(feature = "tree-sitter-ruby")]
+    extensions.extend(RUBY_SUPPORT.file_extensions());
+
+    #[cfg(feature = "tree-sitter-csharp")]
+    extensions.extend(CSHARP_SUPPORT.file_extensions());
+
+    #[cfg(feature = "tree-sitter-php")]
+    extensions.extend(PHP_SUPPORT.file_extensions());
+
+    #[cfg(feature = "tree-sitter-kotlin")]
+    extensions.extend(KOTLIN_SUPPORT.file_extensions());
+
+    #[cfg(feature = "tree-sitter-swift")]
+    extensions.extend(SWIFT_SUPPORT.file_extensions());
+
+    #[cfg(feature = "tree-sitter-bash")]
+    extensions.extend(BASH_SUPPORT.file_extensions());
+
+    #[cfg(feature = "tree-sitter-lua")]
+    extensions.extend(LUA_SUPPORT.file_extensions());
+
+    #[cfg(feature = "tree-sitter-zig")]
+    extensions.extend(ZIG_SUPPORT.file_extensions());
+
+    #[cfg(feature = "tree-sitter-scala")]
+    extensions.extend(SCALA_SUPPORT.file_extensions());
+
+    #[cfg(feature = "tree-sitter-haskell")]
+    extensions.extend(HASKELL_SUPPORT.file_extensions());
+
     extensions
 }
 
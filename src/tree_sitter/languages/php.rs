@@ -0,0 +1,365 @@
+//! PHP language support for tree-sitter.
+
+#[cfg(feature = "tree-sitter-php")]
+use tree_sitter::{Node, Parser, Tree};
+
+#[cfg(feature = "tree-sitter-php")]
+use crate::tree_sitter::language_support::{
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments,
+};
+
+pub struct PhpSupport;
+
+#[cfg(feature = "tree-sitter-php")]
+impl PhpSupport {
+    fn get_language() -> tree_sitter::Language {
+        tree_sitter_php::LANGUAGE_PHP.into()
+    }
+}
+
+#[cfg(feature = "tree-sitter-php")]
+impl LanguageSupport for PhpSupport {
+    fn file_extensions(&self) -> &[&'static str] {
+        &["php"]
+    }
+
+    fn parse(&self, source: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&Self::get_language()).ok()?;
+        parser.parse(source, None)
+    }
+
+    fn extract_signatures(&self, source: &str, visibility: Visibility) -> Vec<Signature> {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut signatures = Vec::new();
+
+        self.extract_signatures_from_node(source, &root, visibility, &mut signatures);
+
+        signatures.sort_by_key(|s| s.line_number);
+        signatures
+    }
+
+    fn extract_structure(&self, source: &str) -> CodeStructure {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return CodeStructure::default(),
+        };
+
+        let root = tree.root_node();
+        let mut structure = CodeStructure {
+            total_lines: source.lines().count(),
+            ..Default::default()
+        };
+
+        self.extract_structure_from_node(source, &root, &mut structure);
+        structure
+    }
+
+    fn find_truncation_point(&self, source: &str, max_bytes: usize) -> usize {
+        if source.len() <= max_bytes {
+            return source.len();
+        }
+
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return max_bytes,
+        };
+
+        let root = tree.root_node();
+        let mut best_end = 0;
+
+        let mut cursor = root.walk();
+        self.find_best_boundary(&mut cursor, max_bytes, &mut best_end);
+        drop(cursor);
+
+        if best_end == 0 { max_bytes } else { best_end }
+    }
+}
+
+#[cfg(feature = "tree-sitter-php")]
+impl PhpSupport {
+    fn extract_signatures_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        visibility: Visibility,
+        signatures: &mut Vec<Signature>,
+    ) {
+        let signature = match node.kind() {
+            "function_definition" => self.extract_signature(source, node, SignatureKind::Function),
+            "method_declaration" => self.extract_signature(source, node, SignatureKind::Method),
+            "class_declaration" => self.extract_signature(source, node, SignatureKind::Class),
+            "interface_declaration" => {
+                self.extract_signature(source, node, SignatureKind::Interface)
+            }
+            "trait_declaration" => self.extract_signature(source, node, SignatureKind::Trait),
+            "enum_declaration" => self.extract_signature(source, node, SignatureKind::Enum),
+            "const_declaration" => self.extract_constant_signature(source, node),
+            _ => None,
+        };
+        if let Some(sig) = signature
+            && sig.visibility.matches_filter(visibility)
+        {
+            signatures.push(sig);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_signatures_from_node(source, &child, visibility, signatures);
+        }
+    }
+
+    /// The PHPDoc block above a declaration.
+    fn doc_comment(&self, source: &str, node: &Node) -> Option<String> {
+        let comments = preceding_comments(source, node, &[]);
+        clean_doc_comment(comments.into_iter().rev().find(|c| c.starts_with("/**")))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        structure: &mut CodeStructure,
+    ) {
+        match node.kind() {
+            "function_definition" | "method_declaration" => structure.functions += 1,
+            "class_declaration" => structure.classes += 1,
+            "interface_declaration" => structure.interfaces += 1,
+            "trait_declaration" => structure.traits += 1,
+            "enum_declaration" => structure.enums += 1,
+            "const_element" => structure.constants += 1,
+            "namespace_use_declaration"
+            | "require_expression"
+            | "require_once_expression"
+            | "include_expression"
+            | "include_once_expression" => {
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_structure_from_node(source, &child, structure);
+        }
+    }
+
+    /// Members without a visibility modifier are public.
+    fn get_visibility(&self, source: &str, node: &Node) -> Visibility {
+        let mut cursor = node.walk();
+        let restricted = node.children(&mut cursor).any(|child| {
+            child.kind() == "visibility_modifier"
+                && matches!(&source[child.byte_range()], "private" | "protected")
+        });
+        if restricted {
+            Visibility::Private
+        } else {
+            Visibility::Public
+        }
+    }
+
+    fn extract_signature(
+        &self,
+        source: &str,
+        node: &Node,
+        kind: SignatureKind,
+    ) -> Option<Signature> {
+        let name = source[node.child_by_field_name("name")?.byte_range()].to_string();
+        let params = node
+            .child_by_field_name("parameters")
+            .map(|p| source[p.byte_range()].to_string());
+        let return_type = node
+            .child_by_field_name("return_type")
+            .map(|r| source[r.byte_range()].to_string());
+
+        // Attributes, modifiers, parameters and the return type, up to the body
+        let end = node
+            .child_by_field_name("body")
+            .map_or(node.end_byte(), |body| body.start_byte());
+        let full_signature = source[node.start_byte()..end]
+            .trim_end()
+            .trim_end_matches(';')
+            .trim_end()
+            .to_string();
+
+        Some(Signature {
+            kind,
+            name,
+            params,
+            return_type,
+            visibility: self.get_visibility(source, node),
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature,
+        })
+    }
+
+    fn extract_constant_signature(&self, source: &str, node: &Node) -> Option<Signature> {
+        let element = node
+            .named_children(&mut node.walk())
+            .find(|child| child.kind() == "const_element")?;
+        let name_node = element
+            .named_children(&mut element.walk())
+            .find(|child| child.kind() == "name")?;
+        let text = &source[node.byte_range()];
+
+        Some(Signature {
+            kind: SignatureKind::Constant,
+            name: source[name_node.byte_range()].to_string(),
+            params: None,
+            return_type: None,
+            visibility: self.get_visibility(source, node),
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature: text.lines().next().unwrap_or(text).trim_end().to_string(),
+        })
+    }
+
+    fn find_best_boundary(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        max_bytes: usize,
+        best_end: &mut usize,
+    ) {
+        loop {
+            let node = cursor.node();
+            let end_byte = node.end_byte();
+
+            if end_byte <= max_bytes && end_byte > *best_end {
+                let is_item = matches!(
+                    node.kind(),
+                    "function_definition"
+                        | "method_declaration"
+                        | "class_declaration"
+                        | "interface_declaration"
+                        | "trait_declaration"
+                        | "enum_declaration"
+                );
+                if is_item {
+                    *best_end = end_byte;
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.find_best_boundary(cursor, max_bytes, best_end);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"<?php
+namespace App\Models;
+
+use App\Contracts\Repo;
+require_once 'helpers.php';
+
+/**
+ * A user.
+ */
+final class User extends Model implements Repo
+{
+    public const MAX = 10;
+
+    /** Finds a user. */
+    public static function find(int $id): ?self { return null; }
+
+    protected function hidden() {}
+    function implicit() {}
+}
+
+interface Repo { public function save(User $u): void; }
+trait Greets { public function hi() {} }
+
+function helper($a, ...$rest) { return $a; }
+"#;
+
+    #[test]
+    fn test_extract_signatures() {
+        let signatures = PhpSupport.extract_signatures(SOURCE, Visibility::All);
+        let names: Vec<_> = signatures.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "User", "MAX", "find", "hidden", "implicit", "Repo", "save", "Greets", "hi",
+                "helper"
+            ]
+        );
+
+        let class = &signatures[0];
+        assert_eq!(
+            class.full_signature,
+            "final class User extends Model implements Repo"
+        );
+        assert_eq!(class.doc.as_deref(), Some("A user."));
+
+        let find = &signatures[2];
+        assert_eq!(find.kind, SignatureKind::Method);
+        assert_eq!(
+            find.full_signature,
+            "public static function find(int $id): ?self"
+        );
+        assert_eq!(find.return_type.as_deref(), Some("?self"));
+        assert_eq!(find.doc.as_deref(), Some("Finds a user."));
+
+        assert_eq!(
+            signatures[6].full_signature,
+            "public function save(User $u): void"
+        );
+        assert_eq!(signatures[7].kind, SignatureKind::Trait);
+        assert_eq!(signatures[9].kind, SignatureKind::Function);
+    }
+
+    #[test]
+    fn test_visibility() {
+        let private = PhpSupport.extract_signatures(SOURCE, Visibility::Private);
+        let names: Vec<_> = private.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["hidden"]);
+
+        let public = PhpSupport.extract_signatures(SOURCE, Visibility::Public);
+        assert!(public.iter().any(|s| s.name == "implicit"));
+    }
+
+    #[test]
+    fn test_extract_structure() {
+        let structure = PhpSupport.extract_structure(SOURCE);
+        assert_eq!(structure.functions, 6);
+        assert_eq!(structure.classes, 1);
+        assert_eq!(structure.interfaces, 1);
+        assert_eq!(structure.traits, 1);
+        assert_eq!(structure.constants, 1);
+        assert_eq!(
+            structure.imports,
+            vec!["use App\\Contracts\\Repo;", "require_once 'helpers.php'"]
+        );
+    }
+
+    #[test]
+    fn test_find_truncation_point() {
+        let point = PhpSupport.find_truncation_point(SOURCE, 420);
+        assert!(point <= 420);
+        assert!(SOURCE[..point].ends_with('}'));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        assert!(PhpSupport.supports_extension("php"));
+        assert!(!PhpSupport.supports_extension("py"));
+    }
+}
@@ -57,7 +57,7 @@ impl LanguageSupport for PythonSupport {
             ..Default::default()
         };
 
-        self.extract_structure_from_node(&root, &mut structure);
+        self.extract_structure_from_node(source, &root, &mut structure);
         structure
     }
 
@@ -143,19 +143,43 @@ impl PythonSupport {
         }
     }
 
-    fn extract_structure_from_node(&self, node: &tree_sitter::Node, structure: &mut CodeStructure) {
+    /// The docstring: a string literal as the first statement of the body.
+    fn docstring(&self, source: &str, node: &tree_sitter::Node) -> Option<String> {
+        let first = node.child_by_field_name("body")?.named_child(0)?;
+        if first.kind() != "expression_statement" {
+            return None;
+        }
+        let string = first.named_child(0).filter(|n| n.kind() == "string")?;
+        let text =
+            source[string.byte_range()].trim_start_matches(|c: char| c.is_ascii_alphabetic());
+        let text = ["\"\"\"", "'''", "\"", "'"]
+            .iter()
+            .find_map(|quote| text.strip_prefix(quote)?.strip_suffix(quote))?;
+        let doc = text.lines().map(str::trim).collect::<Vec<_>>().join("\n");
+        let doc = doc.trim();
+        (!doc.is_empty()).then(|| doc.to_string())
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &tree_sitter::Node,
+        structure: &mut CodeStructure,
+    ) {
         match node.kind() {
             "function_definition" => structure.functions += 1,
             "class_definition" => structure.classes += 1,
             "import_statement" | "import_from_statement" => {
-                structure.imports.push("import".to_string());
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
             }
             _ => {}
         }
 
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            self.extract_structure_from_node(&child, structure);
+            self.extract_structure_from_node(source, &child, structure);
         }
     }
 
@@ -218,6 +242,7 @@ impl PythonSupport {
             return_type: None, // Captured via byte-slicing in full_sig
             visibility: Visibility::All,
             line_number: slice_node.start_position().row + 1,
+            doc: self.docstring(source, node),
             full_signature: full_sig,
         })
     }
@@ -246,6 +271,7 @@ impl PythonSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.docstring(source, node),
             full_signature: full_sig,
         })
     }
@@ -366,4 +392,26 @@ class User:
         assert!(PythonSupport.supports_extension("pyw"));
         assert!(!PythonSupport.supports_extension("rs"));
     }
+
+    #[test]
+    fn test_docstrings() {
+        let source = r#"
+def fetch(url):
+    """Fetch a URL.
+
+    Raises on network errors.
+    """
+    return url
+
+def plain():
+    return 1
+"#;
+
+        let signatures = PythonSupport.extract_signatures(source, Visibility::All);
+        assert_eq!(
+            signatures[0].doc.as_deref(),
+            Some("Fetch a URL.\n\nRaises on network errors.")
+        );
+        assert_eq!(signatures[1].doc, None);
+    }
 }
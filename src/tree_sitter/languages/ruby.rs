@@ -0,0 +1,432 @@
+//! Ruby language support for tree-sitter.
+
+#[cfg(feature = "tree-sitter-ruby")]
+use tree_sitter::{Node, Parser, Tree};
+
+#[cfg(feature = "tree-sitter-ruby")]
+use crate::tree_sitter::language_support::{
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments,
+};
+
+pub struct RubySupport;
+
+#[cfg(feature = "tree-sitter-ruby")]
+impl RubySupport {
+    fn get_language() -> tree_sitter::Language {
+        tree_sitter_ruby::LANGUAGE.into()
+    }
+}
+
+#[cfg(feature = "tree-sitter-ruby")]
+impl LanguageSupport for RubySupport {
+    fn file_extensions(&self) -> &[&'static str] {
+        &["rb", "rake", "gemspec"]
+    }
+
+    fn parse(&self, source: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&Self::get_language()).ok()?;
+        parser.parse(source, None)
+    }
+
+    fn extract_signatures(&self, source: &str, visibility: Visibility) -> Vec<Signature> {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut signatures = Vec::new();
+
+        self.extract_signatures_from_node(source, &root, visibility, &mut signatures);
+
+        signatures.sort_by_key(|s| s.line_number);
+        signatures
+    }
+
+    fn extract_structure(&self, source: &str) -> CodeStructure {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return CodeStructure::default(),
+        };
+
+        let root = tree.root_node();
+        let mut structure = CodeStructure {
+            total_lines: source.lines().count(),
+            ..Default::default()
+        };
+
+        self.extract_structure_from_node(source, &root, &mut structure);
+        structure
+    }
+
+    fn find_truncation_point(&self, source: &str, max_bytes: usize) -> usize {
+        if source.len() <= max_bytes {
+            return source.len();
+        }
+
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return max_bytes,
+        };
+
+        let root = tree.root_node();
+        let mut best_end = 0;
+
+        let mut cursor = root.walk();
+        self.find_best_boundary(&mut cursor, max_bytes, &mut best_end);
+        drop(cursor);
+
+        if best_end == 0 { max_bytes } else { best_end }
+    }
+}
+
+#[cfg(feature = "tree-sitter-ruby")]
+impl RubySupport {
+    fn extract_signatures_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        visibility: Visibility,
+        signatures: &mut Vec<Signature>,
+    ) {
+        let signature = match node.kind() {
+            "method" | "singleton_method" => self.extract_method_signature(source, node),
+            "class" => self.extract_type_signature(source, node, SignatureKind::Class),
+            "module" => self.extract_type_signature(source, node, SignatureKind::Module),
+            "assignment" => self.extract_constant_signature(source, node),
+            _ => None,
+        };
+        if let Some(sig) = signature
+            && sig.visibility.matches_filter(visibility)
+        {
+            signatures.push(sig);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_signatures_from_node(source, &child, visibility, signatures);
+        }
+    }
+
+    /// The `#` comment block above a definition.
+    fn doc_comment(&self, source: &str, node: &Node) -> Option<String> {
+        // `private def foo` wraps the method in a call
+        let anchor = match node.parent().and_then(|p| p.parent()) {
+            Some(call) if call.kind() == "call" && self.is_visibility_call(source, &call) => call,
+            _ => *node,
+        };
+        clean_doc_comment(preceding_comments(source, &anchor, &[]))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        structure: &mut CodeStructure,
+    ) {
+        match node.kind() {
+            "method" | "singleton_method" => structure.functions += 1,
+            // `class` is also the keyword token inside the node
+            "class" if node.is_named() => structure.classes += 1,
+            "assignment" if self.is_constant_assignment(node) => structure.constants += 1,
+            "call" if self.is_require(source, node) => {
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_structure_from_node(source, &child, structure);
+        }
+    }
+
+    /// Methods are public unless made private by a bare `private`/`protected`
+    /// earlier in the class body or by a `private def ...` wrapper.
+    fn get_visibility(&self, source: &str, node: &Node) -> Visibility {
+        if node.kind() == "singleton_method" {
+            return Visibility::Public;
+        }
+        if let Some(call) = node.parent().and_then(|p| p.parent())
+            && call.kind() == "call"
+            && self.is_visibility_call(source, &call)
+            && let Some(method) = call.child_by_field_name("method")
+        {
+            return self.visibility_keyword(source, &method);
+        }
+
+        let mut current = node.prev_named_sibling();
+        while let Some(sibling) = current {
+            if sibling.kind() == "identifier"
+                && matches!(
+                    &source[sibling.byte_range()],
+                    "private" | "protected" | "public"
+                )
+            {
+                return self.visibility_keyword(source, &sibling);
+            }
+            current = sibling.prev_named_sibling();
+        }
+        Visibility::Public
+    }
+
+    fn visibility_keyword(&self, source: &str, node: &Node) -> Visibility {
+        if &source[node.byte_range()] == "public" {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        }
+    }
+
+    fn is_visibility_call(&self, source: &str, node: &Node) -> bool {
+        node.child_by_field_name("method")
+            .is_some_and(|m| matches!(&source[m.byte_range()], "private" | "protected" | "public"))
+    }
+
+    fn is_require(&self, source: &str, node: &Node) -> bool {
+        node.child_by_field_name("receiver").is_none()
+            && node.child_by_field_name("method").is_some_and(|m| {
+                matches!(
+                    &source[m.byte_range()],
+                    "require" | "require_relative" | "load"
+                )
+            })
+    }
+
+    /// `NAME = value` directly inside a class, module or the file.
+    fn is_constant_assignment(&self, node: &Node) -> bool {
+        let is_constant = node
+            .child_by_field_name("left")
+            .is_some_and(|left| left.kind() == "constant");
+        let in_scope = node.parent().is_some_and(|parent| match parent.kind() {
+            "program" => true,
+            "body_statement" => parent
+                .parent()
+                .is_some_and(|p| matches!(p.kind(), "class" | "module")),
+            _ => false,
+        });
+        is_constant && in_scope
+    }
+
+    fn is_in_type(&self, node: &Node) -> bool {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            match parent.kind() {
+                "class" | "module" | "singleton_class" => return true,
+                "method" | "singleton_method" => return false,
+                _ => current = parent.parent(),
+            }
+        }
+        false
+    }
+
+    fn extract_method_signature(&self, source: &str, node: &Node) -> Option<Signature> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = source[name_node.byte_range()].to_string();
+        let params = node
+            .child_by_field_name("parameters")
+            .map(|p| source[p.byte_range()].to_string());
+        let kind = if node.kind() == "singleton_method" || self.is_in_type(node) {
+            SignatureKind::Method
+        } else {
+            SignatureKind::Function
+        };
+
+        // `def name(params)`, without the body or a one-line `; end`
+        let header_end = node
+            .child_by_field_name("parameters")
+            .map_or(name_node.end_byte(), |p| p.end_byte());
+        let full_signature = source[node.start_byte()..header_end].to_string();
+
+        Some(Signature {
+            kind,
+            name,
+            params,
+            return_type: None,
+            visibility: self.get_visibility(source, node),
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature,
+        })
+    }
+
+    fn extract_type_signature(
+        &self,
+        source: &str,
+        node: &Node,
+        kind: SignatureKind,
+    ) -> Option<Signature> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = source[name_node.byte_range()].to_string();
+        let header_end = node
+            .child_by_field_name("superclass")
+            .map_or(name_node.end_byte(), |s| s.end_byte());
+
+        Some(Signature {
+            kind,
+            name,
+            params: None,
+            return_type: None,
+            visibility: Visibility::Public,
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature: source[node.start_byte()..header_end].to_string(),
+        })
+    }
+
+    fn extract_constant_signature(&self, source: &str, node: &Node) -> Option<Signature> {
+        if !self.is_constant_assignment(node) {
+            return None;
+        }
+        let name = source[node.child_by_field_name("left")?.byte_range()].to_string();
+        let text = &source[node.byte_range()];
+        let full_signature = text.lines().next().unwrap_or(text).trim_end().to_string();
+
+        Some(Signature {
+            kind: SignatureKind::Constant,
+            name,
+            params: None,
+            return_type: None,
+            visibility: Visibility::Public,
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature,
+        })
+    }
+
+    fn find_best_boundary(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        max_bytes: usize,
+        best_end: &mut usize,
+    ) {
+        loop {
+            let node = cursor.node();
+            let end_byte = node.end_byte();
+
+            if end_byte <= max_bytes && end_byte > *best_end {
+                let is_item = node.is_named()
+                    && matches!(
+                        node.kind(),
+                        "method" | "singleton_method" | "class" | "module"
+                    );
+                if is_item {
+                    *best_end = end_byte;
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.find_best_boundary(cursor, max_bytes, best_end);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+require 'json'
+require_relative 'lib/helper'
+
+# A user account.
+class User < Base
+  MAX = 10
+
+  # Creates a user.
+  # Names are required.
+  def initialize(name)
+    @name = name
+  end
+
+  def self.build(attrs = {})
+    new(attrs[:name])
+  end
+
+  private
+
+  def secret
+    1
+  end
+end
+
+def top_level(a, *rest)
+  a
+end
+"#;
+
+    #[test]
+    fn test_extract_signatures() {
+        let signatures = RubySupport.extract_signatures(SOURCE, Visibility::All);
+        let names: Vec<_> = signatures.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["User", "MAX", "initialize", "build", "secret", "top_level"]
+        );
+
+        assert_eq!(signatures[0].kind, SignatureKind::Class);
+        assert_eq!(signatures[0].full_signature, "class User < Base");
+        assert_eq!(signatures[0].doc.as_deref(), Some("A user account."));
+        assert_eq!(signatures[1].kind, SignatureKind::Constant);
+        assert_eq!(signatures[2].kind, SignatureKind::Method);
+        assert_eq!(signatures[2].full_signature, "def initialize(name)");
+        assert_eq!(
+            signatures[2].doc.as_deref(),
+            Some("Creates a user.\nNames are required.")
+        );
+        assert_eq!(signatures[3].full_signature, "def self.build(attrs = {})");
+        assert_eq!(signatures[5].kind, SignatureKind::Function);
+    }
+
+    #[test]
+    fn test_visibility() {
+        let public = RubySupport.extract_signatures(SOURCE, Visibility::Public);
+        assert!(public.iter().all(|s| s.name != "secret"));
+        assert!(public.iter().any(|s| s.name == "build"));
+
+        let private = RubySupport.extract_signatures(SOURCE, Visibility::Private);
+        let names: Vec<_> = private.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["secret"]);
+
+        let inline = "class A\n  private def hidden; end\n  def shown; end\nend\n";
+        let private = RubySupport.extract_signatures(inline, Visibility::Private);
+        assert_eq!(private.len(), 1);
+        assert_eq!(private[0].full_signature, "def hidden");
+    }
+
+    #[test]
+    fn test_extract_structure() {
+        let structure = RubySupport.extract_structure(SOURCE);
+        assert_eq!(structure.functions, 4);
+        assert_eq!(structure.classes, 1);
+        assert_eq!(structure.constants, 1);
+        assert_eq!(
+            structure.imports,
+            vec!["require 'json'", "require_relative 'lib/helper'"]
+        );
+    }
+
+    #[test]
+    fn test_find_truncation_point() {
+        let point = RubySupport.find_truncation_point(SOURCE, 200);
+        assert!(point <= 200);
+        assert!(SOURCE[..point].ends_with("end"));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        assert!(RubySupport.supports_extension("rb"));
+        assert!(RubySupport.supports_extension("rake"));
+        assert!(!RubySupport.supports_extension("py"));
+    }
+}
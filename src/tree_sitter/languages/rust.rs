@@ -3,8 +3,8 @@
 use tree_sitter::{Parser, Tree};
 
 use crate::tree_sitter::language_support::{
-    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility,
-    slice_signature_before_body,
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments, slice_signature_before_body,
 };
 
 pub struct RustSupport;
@@ -190,6 +190,16 @@ impl RustSupport {
         }
     }
 
+    /// `///` and `/** */` doc comments above an item, attributes skipped.
+    fn doc_comment(&self, source: &str, node: &tree_sitter::Node) -> Option<String> {
+        let comments = preceding_comments(source, node, &["attribute_item"]);
+        clean_doc_comment(
+            comments.into_iter().filter(|c| {
+                (c.starts_with("///") && !c.starts_with("////")) || c.starts_with("/**")
+            }),
+        )
+    }
+
     fn extract_structure_from_node(
         &self,
         source: &str,
@@ -204,7 +214,15 @@ impl RustSupport {
             "const_item" => structure.constants += 1,
             "type_item" => structure.type_aliases += 1,
             "macro_definition" => structure.macros += 1,
-            "use_declaration" => {
+            // Uses inside inline modules (`mod tests { use super::*; }`) are
+            // relative to that module, not the file's.
+            "use_declaration" if !self.in_inline_module(node) => {
+                structure
+                    .imports
+                    .push(self.node_text(source, node).to_string());
+            }
+            // `mod foo;` pulls in another file; inline `mod foo { .. }` does not.
+            "mod_item" if node.child_by_field_name("body").is_none() => {
                 structure
                     .imports
                     .push(self.node_text(source, node).to_string());
@@ -218,6 +236,17 @@ impl RustSupport {
         }
     }
 
+    fn in_inline_module(&self, node: &tree_sitter::Node) -> bool {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if parent.kind() == "mod_item" {
+                return true;
+            }
+            current = parent.parent();
+        }
+        false
+    }
+
     fn is_public(&self, node: &tree_sitter::Node) -> bool {
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
@@ -281,6 +310,7 @@ impl RustSupport {
             return_type,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -323,6 +353,7 @@ impl RustSupport {
             return_type: None,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -359,6 +390,7 @@ impl RustSupport {
             return_type: None,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -395,6 +427,7 @@ impl RustSupport {
             return_type: None,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -418,6 +451,7 @@ impl RustSupport {
             return_type: None,
             visibility: Visibility::All,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -449,6 +483,7 @@ impl RustSupport {
             return_type: None,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -480,6 +515,7 @@ impl RustSupport {
             return_type: None,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -511,6 +547,7 @@ impl RustSupport {
             return_type: None,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -542,6 +579,7 @@ impl RustSupport {
             return_type: None,
             visibility: vis,
             line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
             full_signature: full_sig,
         })
     }
@@ -853,6 +891,26 @@ fn helper() {}
         assert!(structure.functions >= 1);
     }
 
+    #[test]
+    fn test_extract_structure_imports() {
+        let source = r#"
+mod parser;
+pub mod utils {}
+use crate::parser::Token;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+}
+"#;
+
+        let structure = RustSupport.extract_structure(source);
+        assert_eq!(
+            structure.imports,
+            vec!["mod parser;", "use crate::parser::Token;"]
+        );
+    }
+
     #[test]
     fn test_find_truncation_point() {
         let source = r#"
@@ -888,4 +946,27 @@ fn third() -> i32 {
         assert!(RustSupport.supports_extension("rs"));
         assert!(!RustSupport.supports_extension("py"));
     }
+
+    #[test]
+    fn test_doc_comments() {
+        let source = r#"
+/// Parses the input.
+///
+/// Returns `None` on empty input.
+#[inline]
+pub fn parse(input: &str) -> Option<u32> {
+    None
+}
+
+// Not a doc comment.
+fn helper() {}
+"#;
+
+        let signatures = RustSupport.extract_signatures(source, Visibility::All);
+        assert_eq!(
+            signatures[0].doc.as_deref(),
+            Some("Parses the input.\n\nReturns `None` on empty input.")
+        );
+        assert_eq!(signatures[1].doc, None);
+    }
 }
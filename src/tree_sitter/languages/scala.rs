@@ -0,0 +1,388 @@
+//! Scala language support for tree-sitter.
+
+#[cfg(feature = "tree-sitter-scala")]
+use tree_sitter::{Node, Parser, Tree};
+
+#[cfg(feature = "tree-sitter-scala")]
+use crate::tree_sitter::language_support::{
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments,
+};
+
+pub struct ScalaSupport;
+
+#[cfg(feature = "tree-sitter-scala")]
+impl ScalaSupport {
+    fn get_language() -> tree_sitter::Language {
+        tree_sitter_scala::LANGUAGE.into()
+    }
+}
+
+#[cfg(feature = "tree-sitter-scala")]
+impl LanguageSupport for ScalaSupport {
+    fn file_extensions(&self) -> &[&'static str] {
+        &["scala", "sc"]
+    }
+
+    fn parse(&self, source: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&Self::get_language()).ok()?;
+        parser.parse(source, None)
+    }
+
+    fn extract_signatures(&self, source: &str, visibility: Visibility) -> Vec<Signature> {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut signatures = Vec::new();
+
+        self.extract_signatures_from_node(source, &root, visibility, &mut signatures);
+
+        signatures.sort_by_key(|s| s.line_number);
+        signatures
+    }
+
+    fn extract_structure(&self, source: &str) -> CodeStructure {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return CodeStructure::default(),
+        };
+
+        let root = tree.root_node();
+        let mut structure = CodeStructure {
+            total_lines: source.lines().count(),
+            ..Default::default()
+        };
+
+        self.extract_structure_from_node(source, &root, &mut structure);
+        structure
+    }
+
+    fn find_truncation_point(&self, source: &str, max_bytes: usize) -> usize {
+        if source.len() <= max_bytes {
+            return source.len();
+        }
+
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return max_bytes,
+        };
+
+        let root = tree.root_node();
+        let mut best_end = 0;
+
+        let mut cursor = root.walk();
+        self.find_best_boundary(&mut cursor, max_bytes, &mut best_end);
+        drop(cursor);
+
+        if best_end == 0 { max_bytes } else { best_end }
+    }
+}
+
+#[cfg(feature = "tree-sitter-scala")]
+impl ScalaSupport {
+    fn extract_signatures_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        visibility: Visibility,
+        signatures: &mut Vec<Signature>,
+    ) {
+        if let Some(kind) = self.signature_kind(node)
+            && let Some(sig) = self.extract_signature(source, node, kind)
+            && sig.visibility.matches_filter(visibility)
+        {
+            signatures.push(sig);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_signatures_from_node(source, &child, visibility, signatures);
+        }
+    }
+
+    fn signature_kind(&self, node: &Node) -> Option<SignatureKind> {
+        match node.kind() {
+            "class_definition" | "object_definition" => Some(SignatureKind::Class),
+            "trait_definition" => Some(SignatureKind::Trait),
+            "enum_definition" => Some(SignatureKind::Enum),
+            "type_definition" => Some(SignatureKind::TypeAlias),
+            "function_definition" | "function_declaration" if self.is_member(node) => {
+                Some(SignatureKind::Method)
+            }
+            "function_definition" | "function_declaration" => Some(SignatureKind::Function),
+            "val_definition" if self.is_constant(node) => Some(SignatureKind::Constant),
+            _ => None,
+        }
+    }
+
+    /// The Scaladoc block above a definition.
+    fn doc_comment(&self, source: &str, node: &Node) -> Option<String> {
+        let comments = preceding_comments(source, node, &[]);
+        clean_doc_comment(comments.into_iter().rev().find(|c| c.starts_with("/**")))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        structure: &mut CodeStructure,
+    ) {
+        match self.signature_kind(node) {
+            Some(SignatureKind::Function | SignatureKind::Method) => structure.functions += 1,
+            Some(SignatureKind::Class) => structure.classes += 1,
+            Some(SignatureKind::Trait) => structure.traits += 1,
+            Some(SignatureKind::Enum) => structure.enums += 1,
+            Some(SignatureKind::TypeAlias) => structure.type_aliases += 1,
+            Some(SignatureKind::Constant) => structure.constants += 1,
+            _ if node.kind() == "import_declaration" => {
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_structure_from_node(source, &child, structure);
+        }
+    }
+
+    /// Whether `node` sits directly in a class, trait or object body.
+    fn is_member(&self, node: &Node) -> bool {
+        node.parent()
+            .is_some_and(|parent| parent.kind() == "template_body")
+    }
+
+    /// A `val` at the top level or in an `object`; `val`s in classes are fields.
+    fn is_constant(&self, node: &Node) -> bool {
+        match node.parent() {
+            Some(parent) if parent.kind() == "compilation_unit" => true,
+            Some(parent) if parent.kind() == "template_body" => parent
+                .parent()
+                .is_some_and(|owner| owner.kind() == "object_definition"),
+            _ => false,
+        }
+    }
+
+    /// Definitions are public unless `private` or `protected`.
+    fn get_visibility(&self, source: &str, node: &Node) -> Visibility {
+        let restricted = node
+            .named_children(&mut node.walk())
+            .filter(|child| child.kind() == "modifiers")
+            .any(|modifiers| {
+                modifiers
+                    .named_children(&mut modifiers.walk())
+                    .any(|m| m.kind() == "access_modifier")
+            });
+        if restricted || source[node.byte_range()].starts_with("private ") {
+            Visibility::Private
+        } else {
+            Visibility::Public
+        }
+    }
+
+    fn extract_signature(
+        &self,
+        source: &str,
+        node: &Node,
+        kind: SignatureKind,
+    ) -> Option<Signature> {
+        let name_field = if kind == SignatureKind::Constant {
+            "pattern"
+        } else {
+            "name"
+        };
+        let name = source[node.child_by_field_name(name_field)?.byte_range()].to_string();
+        let params = node
+            .named_children(&mut node.walk())
+            .find(|child| matches!(child.kind(), "parameters" | "class_parameters"))
+            .map(|p| source[p.byte_range()].to_string());
+        let return_type = node
+            .child_by_field_name("return_type")
+            .map(|r| source[r.byte_range()].to_string());
+
+        // Modifiers, type parameters, parameters, parents and the result type
+        let full_signature = match kind {
+            SignatureKind::Constant | SignatureKind::TypeAlias => {
+                let text = &source[node.byte_range()];
+                text.lines().next().unwrap_or(text).trim_end().to_string()
+            }
+            _ => {
+                let end = node
+                    .child_by_field_name("body")
+                    .map_or(node.end_byte(), |body| body.start_byte());
+                source[node.start_byte()..end]
+                    .trim_end()
+                    .trim_end_matches('=')
+                    .trim_end()
+                    .to_string()
+            }
+        };
+
+        Some(Signature {
+            kind,
+            name,
+            params,
+            return_type,
+            visibility: self.get_visibility(source, node),
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature,
+        })
+    }
+
+    fn find_best_boundary(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        max_bytes: usize,
+        best_end: &mut usize,
+    ) {
+        loop {
+            let node = cursor.node();
+            let end_byte = node.end_byte();
+
+            if end_byte <= max_bytes && end_byte > *best_end {
+                let is_item = matches!(
+                    node.kind(),
+                    "function_definition"
+                        | "function_declaration"
+                        | "class_definition"
+                        | "object_definition"
+                        | "trait_definition"
+                        | "enum_definition"
+                );
+                if is_item {
+                    *best_end = end_byte;
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.find_best_boundary(cursor, max_bytes, best_end);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"package com.example
+
+import scala.collection.mutable
+import com.example.util.{A, B}
+
+/** A user. */
+case class User(name: String) extends Base with Serializable {
+  private val secret = 1
+
+  /** Greets. */
+  def greet(other: User): String = "hi"
+
+  protected def helper(): Unit = {}
+}
+
+trait Repo {
+  def save(u: User): Unit
+}
+
+object Registry {
+  val Max = 10
+  def register[T](x: T): Unit = ()
+}
+
+enum Color { case Red, Green }
+type Id = String
+def topLevel(a: Int): Int = a
+"#;
+
+    #[test]
+    fn test_extract_signatures() {
+        let signatures = ScalaSupport.extract_signatures(SOURCE, Visibility::All);
+        let summary: Vec<_> = signatures
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("User", SignatureKind::Class),
+                ("greet", SignatureKind::Method),
+                ("helper", SignatureKind::Method),
+                ("Repo", SignatureKind::Trait),
+                ("save", SignatureKind::Method),
+                ("Registry", SignatureKind::Class),
+                ("Max", SignatureKind::Constant),
+                ("register", SignatureKind::Method),
+                ("Color", SignatureKind::Enum),
+                ("Id", SignatureKind::TypeAlias),
+                ("topLevel", SignatureKind::Function),
+            ]
+        );
+
+        let user = &signatures[0];
+        assert_eq!(
+            user.full_signature,
+            "case class User(name: String) extends Base with Serializable"
+        );
+        assert_eq!(user.params.as_deref(), Some("(name: String)"));
+        assert_eq!(user.doc.as_deref(), Some("A user."));
+        assert_eq!(
+            signatures[1].full_signature,
+            "def greet(other: User): String"
+        );
+        assert_eq!(signatures[1].return_type.as_deref(), Some("String"));
+        assert_eq!(signatures[1].doc.as_deref(), Some("Greets."));
+        assert_eq!(signatures[4].full_signature, "def save(u: User): Unit");
+        assert_eq!(signatures[7].full_signature, "def register[T](x: T): Unit");
+    }
+
+    #[test]
+    fn test_visibility() {
+        let private = ScalaSupport.extract_signatures(SOURCE, Visibility::Private);
+        let names: Vec<_> = private.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["helper"]);
+    }
+
+    #[test]
+    fn test_extract_structure() {
+        let structure = ScalaSupport.extract_structure(SOURCE);
+        assert_eq!(structure.functions, 5);
+        assert_eq!(structure.classes, 2);
+        assert_eq!(structure.traits, 1);
+        assert_eq!(structure.enums, 1);
+        assert_eq!(structure.constants, 1);
+        assert_eq!(structure.type_aliases, 1);
+        assert_eq!(
+            structure.imports,
+            vec![
+                "import scala.collection.mutable",
+                "import com.example.util.{A, B}"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_truncation_point() {
+        let point = ScalaSupport.find_truncation_point(SOURCE, 320);
+        assert!(point <= 320);
+        assert!(SOURCE[..point].ends_with('}'));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        assert!(ScalaSupport.supports_extension("scala"));
+        assert!(ScalaSupport.supports_extension("sc"));
+        assert!(!ScalaSupport.supports_extension("java"));
+    }
+}
@@ -0,0 +1,397 @@
+//! Swift language support for tree-sitter.
+
+#[cfg(feature = "tree-sitter-swift")]
+use tree_sitter::{Node, Parser, Tree};
+
+#[cfg(feature = "tree-sitter-swift")]
+use crate::tree_sitter::language_support::{
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments,
+};
+
+pub struct SwiftSupport;
+
+#[cfg(feature = "tree-sitter-swift")]
+impl SwiftSupport {
+    fn get_language() -> tree_sitter::Language {
+        tree_sitter_swift::LANGUAGE.into()
+    }
+}
+
+#[cfg(feature = "tree-sitter-swift")]
+impl LanguageSupport for SwiftSupport {
+    fn file_extensions(&self) -> &[&'static str] {
+        &["swift"]
+    }
+
+    fn parse(&self, source: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&Self::get_language()).ok()?;
+        parser.parse(source, None)
+    }
+
+    fn extract_signatures(&self, source: &str, visibility: Visibility) -> Vec<Signature> {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut signatures = Vec::new();
+
+        self.extract_signatures_from_node(source, &root, visibility, &mut signatures);
+
+        signatures.sort_by_key(|s| s.line_number);
+        signatures
+    }
+
+    fn extract_structure(&self, source: &str) -> CodeStructure {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return CodeStructure::default(),
+        };
+
+        let root = tree.root_node();
+        let mut structure = CodeStructure {
+            total_lines: source.lines().count(),
+            ..Default::default()
+        };
+
+        self.extract_structure_from_node(source, &root, &mut structure);
+        structure
+    }
+
+    fn find_truncation_point(&self, source: &str, max_bytes: usize) -> usize {
+        if source.len() <= max_bytes {
+            return source.len();
+        }
+
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return max_bytes,
+        };
+
+        let root = tree.root_node();
+        let mut best_end = 0;
+
+        let mut cursor = root.walk();
+        self.find_best_boundary(&mut cursor, max_bytes, &mut best_end);
+        drop(cursor);
+
+        if best_end == 0 { max_bytes } else { best_end }
+    }
+}
+
+#[cfg(feature = "tree-sitter-swift")]
+impl SwiftSupport {
+    fn extract_signatures_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        visibility: Visibility,
+        signatures: &mut Vec<Signature>,
+    ) {
+        if let Some(kind) = self.signature_kind(source, node)
+            && let Some(sig) = self.extract_signature(source, node, kind)
+            && sig.visibility.matches_filter(visibility)
+        {
+            signatures.push(sig);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_signatures_from_node(source, &child, visibility, signatures);
+        }
+    }
+
+    fn signature_kind(&self, source: &str, node: &Node) -> Option<SignatureKind> {
+        match node.kind() {
+            // `class_declaration` covers classes, structs, enums, actors and extensions
+            "class_declaration" => match self.declaration_keyword(node)? {
+                "struct" => Some(SignatureKind::Struct),
+                "enum" => Some(SignatureKind::Enum),
+                "extension" => Some(SignatureKind::Impl),
+                _ => Some(SignatureKind::Class),
+            },
+            "protocol_declaration" => Some(SignatureKind::Interface),
+            "function_declaration" if self.is_member(node) => Some(SignatureKind::Method),
+            "function_declaration" => Some(SignatureKind::Function),
+            "init_declaration" | "protocol_function_declaration" => Some(SignatureKind::Method),
+            "typealias_declaration" => Some(SignatureKind::TypeAlias),
+            "property_declaration" if self.is_global_constant(source, node) => {
+                Some(SignatureKind::Constant)
+            }
+            _ => None,
+        }
+    }
+
+    /// The `///` or `/** */` doc comment above a declaration.
+    fn doc_comment(&self, source: &str, node: &Node) -> Option<String> {
+        let comments = preceding_comments(source, node, &[]);
+        clean_doc_comment(
+            comments
+                .into_iter()
+                .filter(|c| c.starts_with("///") || c.starts_with("/**")),
+        )
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        structure: &mut CodeStructure,
+    ) {
+        match self.signature_kind(source, node) {
+            Some(SignatureKind::Function | SignatureKind::Method) => structure.functions += 1,
+            Some(SignatureKind::Class) => structure.classes += 1,
+            Some(SignatureKind::Struct) => structure.structs += 1,
+            Some(SignatureKind::Enum) => structure.enums += 1,
+            Some(SignatureKind::Interface) => structure.interfaces += 1,
+            Some(SignatureKind::TypeAlias) => structure.type_aliases += 1,
+            Some(SignatureKind::Constant) => structure.constants += 1,
+            _ if node.kind() == "import_declaration" => {
+                structure
+                    .imports
+                    .push(source[node.start_byte()..node.end_byte()].to_string());
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_structure_from_node(source, &child, structure);
+        }
+    }
+
+    fn declaration_keyword(&self, node: &Node) -> Option<&'static str> {
+        let mut cursor = node.walk();
+        node.children(&mut cursor)
+            .find_map(|child| match child.kind() {
+                "class" => Some("class"),
+                "struct" => Some("struct"),
+                "enum" => Some("enum"),
+                "extension" => Some("extension"),
+                "actor" => Some("actor"),
+                _ => None,
+            })
+    }
+
+    fn is_member(&self, node: &Node) -> bool {
+        node.parent().is_some_and(|parent| {
+            matches!(
+                parent.kind(),
+                "class_body" | "enum_class_body" | "protocol_body"
+            )
+        })
+    }
+
+    /// A top-level `let`.
+    fn is_global_constant(&self, source: &str, node: &Node) -> bool {
+        let is_let = node.named_children(&mut node.walk()).any(|child| {
+            child.kind() == "value_binding_pattern" && &source[child.byte_range()] == "let"
+        });
+        is_let && node.parent().is_some_and(|p| p.kind() == "source_file")
+    }
+
+    /// `public` and `open` declarations are public; the default `internal`
+    /// access and `private`/`fileprivate` count as private. Protocol
+    /// requirements share the protocol's visibility.
+    fn get_visibility(&self, source: &str, node: &Node) -> Visibility {
+        if node.kind() == "protocol_function_declaration"
+            && let Some(protocol) = node.parent().and_then(|body| body.parent())
+        {
+            return self.get_visibility(source, &protocol);
+        }
+        let modifiers = node
+            .named_children(&mut node.walk())
+            .find(|child| child.kind() == "modifiers");
+        let is_public = modifiers.is_some_and(|modifiers| {
+            modifiers.named_children(&mut modifiers.walk()).any(|m| {
+                m.kind() == "visibility_modifier"
+                    && matches!(&source[m.byte_range()], "public" | "open")
+            })
+        });
+        if is_public {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        }
+    }
+
+    fn extract_signature(
+        &self,
+        source: &str,
+        node: &Node,
+        kind: SignatureKind,
+    ) -> Option<Signature> {
+        let name = match node.child_by_field_name("name") {
+            Some(name) => source[name.byte_range()].to_string(),
+            None if node.kind() == "init_declaration" => "init".to_string(),
+            None => return None,
+        };
+
+        // Modifiers, generics, parameters, `throws` and the return type, up to the body
+        let end = node
+            .child_by_field_name("body")
+            .map_or(node.end_byte(), |body| body.start_byte());
+        let text = source[node.start_byte()..end].trim_end();
+        let full_signature = if kind == SignatureKind::Constant {
+            text.lines().next().unwrap_or(text).trim_end().to_string()
+        } else {
+            text.to_string()
+        };
+
+        Some(Signature {
+            kind,
+            name,
+            params: None,
+            return_type: None,
+            visibility: self.get_visibility(source, node),
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature,
+        })
+    }
+
+    fn find_best_boundary(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        max_bytes: usize,
+        best_end: &mut usize,
+    ) {
+        loop {
+            let node = cursor.node();
+            let end_byte = node.end_byte();
+
+            if end_byte <= max_bytes && end_byte > *best_end {
+                let is_item = matches!(
+                    node.kind(),
+                    "function_declaration"
+                        | "init_declaration"
+                        | "class_declaration"
+                        | "protocol_declaration"
+                );
+                if is_item {
+                    *best_end = end_byte;
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.find_best_boundary(cursor, max_bytes, best_end);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+import Foundation
+
+/// A user.
+public class User: Base {
+    public init(name: String) { self.name = name }
+
+    /// Greets.
+    /// Politely.
+    public func greet(_ other: User) -> String {
+        return "hi"
+    }
+
+    private static func helper() {}
+}
+
+public struct Point { var x: Int }
+enum Direction { case north, south }
+public protocol Drawable {
+    func draw()
+}
+extension User: Drawable { func draw() {} }
+typealias Id = String
+let limit = 42
+func topLevel<T>(x: T) throws -> T { x }
+"#;
+
+    #[test]
+    fn test_extract_signatures() {
+        let signatures = SwiftSupport.extract_signatures(SOURCE, Visibility::All);
+        let summary: Vec<_> = signatures
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("User", SignatureKind::Class),
+                ("init", SignatureKind::Method),
+                ("greet", SignatureKind::Method),
+                ("helper", SignatureKind::Method),
+                ("Point", SignatureKind::Struct),
+                ("Direction", SignatureKind::Enum),
+                ("Drawable", SignatureKind::Interface),
+                ("draw", SignatureKind::Method),
+                ("User", SignatureKind::Impl),
+                ("draw", SignatureKind::Method),
+                ("Id", SignatureKind::TypeAlias),
+                ("limit", SignatureKind::Constant),
+                ("topLevel", SignatureKind::Function),
+            ]
+        );
+
+        let greet = &signatures[2];
+        assert_eq!(
+            greet.full_signature,
+            "public func greet(_ other: User) -> String"
+        );
+        assert_eq!(greet.doc.as_deref(), Some("Greets.\nPolitely."));
+        assert_eq!(signatures[0].full_signature, "public class User: Base");
+        assert_eq!(
+            signatures[12].full_signature,
+            "func topLevel<T>(x: T) throws -> T"
+        );
+        assert_eq!(signatures[11].full_signature, "let limit = 42");
+    }
+
+    #[test]
+    fn test_visibility() {
+        let public = SwiftSupport.extract_signatures(SOURCE, Visibility::Public);
+        let names: Vec<_> = public.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["User", "init", "greet", "Point", "Drawable", "draw"]
+        );
+    }
+
+    #[test]
+    fn test_extract_structure() {
+        let structure = SwiftSupport.extract_structure(SOURCE);
+        assert_eq!(structure.functions, 6);
+        assert_eq!(structure.classes, 1);
+        assert_eq!(structure.structs, 1);
+        assert_eq!(structure.enums, 1);
+        assert_eq!(structure.interfaces, 1);
+        assert_eq!(structure.type_aliases, 1);
+        assert_eq!(structure.constants, 1);
+        assert_eq!(structure.imports, vec!["import Foundation"]);
+    }
+
+    #[test]
+    fn test_find_truncation_point() {
+        let point = SwiftSupport.find_truncation_point(SOURCE, 300);
+        assert!(point <= 300);
+        assert!(SOURCE[..point].ends_with('}'));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        assert!(SwiftSupport.supports_extension("swift"));
+        assert!(!SwiftSupport.supports_extension("kt"));
+    }
+}
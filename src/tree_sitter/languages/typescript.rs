@@ -5,8 +5,8 @@ use tree_sitter::{Parser, Tree};
 
 #[cfg(feature = "tree-sitter-ts")]
 use crate::tree_sitter::language_support::{
-    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility,
-    slice_signature_before_body,
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    is_require_call, preceding_comments, slice_signature_before_body,
 };
 
 pub struct TypeScriptSupport;
@@ -70,7 +70,7 @@ macro_rules! impl_ts_language_support {
                     ..Default::default()
                 };
 
-                self.extract_structure_from_node(&root, &mut structure);
+                self.extract_structure_from_node(source, &root, &mut structure);
                 structure
             }
 
@@ -149,8 +149,19 @@ macro_rules! impl_ts_language_support {
                 }
             }
 
+            /// The JSDoc block above a declaration, or above the `export` wrapping it.
+            fn doc_comment(&self, source: &str, node: &tree_sitter::Node) -> Option<String> {
+                let anchor = match node.parent() {
+                    Some(parent) if parent.kind() == "export_statement" => parent,
+                    _ => *node,
+                };
+                let comments = preceding_comments(source, &anchor, &[]);
+                clean_doc_comment(comments.into_iter().rev().find(|c| c.starts_with("/**")))
+            }
+
             fn extract_structure_from_node(
                 &self,
+                source: &str,
                 node: &tree_sitter::Node,
                 structure: &mut CodeStructure,
             ) {
@@ -171,17 +182,30 @@ macro_rules! impl_ts_language_support {
                         structure.enums += 1;
                     }
                     "import_statement" => {
-                        structure.imports.push("import".to_string());
+                        structure
+                            .imports
+                            .push(source[node.start_byte()..node.end_byte()].to_string());
                     }
                     "export_statement" => {
                         structure.exports.push("export".to_string());
+                        // `export { x } from "./y"` depends on another module as well.
+                        if node.child_by_field_name("source").is_some() {
+                            structure
+                                .imports
+                                .push(source[node.start_byte()..node.end_byte()].to_string());
+                        }
+                    }
+                    "call_expression" if is_require_call(source, node) => {
+                        structure
+                            .imports
+                            .push(source[node.start_byte()..node.end_byte()].to_string());
                     }
                     _ => {}
                 }
 
                 let mut cursor = node.walk();
                 for child in node.children(&mut cursor) {
-                    self.extract_structure_from_node(&child, structure);
+                    self.extract_structure_from_node(source, &child, structure);
                 }
             }
 
@@ -210,6 +234,7 @@ macro_rules! impl_ts_language_support {
                     return_type,
                     visibility: Visibility::All,
                     line_number: node.start_position().row + 1,
+                    doc: self.doc_comment(source, node),
                     full_signature: full_sig,
                 })
             }
@@ -234,6 +259,7 @@ macro_rules! impl_ts_language_support {
                     return_type: None,
                     visibility: Visibility::All,
                     line_number: node.start_position().row + 1,
+                    doc: self.doc_comment(source, node),
                     full_signature: full_sig,
                 })
             }
@@ -257,6 +283,7 @@ macro_rules! impl_ts_language_support {
                     return_type: None,
                     visibility: Visibility::All,
                     line_number: node.start_position().row + 1,
+                    doc: self.doc_comment(source, node),
                     full_signature: full_sig,
                 })
             }
@@ -277,6 +304,7 @@ macro_rules! impl_ts_language_support {
                     return_type: None,
                     visibility: Visibility::All,
                     line_number: node.start_position().row + 1,
+                    doc: self.doc_comment(source, node),
                     full_signature: full_sig,
                 })
             }
@@ -299,6 +327,7 @@ macro_rules! impl_ts_language_support {
                     return_type: None,
                     visibility: Visibility::All,
                     line_number: node.start_position().row + 1,
+                    doc: self.doc_comment(source, node),
                     full_signature: full_sig,
                 })
             }
@@ -356,6 +385,7 @@ macro_rules! impl_ts_language_support {
                                 return_type: None,
                                 visibility: Visibility::All,
                                 line_number: child.start_position().row + 1,
+                                doc: self.doc_comment(source, &child),
                                 full_signature,
                             });
                         } else {
@@ -371,6 +401,7 @@ macro_rules! impl_ts_language_support {
                                 return_type: type_ann,
                                 visibility: Visibility::All,
                                 line_number: child.start_position().row + 1,
+                                doc: self.doc_comment(source, &child),
                                 full_signature: full_sig,
                             });
                         }
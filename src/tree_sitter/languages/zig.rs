@@ -0,0 +1,389 @@
+//! Zig language support for tree-sitter.
+
+#[cfg(feature = "tree-sitter-zig")]
+use tree_sitter::{Node, Parser, Tree};
+
+#[cfg(feature = "tree-sitter-zig")]
+use crate::tree_sitter::language_support::{
+    CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility, clean_doc_comment,
+    preceding_comments,
+};
+
+pub struct ZigSupport;
+
+#[cfg(feature = "tree-sitter-zig")]
+impl ZigSupport {
+    fn get_language() -> tree_sitter::Language {
+        tree_sitter_zig::LANGUAGE.into()
+    }
+}
+
+#[cfg(feature = "tree-sitter-zig")]
+impl LanguageSupport for ZigSupport {
+    fn file_extensions(&self) -> &[&'static str] {
+        &["zig"]
+    }
+
+    fn parse(&self, source: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&Self::get_language()).ok()?;
+        parser.parse(source, None)
+    }
+
+    fn extract_signatures(&self, source: &str, visibility: Visibility) -> Vec<Signature> {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut signatures = Vec::new();
+
+        self.extract_signatures_from_node(source, &root, visibility, &mut signatures);
+
+        signatures.sort_by_key(|s| s.line_number);
+        signatures
+    }
+
+    fn extract_structure(&self, source: &str) -> CodeStructure {
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return CodeStructure::default(),
+        };
+
+        let root = tree.root_node();
+        let mut structure = CodeStructure {
+            total_lines: source.lines().count(),
+            ..Default::default()
+        };
+
+        self.extract_structure_from_node(source, &root, &mut structure);
+        structure
+    }
+
+    fn find_truncation_point(&self, source: &str, max_bytes: usize) -> usize {
+        if source.len() <= max_bytes {
+            return source.len();
+        }
+
+        let tree = match self.parse(source) {
+            Some(t) => t,
+            None => return max_bytes,
+        };
+
+        let root = tree.root_node();
+        let mut best_end = 0;
+
+        let mut cursor = root.walk();
+        self.find_best_boundary(&mut cursor, max_bytes, &mut best_end);
+        drop(cursor);
+
+        if best_end == 0 { max_bytes } else { best_end }
+    }
+}
+
+#[cfg(feature = "tree-sitter-zig")]
+impl ZigSupport {
+    fn extract_signatures_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        visibility: Visibility,
+        signatures: &mut Vec<Signature>,
+    ) {
+        let signature = match node.kind() {
+            "function_declaration" => self.extract_function_signature(source, node),
+            "variable_declaration" => self.extract_declaration_signature(source, node),
+            _ => None,
+        };
+        if let Some(sig) = signature
+            && sig.visibility.matches_filter(visibility)
+        {
+            signatures.push(sig);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_signatures_from_node(source, &child, visibility, signatures);
+        }
+    }
+
+    /// The `///` doc comment above a declaration.
+    fn doc_comment(&self, source: &str, node: &Node) -> Option<String> {
+        let comments = preceding_comments(source, node, &[]);
+        clean_doc_comment(comments.into_iter().filter(|c| c.starts_with("///")))
+    }
+
+    fn extract_structure_from_node(
+        &self,
+        source: &str,
+        node: &Node,
+        structure: &mut CodeStructure,
+    ) {
+        match node.kind() {
+            "function_declaration" => structure.functions += 1,
+            "variable_declaration" => match self.declaration_kind(source, node) {
+                Some(SignatureKind::Struct) => structure.structs += 1,
+                Some(SignatureKind::Enum) => structure.enums += 1,
+                Some(SignatureKind::Constant) => structure.constants += 1,
+                _ => {
+                    if let Some(value) = self.value(node)
+                        && self.is_import(source, &value)
+                    {
+                        structure
+                            .imports
+                            .push(source[value.start_byte()..value.end_byte()].to_string());
+                    }
+                }
+            },
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_structure_from_node(source, &child, structure);
+        }
+    }
+
+    /// The initializer of a `const`/`var` declaration.
+    fn value<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        node.named_children(&mut node.walk())
+            .skip(1)
+            .filter(|child| child.kind() != "comment")
+            .last()
+    }
+
+    fn is_import(&self, source: &str, node: &Node) -> bool {
+        node.kind() == "builtin_function" && source[node.byte_range()].starts_with("@import")
+    }
+
+    /// Container types by their value; imports are structure only, and any
+    /// other `const` is a constant.
+    fn declaration_kind(&self, source: &str, node: &Node) -> Option<SignatureKind> {
+        let value = self.value(node)?;
+        match value.kind() {
+            "struct_declaration" | "union_declaration" | "opaque_declaration" => {
+                Some(SignatureKind::Struct)
+            }
+            "enum_declaration" | "error_set_declaration" => Some(SignatureKind::Enum),
+            _ if self.is_import(source, &value) => None,
+            _ if node.children(&mut node.walk()).any(|c| c.kind() == "const") => {
+                Some(SignatureKind::Constant)
+            }
+            _ => None,
+        }
+    }
+
+    /// `pub` (and `export`) declarations are visible outside their file.
+    fn get_visibility(&self, source: &str, node: &Node) -> Visibility {
+        let text = &source[node.byte_range()];
+        if text.starts_with("pub ") || text.starts_with("export ") {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        }
+    }
+
+    fn is_member(&self, node: &Node) -> bool {
+        node.parent().is_some_and(|parent| {
+            matches!(
+                parent.kind(),
+                "struct_declaration" | "union_declaration" | "enum_declaration"
+            )
+        })
+    }
+
+    fn extract_function_signature(&self, source: &str, node: &Node) -> Option<Signature> {
+        let name = source[node.child_by_field_name("name")?.byte_range()].to_string();
+        let params = node
+            .named_children(&mut node.walk())
+            .find(|child| child.kind() == "parameters")
+            .map(|p| source[p.byte_range()].to_string());
+        let return_type = node
+            .child_by_field_name("type")
+            .map(|t| source[t.byte_range()].to_string());
+        let end = node
+            .child_by_field_name("body")
+            .map_or(node.end_byte(), |body| body.start_byte());
+        let full_signature = source[node.start_byte()..end]
+            .trim_end()
+            .trim_end_matches(';')
+            .to_string();
+
+        Some(Signature {
+            kind: if self.is_member(node) {
+                SignatureKind::Method
+            } else {
+                SignatureKind::Function
+            },
+            name,
+            params,
+            return_type,
+            visibility: self.get_visibility(source, node),
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature,
+        })
+    }
+
+    fn extract_declaration_signature(&self, source: &str, node: &Node) -> Option<Signature> {
+        let kind = self.declaration_kind(source, node)?;
+        let name_node = node
+            .named_children(&mut node.walk())
+            .find(|child| child.kind() == "identifier")?;
+        let text = &source[node.byte_range()];
+
+        // Containers stop before their fields, constants at the end of the first line
+        let full_signature = match kind {
+            SignatureKind::Constant => text.lines().next().unwrap_or(text),
+            _ => text.split('{').next().unwrap_or(text),
+        }
+        .trim_end()
+        .trim_end_matches(';')
+        .to_string();
+
+        Some(Signature {
+            kind,
+            name: source[name_node.byte_range()].to_string(),
+            params: None,
+            return_type: None,
+            visibility: self.get_visibility(source, node),
+            line_number: node.start_position().row + 1,
+            doc: self.doc_comment(source, node),
+            full_signature,
+        })
+    }
+
+    fn find_best_boundary(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        max_bytes: usize,
+        best_end: &mut usize,
+    ) {
+        loop {
+            let node = cursor.node();
+            let end_byte = node.end_byte();
+
+            if end_byte <= max_bytes && end_byte > *best_end {
+                let is_item = matches!(
+                    node.kind(),
+                    "function_declaration" | "variable_declaration" | "test_declaration"
+                );
+                if is_item {
+                    *best_end = end_byte;
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.find_best_boundary(cursor, max_bytes, best_end);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"const std = @import("std");
+const util = @import("util.zig");
+
+pub const max_users: usize = 10;
+
+/// A user.
+pub const User = struct {
+    name: []const u8,
+
+    /// Creates a user.
+    pub fn init(name: []const u8) User {
+        return .{ .name = name };
+    }
+
+    fn hidden(self: *User) void {}
+};
+
+const Color = enum { red, green };
+pub const Shape = union(enum) { circle: f32 };
+
+pub fn main() !void {}
+fn helper(a: i32) i32 { return a; }
+test "adds" {}
+"#;
+
+    #[test]
+    fn test_extract_signatures() {
+        let signatures = ZigSupport.extract_signatures(SOURCE, Visibility::All);
+        let summary: Vec<_> = signatures
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("max_users", SignatureKind::Constant),
+                ("User", SignatureKind::Struct),
+                ("init", SignatureKind::Method),
+                ("hidden", SignatureKind::Method),
+                ("Color", SignatureKind::Enum),
+                ("Shape", SignatureKind::Struct),
+                ("main", SignatureKind::Function),
+                ("helper", SignatureKind::Function),
+            ]
+        );
+
+        assert_eq!(
+            signatures[0].full_signature,
+            "pub const max_users: usize = 10"
+        );
+        assert_eq!(signatures[1].full_signature, "pub const User = struct");
+        assert_eq!(signatures[1].doc.as_deref(), Some("A user."));
+        assert_eq!(
+            signatures[2].full_signature,
+            "pub fn init(name: []const u8) User"
+        );
+        assert_eq!(signatures[2].return_type.as_deref(), Some("User"));
+        assert_eq!(signatures[2].doc.as_deref(), Some("Creates a user."));
+        assert_eq!(
+            signatures[5].full_signature,
+            "pub const Shape = union(enum)"
+        );
+    }
+
+    #[test]
+    fn test_visibility() {
+        let private = ZigSupport.extract_signatures(SOURCE, Visibility::Private);
+        let names: Vec<_> = private.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["hidden", "Color", "helper"]);
+    }
+
+    #[test]
+    fn test_extract_structure() {
+        let structure = ZigSupport.extract_structure(SOURCE);
+        assert_eq!(structure.functions, 4);
+        assert_eq!(structure.structs, 2);
+        assert_eq!(structure.enums, 1);
+        assert_eq!(structure.constants, 1);
+        assert_eq!(
+            structure.imports,
+            vec!["@import(\"std\")", "@import(\"util.zig\")"]
+        );
+    }
+
+    #[test]
+    fn test_find_truncation_point() {
+        let point = ZigSupport.find_truncation_point(SOURCE, 300);
+        assert!(point <= 300);
+        assert!(SOURCE[..point].ends_with(';') || SOURCE[..point].ends_with('}'));
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        assert!(ZigSupport.supports_extension("zig"));
+        assert!(!ZigSupport.supports_extension("zon"));
+    }
+}
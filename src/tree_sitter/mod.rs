@@ -4,18 +4,31 @@
 //! - Signature extraction (function/class signatures without bodies)
 //! - Smart truncation (truncate at AST boundaries)
 //! - Structure extraction (imports, exports, symbol counts)
+//! - Symbol lookup by name (full definitions with their enclosing context)
+//! - Definition listing for symbol-level change summaries
+//! - Outlines of Markdown, TOML, YAML and JSON files
+//! - Test code removal (test modules, test functions, describe/it blocks)
 //!
 //! Feature-gated: Only compiled when one of the tree-sitter-* features is enabled.
 
 #[cfg(feature = "tree-sitter-base")]
 pub mod language_support;
 
+#[cfg(feature = "tree-sitter-base")]
+pub mod outline;
+
 #[cfg(feature = "tree-sitter-base")]
 pub mod signatures;
 
+#[cfg(feature = "tree-sitter-base")]
+pub mod strip;
+
 #[cfg(feature = "tree-sitter-base")]
 pub mod structure;
 
+#[cfg(feature = "tree-sitter-base")]
+pub mod symbols;
+
 #[cfg(feature = "tree-sitter-base")]
 pub mod truncation;
 
@@ -28,12 +41,18 @@ use std::path::Path;
 #[cfg(feature = "tree-sitter-base")]
 pub use language_support::{CodeStructure, LanguageSupport, Signature, SignatureKind, Visibility};
 
+#[cfg(feature = "tree-sitter-base")]
+pub use outline::{OutlineEntry, extract_outline};
+
 #[cfg(feature = "tree-sitter-base")]
 pub use signatures::extract_signatures;
 
 #[cfg(feature = "tree-sitter-base")]
 pub use structure::extract_structure;
 
+#[cfg(feature = "tree-sitter-base")]
+pub use symbols::{DefinitionSpan, SymbolDefinition, definition_spans, find_symbols};
+
 #[cfg(feature = "tree-sitter-base")]
 pub use truncation::find_truncation_point;
 
@@ -48,6 +67,17 @@ pub fn is_supported_extension(_ext: &str) -> bool {
     false
 }
 
+/// Check if an outline can be extracted for a given file extension.
+#[cfg(feature = "tree-sitter-base")]
+pub fn has_outline(ext: &str) -> bool {
+    outline::has_outline(ext)
+}
+
+#[cfg(not(feature = "tree-sitter-base"))]
+pub fn has_outline(_ext: &str) -> bool {
+    false
+}
+
 /// Extract file extension from a path.
 #[cfg(feature = "tree-sitter-base")]
 fn get_extension(path: &Path) -> Option<String> {
@@ -81,6 +111,53 @@ pub fn extract_structure_for_file(source: &str, ext: &str) -> Option<CodeStructu
     Some(extract_structure(source, support))
 }
 
+/// Find the definitions matching `queries` for a given file extension.
+#[cfg(feature = "tree-sitter-base")]
+pub fn find_symbols_for_file(
+    source: &str,
+    ext: &str,
+    queries: &[String],
+) -> Option<Vec<SymbolDefinition>> {
+    let support = languages::get_language_support(ext)?;
+    Some(find_symbols(source, support, queries))
+}
+
+/// List every definition in source code for a given file extension.
+#[cfg(feature = "tree-sitter-base")]
+pub fn definition_spans_for_file(source: &str, ext: &str) -> Option<Vec<DefinitionSpan>> {
+    let support = languages::get_language_support(ext)?;
+    Some(definition_spans(source, support))
+}
+
+/// Remove test code from source code for a given file path.
+#[cfg(feature = "tree-sitter-base")]
+pub fn strip_tests_for_file(source: &str, path: &Path) -> Option<String> {
+    let ext = path.extension().and_then(|s| s.to_str())?;
+    let support = languages::get_language_support(ext)?;
+    Some(strip::strip_tests(source, support, path))
+}
+
+/// Locate the comments in source code for a given file extension.
+#[cfg(feature = "tree-sitter-base")]
+pub fn comment_ranges_for_file(source: &str, ext: &str) -> Option<Vec<(usize, usize)>> {
+    let support = languages::get_language_support(ext)?;
+    strip::comment_ranges(source, support)
+}
+
+/// Locate the string literals in source code for a given file extension.
+#[cfg(feature = "tree-sitter-base")]
+pub fn literal_ranges_for_file(source: &str, ext: &str) -> Option<Vec<(usize, usize)>> {
+    let support = languages::get_language_support(ext)?;
+    strip::literal_ranges(source, support)
+}
+
+/// Locate the type annotations in source code for a given file extension.
+#[cfg(feature = "tree-sitter-base")]
+pub fn type_annotation_ranges_for_file(source: &str, ext: &str) -> Option<Vec<(usize, usize)>> {
+    let support = languages::get_language_support(ext)?;
+    structure::type_annotation_ranges(source, support)
+}
+
 /// Find a smart truncation point for a given file extension.
 #[cfg(feature = "tree-sitter-base")]
 pub fn find_smart_truncation_point(source: &str, max_bytes: usize, ext: &str) -> Option<usize> {
@@ -102,6 +179,11 @@ pub fn extract_structure_for_file(_source: &str, _ext: &str) -> Option<()> {
     None
 }
 
+#[cfg(not(feature = "tree-sitter-base"))]
+pub fn strip_tests_for_file(_source: &str, _path: &std::path::Path) -> Option<String> {
+    None
+}
+
 #[cfg(not(feature = "tree-sitter-base"))]
 pub fn find_smart_truncation_point(_source: &str, _max_bytes: usize, _ext: &str) -> Option<usize> {
     None
@@ -0,0 +1,614 @@
+//! Outlines of documentation and data files.
+//!
+//! Markdown, TOML, YAML and JSON have no signatures, but the same idea
+//! applies: a Markdown document is summarized by its heading tree, a config
+//! file by its tables and keys, and a JSON document by its shape (keys with
+//! value types and array lengths, the first element standing in for the
+//! rest of an array).
+
+use serde::Serialize;
+
+#[cfg(any(
+    feature = "tree-sitter-markdown",
+    feature = "tree-sitter-toml",
+    feature = "tree-sitter-yaml",
+    feature = "tree-sitter-json"
+))]
+use tree_sitter::{Node, Parser, Tree};
+
+/// One line of an outline: a heading, a table or a key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutlineEntry {
+    /// Nesting level, 0 at the top.
+    pub depth: usize,
+    /// The heading (`## Install`), table (`[dependencies]`) or key with the
+    /// type of its value (`version: string`, `tags: array[2]`).
+    pub label: String,
+    pub line_number: usize,
+}
+
+/// Keys nested deeper than this are left out.
+#[cfg(any(
+    feature = "tree-sitter-toml",
+    feature = "tree-sitter-yaml",
+    feature = "tree-sitter-json"
+))]
+const MAX_DEPTH: usize = 4;
+
+/// Keys listed per object before the rest are summarized as a count.
+#[cfg(any(
+    feature = "tree-sitter-toml",
+    feature = "tree-sitter-yaml",
+    feature = "tree-sitter-json"
+))]
+const MAX_KEYS: usize = 50;
+
+/// Whether an outline can be extracted for files with this extension.
+pub fn has_outline(extension: &str) -> bool {
+    match extension.to_lowercase().as_str() {
+        #[cfg(feature = "tree-sitter-markdown")]
+        "md" | "markdown" => true,
+        #[cfg(feature = "tree-sitter-toml")]
+        "toml" => true,
+        #[cfg(feature = "tree-sitter-yaml")]
+        "yaml" | "yml" => true,
+        #[cfg(feature = "tree-sitter-json")]
+        "json" => true,
+        _ => false,
+    }
+}
+
+/// Extracts the outline of `source`, or `None` if the extension has none.
+#[allow(unused_variables)]
+pub fn extract_outline(source: &str, extension: &str) -> Option<Vec<OutlineEntry>> {
+    match extension.to_lowercase().as_str() {
+        #[cfg(feature = "tree-sitter-markdown")]
+        "md" | "markdown" => Some(markdown_outline(source)),
+        #[cfg(feature = "tree-sitter-toml")]
+        "toml" => Some(toml_outline(source)),
+        #[cfg(feature = "tree-sitter-yaml")]
+        "yaml" | "yml" => Some(yaml_outline(source)),
+        #[cfg(feature = "tree-sitter-json")]
+        "json" => Some(json_outline(source)),
+        _ => None,
+    }
+}
+
+/// Format an outline as an indented markdown code block.
+pub fn format_outline_as_markdown(entries: &[OutlineEntry]) -> String {
+    if entries.is_empty() {
+        return String::new();
+    }
+
+    let mut output = String::from("**Outline:**\n\n```text\n");
+    for entry in entries {
+        output.push_str(&format_outline_line(entry));
+        output.push('\n');
+    }
+    output.push_str("```\n");
+    output
+}
+
+/// An entry indented by two spaces per level.
+pub fn format_outline_line(entry: &OutlineEntry) -> String {
+    format!("{}{}", "  ".repeat(entry.depth), entry.label)
+}
+
+#[cfg(any(
+    feature = "tree-sitter-markdown",
+    feature = "tree-sitter-toml",
+    feature = "tree-sitter-yaml",
+    feature = "tree-sitter-json"
+))]
+fn parse(source: &str, language: tree_sitter::Language) -> Option<Tree> {
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    parser.parse(source, None)
+}
+
+/// The shape of a data value: what an outline shows in place of the value.
+#[cfg(any(
+    feature = "tree-sitter-toml",
+    feature = "tree-sitter-yaml",
+    feature = "tree-sitter-json"
+))]
+enum Shape {
+    Scalar(&'static str),
+    /// Keys with the line they are on and their value's shape.
+    Object(Vec<(String, usize, Shape)>),
+    /// Length, and the shape of the first element.
+    Array(usize, Option<Box<Shape>>),
+}
+
+#[cfg(any(
+    feature = "tree-sitter-toml",
+    feature = "tree-sitter-yaml",
+    feature = "tree-sitter-json"
+))]
+impl Shape {
+    fn describe(&self) -> String {
+        match self {
+            Shape::Scalar(kind) => kind.to_string(),
+            Shape::Object(fields) if fields.len() == 1 => "object (1 key)".to_string(),
+            Shape::Object(fields) => format!("object ({} keys)", fields.len()),
+            Shape::Array(len, Some(first)) if !matches!(**first, Shape::Array(..)) => {
+                let element = match &**first {
+                    Shape::Scalar(kind) => kind,
+                    _ => "object",
+                };
+                format!("array[{}] of {}", len, element)
+            }
+            Shape::Array(len, _) => format!("array[{}]", len),
+        }
+    }
+
+    /// The keys listed under this value: an object's own, or those of an
+    /// array's first element.
+    fn fields(&self) -> &[(String, usize, Shape)] {
+        match self {
+            Shape::Object(fields) => fields,
+            Shape::Array(_, Some(first)) => first.fields(),
+            _ => &[],
+        }
+    }
+
+    fn push_fields(&self, depth: usize, entries: &mut Vec<OutlineEntry>) {
+        if depth >= MAX_DEPTH {
+            return;
+        }
+        let fields = self.fields();
+        for (key, line_number, value) in fields.iter().take(MAX_KEYS) {
+            entries.push(OutlineEntry {
+                depth,
+                label: format!("{}: {}", key, value.describe()),
+                line_number: *line_number,
+            });
+            value.push_fields(depth + 1, entries);
+        }
+        if let Some((_, line_number, _)) = fields.get(MAX_KEYS) {
+            entries.push(OutlineEntry {
+                depth,
+                label: format!("... {} more keys", fields.len() - MAX_KEYS),
+                line_number: *line_number,
+            });
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "tree-sitter-toml",
+    feature = "tree-sitter-yaml",
+    feature = "tree-sitter-json"
+))]
+fn named_children<'a>(node: &Node<'a>) -> Vec<Node<'a>> {
+    node.named_children(&mut node.walk())
+        .filter(|child| child.kind() != "comment")
+        .collect()
+}
+
+/// The heading tree; headings inside lists and block quotes are skipped.
+#[cfg(feature = "tree-sitter-markdown")]
+fn markdown_outline(source: &str) -> Vec<OutlineEntry> {
+    fn collect(source: &str, node: &Node, headings: &mut Vec<(usize, String, usize)>) {
+        for child in node.named_children(&mut node.walk()) {
+            match child.kind() {
+                "section" => collect(source, &child, headings),
+                "atx_heading" | "setext_heading" => {
+                    let level = child
+                        .named_children(&mut child.walk())
+                        .find_map(|marker| match marker.kind() {
+                            "setext_h1_underline" => Some(1),
+                            "setext_h2_underline" => Some(2),
+                            kind => kind
+                                .strip_prefix("atx_h")
+                                .and_then(|rest| rest.strip_suffix("_marker"))
+                                .and_then(|level| level.parse().ok()),
+                        })
+                        .unwrap_or(1);
+                    let text = child
+                        .child_by_field_name("heading_content")
+                        .map(|content| source[content.byte_range()].trim().to_string())
+                        .unwrap_or_default();
+                    headings.push((level, text, child.start_position().row + 1));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let Some(tree) = parse(source, tree_sitter_md::LANGUAGE.into()) else {
+        return Vec::new();
+    };
+    let mut headings = Vec::new();
+    collect(source, &tree.root_node(), &mut headings);
+
+    // Documents that start at `##` are not indented for the missing `#`
+    let top = headings
+        .iter()
+        .map(|(level, _, _)| *level)
+        .min()
+        .unwrap_or(1);
+    headings
+        .into_iter()
+        .map(|(level, text, line_number)| OutlineEntry {
+            depth: level - top,
+            label: format!("{} {}", "#".repeat(level), text),
+            line_number,
+        })
+        .collect()
+}
+
+/// Top-level keys, then each `[table]` and `[[array]]` element with its keys.
+#[cfg(feature = "tree-sitter-toml")]
+fn toml_outline(source: &str) -> Vec<OutlineEntry> {
+    fn pair_shape(source: &str, pair: &Node) -> Option<(String, usize, Shape)> {
+        let children = named_children(pair);
+        let key = children.first()?;
+        let value = children.last()?;
+        Some((
+            source[key.byte_range()].to_string(),
+            pair.start_position().row + 1,
+            value_shape(source, value),
+        ))
+    }
+
+    fn value_shape(source: &str, node: &Node) -> Shape {
+        match node.kind() {
+            "inline_table" => Shape::Object(
+                named_children(node)
+                    .iter()
+                    .filter_map(|pair| pair_shape(source, pair))
+                    .collect(),
+            ),
+            "array" => {
+                let items = named_children(node);
+                Shape::Array(
+                    items.len(),
+                    items
+                        .first()
+                        .map(|item| Box::new(value_shape(source, item))),
+                )
+            }
+            "string" => Shape::Scalar("string"),
+            "integer" => Shape::Scalar("integer"),
+            "float" => Shape::Scalar("float"),
+            "boolean" => Shape::Scalar("boolean"),
+            _ => Shape::Scalar("datetime"),
+        }
+    }
+
+    let Some(tree) = parse(source, tree_sitter_toml_ng::LANGUAGE.into()) else {
+        return Vec::new();
+    };
+    let root = tree.root_node();
+    let mut entries = Vec::new();
+    let mut top_level = Vec::new();
+    let mut tables = Vec::new();
+    for child in named_children(&root) {
+        match child.kind() {
+            "pair" => top_level.extend(pair_shape(source, &child)),
+            "table" | "table_array_element" => tables.push(child),
+            _ => {}
+        }
+    }
+
+    Shape::Object(top_level).push_fields(0, &mut entries);
+    for table in tables {
+        let children = named_children(&table);
+        let Some(name) = children.first() else {
+            continue;
+        };
+        let brackets = if table.kind() == "table" {
+            ("[", "]")
+        } else {
+            ("[[", "]]")
+        };
+        entries.push(OutlineEntry {
+            depth: 0,
+            label: format!("{}{}{}", brackets.0, &source[name.byte_range()], brackets.1),
+            line_number: table.start_position().row + 1,
+        });
+        let fields = children[1..]
+            .iter()
+            .filter_map(|pair| pair_shape(source, pair))
+            .collect();
+        Shape::Object(fields).push_fields(1, &mut entries);
+    }
+    entries
+}
+
+/// The key hierarchy of each document in the stream.
+#[cfg(feature = "tree-sitter-yaml")]
+fn yaml_outline(source: &str) -> Vec<OutlineEntry> {
+    /// Shape of a `block_node` or `flow_node`, looking past anchors and tags.
+    fn node_shape(source: &str, node: &Node) -> Shape {
+        let Some(inner) = named_children(node)
+            .into_iter()
+            .find(|child| !matches!(child.kind(), "anchor" | "tag"))
+        else {
+            return Shape::Scalar("null");
+        };
+        match inner.kind() {
+            "block_mapping" | "flow_mapping" => Shape::Object(
+                named_children(&inner)
+                    .iter()
+                    .filter_map(|pair| {
+                        let key = pair.child_by_field_name("key")?;
+                        let value = pair
+                            .child_by_field_name("value")
+                            .map_or(Shape::Scalar("null"), |value| node_shape(source, &value));
+                        Some((
+                            source[key.byte_range()].to_string(),
+                            pair.start_position().row + 1,
+                            value,
+                        ))
+                    })
+                    .collect(),
+            ),
+            "block_sequence" | "flow_sequence" => {
+                let items: Vec<Node> = named_children(&inner)
+                    .into_iter()
+                    .map(|item| match item.kind() {
+                        "block_sequence_item" => item.named_child(0).unwrap_or(item),
+                        _ => item,
+                    })
+                    .collect();
+                Shape::Array(
+                    items.len(),
+                    items.first().map(|item| Box::new(node_shape(source, item))),
+                )
+            }
+            "plain_scalar" => match inner.named_child(0).map(|scalar| scalar.kind()) {
+                Some("integer_scalar") => Shape::Scalar("integer"),
+                Some("float_scalar") => Shape::Scalar("float"),
+                Some("boolean_scalar") => Shape::Scalar("boolean"),
+                Some("null_scalar") => Shape::Scalar("null"),
+                _ => Shape::Scalar("string"),
+            },
+            "alias" => Shape::Scalar("alias"),
+            _ => Shape::Scalar("string"),
+        }
+    }
+
+    let Some(tree) = parse(source, tree_sitter_yaml::LANGUAGE.into()) else {
+        return Vec::new();
+    };
+    let root = tree.root_node();
+    let mut entries = Vec::new();
+    for (index, document) in named_children(&root).iter().enumerate() {
+        if index > 0 {
+            entries.push(OutlineEntry {
+                depth: 0,
+                label: "---".to_string(),
+                line_number: document.start_position().row + 1,
+            });
+        }
+        if let Some(body) = named_children(document)
+            .into_iter()
+            .find(|child| matches!(child.kind(), "block_node" | "flow_node"))
+        {
+            push_root_shape(node_shape(source, &body), &body, &mut entries);
+        }
+    }
+    entries
+}
+
+/// Keys with value types; arrays show their length and the keys of their first element.
+#[cfg(feature = "tree-sitter-json")]
+fn json_outline(source: &str) -> Vec<OutlineEntry> {
+    fn value_shape(source: &str, node: &Node) -> Shape {
+        match node.kind() {
+            "object" => Shape::Object(
+                named_children(node)
+                    .iter()
+                    .filter_map(|pair| {
+                        let key = pair.child_by_field_name("key")?;
+                        let value = pair.child_by_field_name("value")?;
+                        Some((
+                            source[key.byte_range()].trim_matches('"').to_string(),
+                            pair.start_position().row + 1,
+                            value_shape(source, &value),
+                        ))
+                    })
+                    .collect(),
+            ),
+            "array" => {
+                let items = named_children(node);
+                Shape::Array(
+                    items.len(),
+                    items
+                        .first()
+                        .map(|item| Box::new(value_shape(source, item))),
+                )
+            }
+            "string" => Shape::Scalar("string"),
+            "number" => Shape::Scalar("number"),
+            "true" | "false" => Shape::Scalar("boolean"),
+            _ => Shape::Scalar("null"),
+        }
+    }
+
+    let Some(tree) = parse(source, tree_sitter_json::LANGUAGE.into()) else {
+        return Vec::new();
+    };
+    let root = tree.root_node();
+    let mut entries = Vec::new();
+    if let Some(value) = named_children(&root).first() {
+        push_root_shape(value_shape(source, value), value, &mut entries);
+    }
+    entries
+}
+
+/// A root object lists its keys at the top level; any other root value gets
+/// an entry of its own, with an array's element keys below it.
+#[cfg(any(feature = "tree-sitter-yaml", feature = "tree-sitter-json"))]
+fn push_root_shape(shape: Shape, node: &Node, entries: &mut Vec<OutlineEntry>) {
+    if let Shape::Object(_) = shape {
+        shape.push_fields(0, entries);
+    } else {
+        entries.push(OutlineEntry {
+            depth: 0,
+            label: shape.describe(),
+            line_number: node.start_position().row + 1,
+        });
+        shape.push_fields(1, entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    fn labels(entries: &[OutlineEntry]) -> Vec<String> {
+        entries.iter().map(format_outline_line).collect()
+    }
+
+    #[test]
+    fn test_format_outline() {
+        let entries = vec![
+            OutlineEntry {
+                depth: 0,
+                label: "# Title".to_string(),
+                line_number: 1,
+            },
+            OutlineEntry {
+                depth: 1,
+                label: "## Install".to_string(),
+                line_number: 5,
+            },
+        ];
+        assert_eq!(
+            format_outline_as_markdown(&entries),
+            "**Outline:**\n\n```text\n# Title\n  ## Install\n```\n"
+        );
+        assert!(format_outline_as_markdown(&[]).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-markdown")]
+    fn test_markdown_outline() {
+        let source = "Intro\n\n## Install\n\n```sh\n# not a heading\n```\n\n### From source\n\nSetext\n------\n\n## Usage\n";
+        let entries = extract_outline(source, "md").unwrap();
+        assert_eq!(
+            labels(&entries),
+            vec!["## Install", "  ### From source", "## Setext", "## Usage"]
+        );
+        assert_eq!(entries[0].line_number, 3);
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-toml")]
+    fn test_toml_outline() {
+        let source = r#"edition = "2024"
+
+[package]
+name = "demo"
+authors = ["a", "b"]
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+
+[[bin]]
+name = "a"
+"#;
+        let entries = extract_outline(source, "toml").unwrap();
+        assert_eq!(
+            labels(&entries),
+            vec![
+                "edition: string",
+                "[package]",
+                "  name: string",
+                "  authors: array[2] of string",
+                "[dependencies]",
+                "  serde: object (2 keys)",
+                "    version: string",
+                "    features: array[1] of string",
+                "[[bin]]",
+                "  name: string",
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-yaml")]
+    fn test_yaml_outline() {
+        let source = r#"name: CI
+on:
+  push:
+    branches: [main]
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    timeout: 10
+    steps:
+      - uses: actions/checkout@v4
+      - name: Test
+---
+second: true
+"#;
+        let entries = extract_outline(source, "yaml").unwrap();
+        assert_eq!(
+            labels(&entries),
+            vec![
+                "name: string",
+                "on: object (1 key)",
+                "  push: object (1 key)",
+                "    branches: array[1] of string",
+                "jobs: object (1 key)",
+                "  build: object (3 keys)",
+                "    runs-on: string",
+                "    timeout: integer",
+                "    steps: array[2] of object",
+                "      uses: string",
+                "---",
+                "second: boolean",
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-json")]
+    fn test_json_outline() {
+        let source = r#"{
+  "name": "demo",
+  "private": true,
+  "deps": {"x": "1.0", "y": null},
+  "items": [{"id": 1, "ok": false}, {"id": 2}]
+}"#;
+        let entries = extract_outline(source, "json").unwrap();
+        assert_eq!(
+            labels(&entries),
+            vec![
+                "name: string",
+                "private: boolean",
+                "deps: object (2 keys)",
+                "  x: string",
+                "  y: null",
+                "items: array[2] of object",
+                "  id: number",
+                "  ok: boolean",
+            ]
+        );
+        assert_eq!(entries[5].line_number, 5);
+
+        let entries = extract_outline("[[1, 2], [3]]", "json").unwrap();
+        assert_eq!(labels(&entries), vec!["array[2]"]);
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-json")]
+    fn test_outline_limits_keys() {
+        let keys: Vec<String> = (0..60).map(|i| format!("\"k{}\": {}", i, i)).collect();
+        let source = format!("{{{}}}", keys.join(", "));
+        let entries = extract_outline(&source, "json").unwrap();
+        assert_eq!(entries.len(), MAX_KEYS + 1);
+        assert_eq!(entries[MAX_KEYS].label, "... 10 more keys");
+    }
+
+    #[test]
+    fn test_unsupported_extension() {
+        assert!(!has_outline("rs"));
+        assert!(extract_outline("fn main() {}", "rs").is_none());
+    }
+}
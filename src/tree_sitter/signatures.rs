@@ -50,6 +50,9 @@ pub fn format_signatures_as_markdown(signatures: &[Signature], language: &str) -
             current_kind = Some(kind_str);
         }
 
+        if let Some(doc) = &sig.doc {
+            output.push_str(&format_doc_comment(doc, language));
+        }
         output.push_str(&sig.full_signature);
         output.push('\n');
     }
@@ -58,6 +61,25 @@ pub fn format_signatures_as_markdown(signatures: &[Signature], language: &str) -
     output
 }
 
+/// Renders a signature's doc as line comments of `language`, one per line.
+pub fn format_doc_comment(doc: &str, language: &str) -> String {
+    let marker = match language {
+        "rust" => "///",
+        "python" | "ruby" | "bash" => "#",
+        "lua" | "haskell" => "--",
+        _ => "//",
+    };
+    doc.lines()
+        .map(|line| {
+            if line.is_empty() {
+                format!("{}\n", marker)
+            } else {
+                format!("{} {}\n", marker, line)
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::language_support::SignatureKind;
@@ -71,6 +93,7 @@ mod tests {
             return_type: None,
             visibility: Visibility::All,
             line_number: 1,
+            doc: None,
             full_signature: full_sig.to_string(),
         }
     }
@@ -95,6 +118,29 @@ mod tests {
         assert!(output.ends_with("```\n"));
     }
 
+    #[test]
+    fn test_format_signature_docs_modes() {
+        use super::super::language_support::SignatureDocs;
+
+        let mut sig = make_sig(SignatureKind::Function, "foo", "fn foo()");
+        sig.doc = Some("Does foo.\n\nIn detail.".to_string());
+
+        let mut full = vec![sig.clone()];
+        SignatureDocs::Full.apply(&mut full);
+        let output = format_signatures_as_markdown(&full, "rust");
+        assert!(output.contains("/// Does foo.\n///\n/// In detail.\nfn foo()\n"));
+
+        let mut first_line = vec![sig.clone()];
+        SignatureDocs::FirstLine.apply(&mut first_line);
+        let output = format_signatures_as_markdown(&first_line, "python");
+        assert!(output.contains("# Does foo.\nfn foo()\n"));
+        assert!(!output.contains("In detail."));
+
+        let mut hidden = vec![sig];
+        SignatureDocs::Hidden.apply(&mut hidden);
+        assert_eq!(hidden[0].doc, None);
+    }
+
     #[test]
     fn test_format_multiple_same_kind() {
         let sigs = vec![
@@ -0,0 +1,477 @@
+//! Removal of test code from source files (`--strip-tests`), and the
+//! comment and string literal locations `--compact` works around.
+//!
+//! Test scaffolding is recognized per language family on the parse tree:
+//! - Rust: items marked `#[cfg(test)]`, `#[test]` (or `#[tokio::test]` and
+//!   other `::test` attributes) and `#[bench]`, in any file
+//! - JavaScript/TypeScript: top-level `describe`/`it`/`test` calls (with
+//!   `.skip`, `.only`, `.each(...)`) and the `before*`/`after*` hooks, in
+//!   `*.test.*` and `*.spec.*` files and under `__tests__/`
+//! - Python: `test_*` functions and `Test*` classes, decorators included,
+//!   in `test_*.py`, `*_test.py` and `conftest.py` files and under `tests/`
+//! - Go: `Test*`, `Benchmark*`, `Fuzz*` and `Example*` functions, in
+//!   `*_test.go` files
+//!
+//! Outside those files the names carry no meaning, so a production
+//! `TestClient` class or `TestMain` helper is left alone.
+//!
+//! A removed definition takes its attributes and the comments directly
+//! above it along; whole lines are removed so no stray indentation remains.
+
+use super::language_support::LanguageSupport;
+use std::path::{Component, Path};
+use tree_sitter::Node;
+
+/// Call names that open a JS/TS test block or hook.
+const JS_TEST_CALLS: &[&str] = &[
+    "describe",
+    "it",
+    "test",
+    "beforeAll",
+    "beforeEach",
+    "afterAll",
+    "afterEach",
+];
+
+#[derive(Clone, Copy)]
+enum Family {
+    Rust,
+    JavaScript,
+    Python,
+    Go,
+}
+
+impl Family {
+    fn for_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "rs" => Some(Family::Rust),
+            "js" | "mjs" | "cjs" | "jsx" | "ts" | "tsx" | "mts" | "cts" => Some(Family::JavaScript),
+            "py" | "pyw" => Some(Family::Python),
+            "go" => Some(Family::Go),
+            _ => None,
+        }
+    }
+
+    /// The family whose test rules apply to `path`. Rust test code is marked
+    /// by attributes and recognized anywhere; the other families only follow
+    /// naming conventions inside their test files.
+    fn for_path(path: &Path) -> Option<Self> {
+        let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+        let family = Self::for_extension(extension)?;
+        let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+        let in_directory = |name: &str| {
+            path.parent().is_some_and(|parent| {
+                parent
+                    .components()
+                    .any(|c| c == Component::Normal(name.as_ref()))
+            })
+        };
+        let is_test_file = match family {
+            Family::Rust => true,
+            Family::JavaScript => {
+                file_name.contains(".test.")
+                    || file_name.contains(".spec.")
+                    || in_directory("__tests__")
+            }
+            Family::Python => {
+                let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+                stem.starts_with("test_")
+                    || stem.ends_with("_test")
+                    || file_name == "conftest.py"
+                    || in_directory("tests")
+            }
+            Family::Go => file_name.ends_with("_test.go"),
+        };
+        is_test_file.then_some(family)
+    }
+}
+
+/// Returns `source` without its test code. Files outside the test-file
+/// conventions of their language, languages without test rules, and sources
+/// that fail to parse are returned unchanged.
+pub fn strip_tests(source: &str, support: &dyn LanguageSupport, path: &Path) -> String {
+    let Some(family) = Family::for_path(path) else {
+        return source.to_string();
+    };
+    let Some(tree) = support.parse(source) else {
+        return source.to_string();
+    };
+
+    let mut ranges = Vec::new();
+    collect_test_ranges(source, &tree.root_node(), family, &mut ranges);
+    remove_lines(source, &ranges)
+}
+
+/// Byte ranges of the comments in `source`, in order. `None` if it fails to parse.
+pub fn comment_ranges(source: &str, support: &dyn LanguageSupport) -> Option<Vec<(usize, usize)>> {
+    fn collect(node: &Node, ranges: &mut Vec<(usize, usize)>) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.kind().contains("comment") || child.kind() == "haddock" {
+                ranges.push((child.start_byte(), child.end_byte()));
+            } else {
+                collect(&child, ranges);
+            }
+        }
+    }
+
+    let tree = support.parse(source)?;
+    let mut ranges = Vec::new();
+    collect(&tree.root_node(), &mut ranges);
+    Some(ranges)
+}
+
+/// Byte ranges of the string literals in `source` (raw strings, heredocs and
+/// text blocks included), in order. `None` if it fails to parse.
+pub fn literal_ranges(source: &str, support: &dyn LanguageSupport) -> Option<Vec<(usize, usize)>> {
+    fn is_literal(kind: &str) -> bool {
+        kind.contains("string")
+            || kind.contains("heredoc")
+            || kind == "nowdoc"
+            || kind == "text_block"
+    }
+
+    fn collect(node: &Node, ranges: &mut Vec<(usize, usize)>) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if is_literal(child.kind()) {
+                ranges.push((child.start_byte(), child.end_byte()));
+            } else {
+                collect(&child, ranges);
+            }
+        }
+    }
+
+    let tree = support.parse(source)?;
+    let mut ranges = Vec::new();
+    collect(&tree.root_node(), &mut ranges);
+    Some(ranges)
+}
+
+fn collect_test_ranges(
+    source: &str,
+    node: &Node,
+    family: Family,
+    ranges: &mut Vec<(usize, usize)>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let is_test = match family {
+            Family::Rust => is_rust_test(source, &child),
+            Family::JavaScript => is_js_test(source, &child),
+            Family::Python => is_python_test(source, &child),
+            Family::Go => is_go_test(source, &child),
+        };
+        if is_test {
+            ranges.push((attached_start(&child), child.end_byte()));
+        } else {
+            collect_test_ranges(source, &child, family, ranges);
+        }
+    }
+}
+
+/// Start of `node` including the attributes and comments directly above it.
+fn attached_start(node: &Node) -> usize {
+    let mut anchor = *node;
+    while let Some(sibling) = anchor.prev_sibling() {
+        let attached = sibling.kind().contains("comment") || sibling.kind() == "attribute_item";
+        if !attached || sibling.end_position().row + 1 < anchor.start_position().row {
+            break;
+        }
+        anchor = sibling;
+    }
+    anchor.start_byte()
+}
+
+/// An item whose attributes mark it as test-only.
+fn is_rust_test(source: &str, node: &Node) -> bool {
+    if node.kind() == "attribute_item" || node.kind().contains("comment") {
+        return false;
+    }
+    let mut sibling = node.prev_sibling();
+    while let Some(attribute) = sibling {
+        if attribute.kind() == "attribute_item" {
+            let text = source[attribute.byte_range()]
+                .trim_start_matches("#[")
+                .trim_end_matches(']')
+                .trim();
+            let path = text.split('(').next().unwrap_or(text).trim();
+            if text == "cfg(test)" || path == "test" || path.ends_with("::test") || path == "bench"
+            {
+                return true;
+            }
+        } else if !attribute.kind().contains("comment") {
+            break;
+        }
+        sibling = attribute.prev_sibling();
+    }
+    false
+}
+
+/// A statement calling `describe(...)`, `it.only(...)`, `test.each(...)(...)`, etc.
+fn is_js_test(source: &str, node: &Node) -> bool {
+    if node.kind() != "expression_statement" {
+        return false;
+    }
+    let Some(mut callee) = node.named_child(0) else {
+        return false;
+    };
+    loop {
+        let next = match callee.kind() {
+            "call_expression" => callee.child_by_field_name("function"),
+            "member_expression" => callee.child_by_field_name("object"),
+            "identifier" => return JS_TEST_CALLS.contains(&&source[callee.byte_range()]),
+            _ => None,
+        };
+        match next {
+            Some(next) => callee = next,
+            None => return false,
+        }
+    }
+}
+
+/// A `test_*` function or `Test*` class, with its decorators.
+fn is_python_test(source: &str, node: &Node) -> bool {
+    let definition = match node.kind() {
+        "decorated_definition" => match node.child_by_field_name("definition") {
+            Some(definition) => definition,
+            None => return false,
+        },
+        _ => *node,
+    };
+    let name = definition
+        .child_by_field_name("name")
+        .map(|name| &source[name.byte_range()]);
+    match (definition.kind(), name) {
+        ("function_definition", Some(name)) => name == "test" || name.starts_with("test_"),
+        ("class_definition", Some(name)) => name.starts_with("Test"),
+        _ => false,
+    }
+}
+
+/// A function `go test` runs: `TestXxx`, `BenchmarkXxx`, `FuzzXxx` or `ExampleXxx`.
+fn is_go_test(source: &str, node: &Node) -> bool {
+    if node.kind() != "function_declaration" {
+        return false;
+    }
+    let Some(name) = node.child_by_field_name("name") else {
+        return false;
+    };
+    let name = &source[name.byte_range()];
+    ["Test", "Benchmark", "Fuzz", "Example"]
+        .iter()
+        .any(|prefix| {
+            name.strip_prefix(prefix)
+                .is_some_and(|rest| !rest.starts_with(|c: char| c.is_lowercase()))
+        })
+}
+
+/// Removes the lines spanned by `ranges`, and the blank line that separated
+/// a removed block from the code around it.
+fn remove_lines(source: &str, ranges: &[(usize, usize)]) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for &(start, end) in ranges {
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let start = if source[line_start..start].trim().is_empty() {
+            line_start
+        } else {
+            start
+        };
+        let line_end = source[end..]
+            .find('\n')
+            .map_or(source.len(), |i| end + i + 1);
+        let mut end = if source[end..line_end].trim().is_empty() {
+            line_end
+        } else {
+            end
+        };
+        if start < cursor {
+            continue;
+        }
+
+        output.push_str(&source[cursor..start]);
+        let next_line_end = source[end..]
+            .find('\n')
+            .map_or(source.len(), |i| end + i + 1);
+        let next_line = source[end..next_line_end].trim();
+        if output.is_empty() || output.ends_with("\n\n") {
+            if next_line.is_empty() {
+                end = next_line_end;
+            } else if next_line.starts_with(['}', ')', ']']) {
+                // The block was last in its body: no blank line before the brace
+                output.pop();
+            }
+        }
+        cursor = end;
+    }
+    let rest = &source[cursor..];
+    if rest.trim().is_empty() && output.ends_with("\n\n") {
+        // The removed block was last: drop the blank line left above it
+        output.pop();
+    } else {
+        output.push_str(rest);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    fn strip(source: &str, path: &str) -> String {
+        let path = Path::new(path);
+        let extension = path.extension().and_then(|s| s.to_str()).unwrap();
+        let support = super::super::languages::get_language_support(extension).unwrap();
+        strip_tests(source, support, path)
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-rust")]
+    fn test_strip_rust_tests() {
+        let source = r#"pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[test]
+fn top_level() {}
+
+pub fn sub(a: i32, b: i32) -> i32 {
+    a - b
+}
+
+/// Test helpers.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds() {
+        assert_eq!(add(1, 2), 3);
+    }
+}
+"#;
+        assert_eq!(
+            strip(source, "src/lib.rs"),
+            "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\npub fn sub(a: i32, b: i32) -> i32 {\n    a - b\n}\n"
+        );
+
+        let source = "impl Foo {\n    pub fn new() -> Self { Foo }\n\n    #[tokio::test(flavor = \"multi_thread\")]\n    async fn runs() {}\n}\n";
+        assert_eq!(
+            strip(source, "src/lib.rs"),
+            "impl Foo {\n    pub fn new() -> Self { Foo }\n}\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-js")]
+    fn test_strip_js_tests() {
+        let source = r#"import { sum } from "./sum";
+
+export function helper() {
+  return 1;
+}
+
+// Sum behaviour
+describe("sum", () => {
+  it("adds", () => {
+    expect(sum(1, 2)).toBe(3);
+  });
+});
+
+test.each([[1, 2]])("pairs %i %i", (a, b) => {});
+
+beforeEach(() => reset());
+"#;
+        assert_eq!(
+            strip(source, "src/sum.test.js"),
+            "import { sum } from \"./sum\";\n\nexport function helper() {\n  return 1;\n}\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-python")]
+    fn test_strip_python_tests() {
+        let source = r#"import pytest
+
+
+def parse(text):
+    return text.split()
+
+
+@pytest.mark.parametrize("x", [1, 2])
+def test_parse(x):
+    assert parse("a b")
+
+
+class TestParser:
+    def test_empty(self):
+        assert parse("") == []
+
+
+class Parser:
+    def tested(self):
+        return True
+"#;
+        let stripped = strip(source, "tests/test_parser.py");
+        assert!(stripped.contains("def parse(text):"));
+        assert!(stripped.contains("class Parser:\n    def tested(self):"));
+        assert!(!stripped.contains("pytest.mark"));
+        assert!(!stripped.contains("test_parse"));
+        assert!(!stripped.contains("TestParser"));
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-go")]
+    fn test_strip_go_tests() {
+        let source = r#"package sum
+
+// Sum adds two numbers.
+func Sum(a, b int) int { return a + b }
+
+// TestSum checks Sum.
+func TestSum(t *testing.T) {}
+
+func BenchmarkSum(b *testing.B) {}
+
+func Testimony() {}
+"#;
+        assert_eq!(
+            strip(source, "sum/sum_test.go"),
+            "package sum\n\n// Sum adds two numbers.\nfunc Sum(a, b int) int { return a + b }\n\nfunc Testimony() {}\n"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "tree-sitter-go", feature = "tree-sitter-python"))]
+    fn test_production_test_names_survive() {
+        let source = "package client\n\nfunc TestConnection(addr string) error { return nil }\n";
+        assert_eq!(strip(source, "client/client.go"), source);
+
+        let source = "class TestClient:\n    def test_request(self):\n        pass\n";
+        assert_eq!(strip(source, "app/fakes.py"), source);
+        assert_eq!(strip(source, "app/tests/fakes.py"), "");
+        assert_eq!(strip(source, "app/conftest.py"), "");
+        assert_eq!(strip(source, "app/client_test.py"), "");
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-js")]
+    fn test_js_test_file_conventions() {
+        let source = "export const test = 1;\ntest(\"adds\", () => {});\n";
+        assert_eq!(strip(source, "src/sum.js"), source);
+        assert_eq!(strip(source, "src/sum.spec.js"), "export const test = 1;\n");
+        assert_eq!(
+            strip(source, "src/__tests__/sum.js"),
+            "export const test = 1;\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-java")]
+    fn test_other_languages_unchanged() {
+        let source = "class A { @Test void t() {} }\n";
+        assert_eq!(strip(source, "src/test/java/A.java"), source);
+    }
+}
@@ -1,12 +1,43 @@
 //! Code structure extraction utilities.
 
 use super::language_support::{CodeStructure, LanguageSupport};
+use tree_sitter::TreeCursor;
 
 /// Extract structure information from source code.
 pub fn extract_structure(source: &str, support: &dyn LanguageSupport) -> CodeStructure {
     support.extract_structure(source)
 }
 
+/// Byte ranges of the type annotations in `source`: nodes in a `type` field
+/// (struct fields, parameters, variables) and `type_annotation`/`type` nodes
+/// (TypeScript `: T`, Python annotations). `None` if it fails to parse.
+pub fn type_annotation_ranges(
+    source: &str,
+    support: &dyn LanguageSupport,
+) -> Option<Vec<(usize, usize)>> {
+    fn collect(cursor: &mut TreeCursor, ranges: &mut Vec<(usize, usize)>) {
+        loop {
+            let node = cursor.node();
+            if cursor.field_name() == Some("type")
+                || matches!(node.kind(), "type_annotation" | "type")
+            {
+                ranges.push((node.start_byte(), node.end_byte()));
+            } else if cursor.goto_first_child() {
+                collect(cursor, ranges);
+                cursor.goto_parent();
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    let tree = support.parse(source)?;
+    let mut ranges = Vec::new();
+    collect(&mut tree.walk(), &mut ranges);
+    Some(ranges)
+}
+
 /// Format structure as markdown summary.
 pub fn format_structure_as_markdown(structure: &CodeStructure) -> String {
     if structure.total_symbols() == 0 {
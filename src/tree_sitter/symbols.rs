@@ -0,0 +1,388 @@
+//! Symbol lookup by name (`--symbol`).
+//!
+//! Definitions are located through the signatures a language already
+//! extracts: each signature's `line_number` points at the start of its
+//! definition node, which gives the full source span. Names are qualified by
+//! the enclosing impl/class/trait/namespace (or Go method receiver), so
+//! `CacheManager::new` matches the `new` inside `impl CacheManager`.
+//!
+//! The same lookup lists every definition with its span, which the auto-diff
+//! uses to report API changes between two versions of a file.
+
+use super::language_support::{LanguageSupport, Signature, SignatureKind, Visibility};
+use std::ops::Range;
+use tree_sitter::Node;
+
+/// Node kinds that qualify the names of the definitions inside them.
+const CONTAINER_KINDS: &[&str] = &[
+    "impl_item",
+    "trait_item",
+    "mod_item",
+    "class_declaration",
+    "abstract_class_declaration",
+    "interface_declaration",
+    "class_definition",
+    "class_specifier",
+    "struct_specifier",
+    "namespace_definition",
+    "enum_declaration",
+    "record_declaration",
+    "struct_declaration",
+    "namespace_declaration",
+    "trait_declaration",
+    "protocol_declaration",
+    "object_declaration",
+    "trait_definition",
+    "object_definition",
+    "class",
+    "module",
+];
+
+/// A definition matching a `--symbol` query.
+#[derive(Debug, Clone)]
+pub struct SymbolDefinition {
+    /// Qualified name, e.g. `CacheManager::new`.
+    pub name: String,
+    pub kind: SignatureKind,
+    /// 1-based line range of the definition, attributes and decorators included.
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Headers of the enclosing containers, outermost first (`impl CacheManager {`).
+    pub context: Vec<String>,
+    /// Full source of the definition.
+    pub source: String,
+}
+
+/// Every definition in `source` with its span, for comparing two versions
+/// of a file.
+#[derive(Debug, Clone)]
+pub struct DefinitionSpan {
+    /// Qualified name, e.g. `CacheManager::new`.
+    pub name: String,
+    pub kind: SignatureKind,
+    /// The extracted signature (the definition up to its body).
+    pub signature: String,
+    /// Byte range of the definition, attributes and decorators included.
+    pub range: Range<usize>,
+}
+
+/// Finds the definitions in `source` matching any of `queries`.
+///
+/// A query is a name or a path (`Type::method`, or `Type.method`) that must
+/// match the end of a definition's qualified name.
+pub fn find_symbols(
+    source: &str,
+    support: &dyn LanguageSupport,
+    queries: &[String],
+) -> Vec<SymbolDefinition> {
+    let mut definitions = Vec::new();
+    visit_definitions(source, support, |node, signature, path| {
+        if !queries.iter().any(|query| matches_query(&path, query)) {
+            return;
+        }
+        let start = leading_attributes(node).unwrap_or(node);
+        let start_byte = line_start_of(source, start.start_byte());
+        definitions.push(SymbolDefinition {
+            name: path.join("::"),
+            kind: signature.kind,
+            start_line: start.start_position().row + 1,
+            end_line: node.end_position().row + 1,
+            context: context_headers(source, node),
+            source: source[start_byte..node.end_byte()].to_string(),
+        });
+    });
+    definitions
+}
+
+/// Lists every definition in `source`, in source order.
+pub fn definition_spans(source: &str, support: &dyn LanguageSupport) -> Vec<DefinitionSpan> {
+    let mut spans = Vec::new();
+    visit_definitions(source, support, |node, signature, path| {
+        let start = leading_attributes(node).unwrap_or(node);
+        spans.push(DefinitionSpan {
+            name: path.join("::"),
+            kind: signature.kind,
+            signature: signature.full_signature,
+            range: line_start_of(source, start.start_byte())..node.end_byte(),
+        });
+    });
+    spans
+}
+
+/// Calls `visit` with the definition node, signature and qualified name of
+/// each definition in `source`, once per node.
+fn visit_definitions(
+    source: &str,
+    support: &dyn LanguageSupport,
+    mut visit: impl FnMut(Node<'_>, Signature, Vec<String>),
+) {
+    let Some(tree) = support.parse(source) else {
+        return;
+    };
+    let root = tree.root_node();
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+
+    let mut seen = Vec::new();
+    for signature in support.extract_signatures(source, Visibility::All) {
+        let Some(&line_start) = line_starts.get(signature.line_number.saturating_sub(1)) else {
+            continue;
+        };
+        let indent = source[line_start..]
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(0);
+        let Some(node) = definition_at(root, line_start + indent) else {
+            continue;
+        };
+        if seen.contains(&node.id()) {
+            continue;
+        }
+        seen.push(node.id());
+
+        let mut path = qualifiers(source, node);
+        let own_name = node_name(source, node).unwrap_or_else(|| signature.name.clone());
+        path.extend(own_name.split("::").map(strip_generics));
+        visit(node, signature, path);
+    }
+}
+
+/// Whether the qualified `path` ends with the segments of `query`.
+pub fn matches_query(path: &[String], query: &str) -> bool {
+    let segments: Vec<&str> = query
+        .split("::")
+        .flat_map(|part| part.split('.'))
+        .filter(|s| !s.is_empty())
+        .collect();
+    !segments.is_empty()
+        && segments.len() <= path.len()
+        && path[path.len() - segments.len()..]
+            .iter()
+            .zip(&segments)
+            .all(|(a, b)| a == b)
+}
+
+/// The largest node starting at `byte` on its line that is not a container
+/// body (and not the root).
+fn definition_at(root: Node<'_>, byte: usize) -> Option<Node<'_>> {
+    let mut node = root.descendant_for_byte_range(byte, byte)?;
+    let row = node.start_position().row;
+    while let Some(parent) = node.parent() {
+        if parent.parent().is_none() || parent.start_position().row != row || is_body(parent) {
+            break;
+        }
+        node = parent;
+    }
+    // Python keeps decorators in a wrapper node
+    match node.parent() {
+        Some(parent) if parent.kind() == "decorated_definition" => Some(parent),
+        _ if node.id() == root.id() => None,
+        _ => Some(node),
+    }
+}
+
+fn is_body(node: Node<'_>) -> bool {
+    node.parent()
+        .and_then(|parent| parent.child_by_field_name("body"))
+        .is_some_and(|body| body.id() == node.id())
+}
+
+/// First of the attribute items (`#[derive(..)]`) directly preceding `node`.
+fn leading_attributes(node: Node<'_>) -> Option<Node<'_>> {
+    let mut first = None;
+    let mut current = node.prev_sibling();
+    while let Some(sibling) = current {
+        if sibling.kind() != "attribute_item" {
+            break;
+        }
+        first = Some(sibling);
+        current = sibling.prev_sibling();
+    }
+    first
+}
+
+fn enclosing_containers(node: Node<'_>) -> Vec<Node<'_>> {
+    let mut containers = Vec::new();
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        // Python's root node is also a `module`
+        if CONTAINER_KINDS.contains(&parent.kind()) && parent.parent().is_some() {
+            containers.push(parent);
+        }
+        current = parent.parent();
+    }
+    containers.reverse();
+    containers
+}
+
+/// Names qualifying `node`: its containers and, for Go methods, the receiver type.
+fn qualifiers(source: &str, node: Node<'_>) -> Vec<String> {
+    let mut names: Vec<String> = enclosing_containers(node)
+        .into_iter()
+        .filter_map(|container| node_name(source, container))
+        .collect();
+    if let Some(receiver) = node.child_by_field_name("receiver") {
+        let text = &source[receiver.byte_range()];
+        if let Some(ty) = text
+            .trim_matches(|c| c == '(' || c == ')')
+            .split_whitespace()
+            .last()
+        {
+            names.push(strip_generics(ty.trim_start_matches('*')));
+        }
+    }
+    names
+}
+
+fn node_name(source: &str, node: Node<'_>) -> Option<String> {
+    let field = if node.kind() == "impl_item" {
+        "type"
+    } else {
+        "name"
+    };
+    // Zig containers are anonymous; `const User = struct { ... }` names them
+    let name = node.child_by_field_name(field).or_else(|| {
+        node.parent()
+            .filter(|parent| parent.kind() == "variable_declaration")
+            .and_then(|parent| {
+                parent
+                    .named_children(&mut parent.walk())
+                    .find(|child| child.kind() == "identifier")
+            })
+    })?;
+    Some(strip_generics(&source[name.byte_range()]))
+}
+
+fn strip_generics(name: &str) -> String {
+    name.split(['<', '['])
+        .next()
+        .unwrap_or(name)
+        .trim()
+        .to_string()
+}
+
+/// Headers of the containers around `node`, each up to its body.
+fn context_headers(source: &str, node: Node<'_>) -> Vec<String> {
+    enclosing_containers(node)
+        .into_iter()
+        .map(|container| {
+            let end = container
+                .child_by_field_name("body")
+                .map(|body| {
+                    body.start_byte() + usize::from(source[body.byte_range()].starts_with('{'))
+                })
+                .unwrap_or_else(|| {
+                    source[container.start_byte()..]
+                        .find('\n')
+                        .map_or(container.end_byte(), |i| container.start_byte() + i)
+                });
+            let start = line_start_of(source, container.start_byte());
+            source[start..end].trim_end().to_string()
+        })
+        .collect()
+}
+
+/// Start of the line containing `byte`, if only whitespace precedes `byte` on it.
+fn line_start_of(source: &str, byte: usize) -> usize {
+    let line_start = source[..byte].rfind('\n').map_or(0, |i| i + 1);
+    if source[line_start..byte].trim().is_empty() {
+        line_start
+    } else {
+        byte
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(segments: &[&str]) -> Vec<String> {
+        segments.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_matches_query() {
+        let name = path(&["CacheManager", "new"]);
+        assert!(matches_query(&name, "new"));
+        assert!(matches_query(&name, "CacheManager::new"));
+        assert!(matches_query(&name, "CacheManager.new"));
+        assert!(!matches_query(&name, "ProjectState::new"));
+        assert!(!matches_query(&name, "Other::CacheManager::new"));
+        assert!(!matches_query(&name, ""));
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-rust")]
+    fn test_find_rust_method_with_impl_context() {
+        let source = r#"
+pub struct CacheManager {
+    dir: String,
+}
+
+impl CacheManager {
+    /// Creates a manager.
+    #[inline]
+    pub fn new(dir: String) -> Self {
+        Self { dir }
+    }
+
+    pub fn clear(&self) {}
+}
+
+impl Other {
+    pub fn new() -> Self { Other }
+}
+"#;
+        let support = super::super::languages::get_language_support("rs").unwrap();
+        let found = find_symbols(source, support, &["CacheManager::new".to_string()]);
+
+        assert_eq!(found.len(), 1);
+        let definition = &found[0];
+        assert_eq!(definition.name, "CacheManager::new");
+        assert_eq!(definition.context, vec!["impl CacheManager {"]);
+        assert!(
+            definition
+                .source
+                .starts_with("    #[inline]\n    pub fn new")
+        );
+        assert!(definition.source.ends_with("Self { dir }\n    }"));
+        assert_eq!((definition.start_line, definition.end_line), (8, 11));
+
+        let types = find_symbols(source, support, &["CacheManager".to_string()]);
+        assert_eq!(types.len(), 2, "the struct and its impl block");
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-python")]
+    fn test_find_python_method() {
+        let source = "class Store:\n    def get(self, key):\n        return key\n\n    @staticmethod\n    def create():\n        return Store()\n";
+        let support = super::super::languages::get_language_support("py").unwrap();
+
+        let found = find_symbols(source, support, &["Store.create".to_string()]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].context, vec!["class Store:"]);
+        assert!(
+            found[0]
+                .source
+                .starts_with("    @staticmethod\n    def create")
+        );
+
+        let found = find_symbols(source, support, &["get".to_string()]);
+        assert_eq!(
+            found[0].source,
+            "    def get(self, key):\n        return key"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-zig")]
+    fn test_find_zig_method_in_named_struct() {
+        let source =
+            "const User = struct {\n    pub fn init() User {\n        return .{};\n    }\n};\n";
+        let support = super::super::languages::get_language_support("zig").unwrap();
+
+        let found = find_symbols(source, support, &["User.init".to_string()]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "User::init");
+    }
+}
@@ -0,0 +1,298 @@
+//! Watch mode (`--watch`).
+//!
+//! Generates the output once, then polls the input directory and regenerates
+//! it whenever a selected file (or `context-builder.toml`) is added, removed
+//! or modified. Only files that `collect_files` would pick up are watched, so
+//! the tool's own output and cache never retrigger a run. Changes are
+//! debounced until the tree has been quiet for a short while, which keeps a
+//! burst of saves (formatters, branch switches) down to a single
+//! regeneration. With auto-diff enabled every regeneration carries a fresh
+//! change summary against the previous one.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::cli::Args;
+use crate::config::{Config, load_config_from_path};
+use crate::file_utils::collect_files;
+use crate::{Prompter, compute_auto_ignores, finalize_args, run_with_args};
+
+/// How often the input directory is scanned for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long the tree must stay unchanged before regenerating.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Modification time and size of every watched file.
+type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Regenerates the output on every change until the process is interrupted.
+pub fn watch(args: Args, prompter: &impl Prompter) -> io::Result<()> {
+    watch_loop(args, prompter, POLL_INTERVAL, DEBOUNCE, None)
+}
+
+/// The file selection rules in effect for one generation.
+struct Selection {
+    base_path: PathBuf,
+    filter: Vec<String>,
+    ignore: Vec<String>,
+    auto_ignores: Vec<String>,
+}
+
+impl Selection {
+    fn new(args: &Args, config: &Config) -> Self {
+        let base_path = PathBuf::from(&args.input);
+        let auto_ignores = compute_auto_ignores(&base_path, args, config);
+        Self {
+            base_path,
+            filter: args.filter.clone(),
+            ignore: args.ignore.clone(),
+            auto_ignores,
+        }
+    }
+
+    fn same_rules(&self, other: &Selection) -> bool {
+        self.filter == other.filter
+            && self.ignore == other.ignore
+            && self.auto_ignores == other.auto_ignores
+    }
+
+    fn snapshot(&self) -> io::Result<Snapshot> {
+        let files = collect_files(
+            &self.base_path,
+            &self.filter,
+            &self.ignore,
+            &self.auto_ignores,
+        )?;
+        let mut snapshot: Snapshot = files
+            .iter()
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((
+                    entry.path().to_path_buf(),
+                    (metadata.modified().ok(), metadata.len()),
+                ))
+            })
+            .collect();
+        // Config edits change what gets generated even when filtered out
+        let config_path = self.base_path.join("context-builder.toml");
+        if let Ok(metadata) = config_path.metadata() {
+            snapshot.insert(config_path, (metadata.modified().ok(), metadata.len()));
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Resolves the config afresh (it may have been edited) and generates once.
+fn generate(args: &Args, prompter: &impl Prompter, first: bool) -> io::Result<Selection> {
+    let config = load_config_from_path(Path::new(&args.input));
+    let (mut final_args, final_config, warnings) = finalize_args(args.clone(), config);
+    if first && !is_silent() {
+        for warning in &warnings {
+            eprintln!("Warning: {}", warning);
+        }
+    }
+    // Prompts only make sense for the initial run
+    if !first {
+        final_args.yes = true;
+    }
+    let selection = Selection::new(&final_args, &final_config);
+    run_with_args(final_args, final_config, prompter)?;
+    Ok(selection)
+}
+
+/// Counts paths that were added, removed or modified between two snapshots.
+fn count_changes(previous: &Snapshot, current: &Snapshot) -> usize {
+    let changed = current
+        .iter()
+        .filter(|(path, stamp)| previous.get(*path) != Some(*stamp))
+        .count();
+    let removed = previous
+        .keys()
+        .filter(|path| !current.contains_key(*path))
+        .count();
+    changed + removed
+}
+
+fn is_silent() -> bool {
+    std::env::var("CB_SILENT")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+fn watch_loop(
+    args: Args,
+    prompter: &impl Prompter,
+    poll_interval: Duration,
+    debounce: Duration,
+    max_regenerations: Option<usize>,
+) -> io::Result<()> {
+    if crate::markdown::is_stdout_output(&args.output) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--watch regenerates an output file and cannot be combined with -o -",
+        ));
+    }
+    let silent = is_silent();
+
+    let mut selection = generate(&args, prompter, true)?;
+    let mut last = selection.snapshot()?;
+    if !silent {
+        println!(
+            "Watching {} for changes (Ctrl+C to stop)...",
+            selection.base_path.display()
+        );
+    }
+
+    let mut regenerations = 0;
+    while max_regenerations.is_none_or(|max| regenerations < max) {
+        thread::sleep(poll_interval);
+        let mut settled = selection.snapshot()?;
+        if settled == last {
+            continue;
+        }
+        // Wait for the burst of changes to settle before regenerating
+        loop {
+            thread::sleep(debounce);
+            let next = selection.snapshot()?;
+            if next == settled {
+                break;
+            }
+            settled = next;
+        }
+
+        if !silent {
+            println!(
+                "\n{} change(s) detected, regenerating...",
+                count_changes(&last, &settled)
+            );
+        }
+        match generate(&args, prompter, false) {
+            Ok(next_selection) => {
+                // A config edit can change the rules; rescan so it does not retrigger
+                if !next_selection.same_rules(&selection) {
+                    settled = next_selection.snapshot()?;
+                }
+                selection = next_selection;
+            }
+            Err(e) => {
+                if !silent {
+                    eprintln!("Error: regeneration failed: {}", e);
+                }
+            }
+        }
+        last = settled;
+        regenerations += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    struct TestPrompter;
+
+    impl Prompter for TestPrompter {
+        fn confirm_processing(&self, _file_count: usize) -> io::Result<bool> {
+            Ok(true)
+        }
+        fn confirm_overwrite(&self, _file_path: &str) -> io::Result<bool> {
+            Ok(true)
+        }
+    }
+
+    fn args_for(input: &Path, output: &Path) -> Args {
+        use clap::Parser;
+        Args::parse_from([
+            "context-builder",
+            "-d",
+            input.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+            "-y",
+        ])
+    }
+
+    #[test]
+    fn snapshot_ignores_output_and_tracks_changes() {
+        let dir = tempdir().unwrap();
+        let base = dir.path();
+        fs::write(base.join("main.rs"), "fn main() {}\n").unwrap();
+        fs::write(base.join("output.md"), "generated").unwrap();
+        let args = args_for(base, &base.join("output.md"));
+        let selection = Selection::new(&args, &Config::default());
+
+        let before = selection.snapshot().unwrap();
+        assert!(before.contains_key(&base.join("main.rs")));
+        assert!(!before.contains_key(&base.join("output.md")));
+
+        fs::write(base.join("output.md"), "regenerated output").unwrap();
+        assert_eq!(selection.snapshot().unwrap(), before);
+
+        fs::write(base.join("main.rs"), "fn main() { println!(); }\n").unwrap();
+        fs::write(base.join("lib.rs"), "pub fn lib() {}\n").unwrap();
+        let after = selection.snapshot().unwrap();
+        assert_eq!(count_changes(&before, &after), 2);
+    }
+
+    #[test]
+    fn regenerates_after_a_change() {
+        unsafe {
+            std::env::set_var("CB_SILENT", "1");
+        }
+        let dir = tempdir().unwrap();
+        let base = dir.path().to_path_buf();
+        fs::write(base.join("main.rs"), "fn main() {}\n").unwrap();
+        let output = base.join("output.md");
+        let args = args_for(&base, &output);
+
+        let watcher = thread::spawn(move || {
+            watch_loop(
+                args,
+                &TestPrompter,
+                Duration::from_millis(20),
+                Duration::from_millis(20),
+                Some(1),
+            )
+        });
+
+        // Wait for the initial generation before touching the tree
+        for _ in 0..200 {
+            if output.exists() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(output.exists(), "initial generation did not happen");
+
+        // Keep editing until the watcher has picked up a change and regenerated
+        let mut edits = 0;
+        while !watcher.is_finished() && edits < 200 {
+            fs::write(
+                base.join("added.rs"),
+                format!("fn added_{}() {{}}\n", edits),
+            )
+            .unwrap();
+            edits += 1;
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        watcher.join().unwrap().unwrap();
+        assert!(fs::read_to_string(&output).unwrap().contains("fn added_"));
+    }
+
+    #[test]
+    fn rejects_stdout_output() {
+        let dir = tempdir().unwrap();
+        let mut args = args_for(dir.path(), &dir.path().join("output.md"));
+        args.output = "-".to_string();
+        let err = watch(args, &TestPrompter).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
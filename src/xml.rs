@@ -0,0 +1,431 @@
+//! XML document-tag output.
+//!
+//! Wraps each file in `<document>` blocks instead of fenced code blocks, the
+//! layout commonly used for long-context prompts:
+//!
+//! ```text
+//! <documents>
+//! <document index="1">
+//! <source>src/main.rs</source>
+//! <document_content>
+//! fn main() {}
+//! </document_content>
+//! </document>
+//! </documents>
+//! ```
+//!
+//! File content is embedded verbatim so code stays readable; only the literal
+//! closing tags of the enclosing elements are escaped so a file can never
+//! terminate its own block.
+
+use ignore::DirEntry;
+use log::{error, info};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::markdown::{
+    FileContent, TreeSitterConfig, compute_content_hash, format_modified_time, open_output,
+    read_file_content, resolve_input_dir_name, write_file_sections,
+};
+use crate::redact::Redactor;
+use crate::tree::{FileTree, write_tree_to_file};
+
+/// Escapes the five XML special characters, for attribute values and short
+/// text nodes such as paths.
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Neutralizes closing tags that would end the surrounding block early.
+fn escape_closing_tags(content: &str) -> String {
+    content
+        .replace("</document_content>", "&lt;/document_content>")
+        .replace("</document>", "&lt;/document>")
+}
+
+/// Writes `content` on its own lines, optionally prefixed with line numbers.
+fn write_block_text(output: &mut impl Write, content: &str, line_numbers: bool) -> io::Result<()> {
+    let content = escape_closing_tags(content);
+    if line_numbers {
+        for (i, line) in content.lines().enumerate() {
+            writeln!(output, "{:>4} | {}", i + 1, line)?;
+        }
+    } else {
+        output.write_all(content.as_bytes())?;
+        if !content.is_empty() && !content.ends_with('\n') {
+            writeln!(output)?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders a single file as a `<document>` block.
+///
+/// `index` is the 1-based position of the file in the document list.
+#[allow(clippy::too_many_arguments)]
+pub fn process_file_xml(
+    base_path: &Path,
+    file_path: &Path,
+    index: usize,
+    output: &mut impl Write,
+    line_numbers: bool,
+    encoding_strategy: Option<&str>,
+    redactor: Option<&Redactor>,
+    ts_config: &TreeSitterConfig,
+) -> io::Result<()> {
+    let relative_path = file_path.strip_prefix(base_path).unwrap_or(file_path);
+    info!("Processing file: {}", relative_path.display());
+    let ts_config = &*ts_config.for_file(relative_path);
+
+    let metadata = match fs::metadata(file_path) {
+        Ok(meta) => meta,
+        Err(e) => {
+            error!(
+                "Failed to get metadata for {}: {}",
+                relative_path.display(),
+                e
+            );
+            return Ok(());
+        }
+    };
+
+    let extension = file_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("text");
+
+    writeln!(output, "<document index=\"{}\">", index)?;
+    writeln!(
+        output,
+        "<source>{}</source>",
+        escape_xml(&relative_path.to_string_lossy().replace('\\', "/"))
+    )?;
+    writeln!(
+        output,
+        "<metadata size=\"{}\" modified=\"{}\"/>",
+        metadata.len(),
+        escape_xml(&format_modified_time(&metadata))
+    )?;
+
+    match read_file_content(file_path, relative_path, encoding_strategy, redactor) {
+        FileContent::Text(content) => {
+            let content = ts_config.prepare_content(content, relative_path);
+            let signatures_only = ts_config.signatures_only(extension, metadata.len());
+
+            if !signatures_only {
+                writeln!(output, "<document_content>")?;
+                write_block_text(output, &content, line_numbers)?;
+                writeln!(output, "</document_content>")?;
+            }
+
+            write_tree_sitter_elements(output, &content, extension, ts_config)?;
+        }
+        FileContent::Binary => {
+            writeln!(output, "<document_content>")?;
+            writeln!(
+                output,
+                "&lt;Binary file or unsupported encoding: {} bytes&gt;",
+                metadata.len()
+            )?;
+            writeln!(output, "</document_content>")?;
+        }
+        FileContent::Unreadable(placeholder) => {
+            writeln!(output, "<document_content>")?;
+            writeln!(output, "{}", escape_xml(placeholder))?;
+            writeln!(output, "</document_content>")?;
+        }
+    }
+
+    writeln!(output, "</document>")?;
+    Ok(())
+}
+
+/// Writes `<structure>` and `<signatures>` elements for supported languages,
+/// and `<outline>` for documentation and data files.
+#[allow(unused_variables)]
+fn write_tree_sitter_elements(
+    output: &mut impl Write,
+    content: &str,
+    extension: &str,
+    ts_config: &TreeSitterConfig,
+) -> io::Result<()> {
+    #[cfg(feature = "tree-sitter-base")]
+    {
+        use crate::tree_sitter::language_support::{SignatureDocs, Visibility};
+
+        if ts_config.structure
+            && let Some(structure) =
+                crate::tree_sitter::extract_structure_for_file(content, extension)
+            && structure.total_symbols() > 0
+        {
+            let counts = [
+                ("functions", structure.functions),
+                ("structs", structure.structs),
+                ("classes", structure.classes),
+                ("enums", structure.enums),
+                ("traits", structure.traits),
+                ("interfaces", structure.interfaces),
+                ("constants", structure.constants),
+                ("types", structure.type_aliases),
+                ("macros", structure.macros),
+            ];
+            write!(output, "<structure")?;
+            for (name, count) in counts.iter().filter(|(_, count)| *count > 0) {
+                write!(output, " {}=\"{}\"", name, count)?;
+            }
+            writeln!(
+                output,
+                " lines=\"{}\" code_lines=\"{}\"/>",
+                structure.total_lines, structure.code_lines
+            )?;
+        }
+
+        if ts_config.signatures {
+            let vis_filter: Visibility = ts_config.visibility.parse().unwrap_or(Visibility::All);
+            if let Some(mut signatures) =
+                crate::tree_sitter::extract_signatures_for_file(content, extension, vis_filter)
+                && !signatures.is_empty()
+            {
+                let docs: SignatureDocs = ts_config.signature_docs.parse().unwrap_or_default();
+                docs.apply(&mut signatures);
+                let language = crate::markdown::language_for_extension(extension);
+                writeln!(output, "<signatures>")?;
+                for sig in &signatures {
+                    if let Some(doc) = &sig.doc {
+                        let comment =
+                            crate::tree_sitter::signatures::format_doc_comment(doc, language);
+                        write!(output, "{}", escape_closing_tags(&comment))?;
+                    }
+                    writeln!(output, "{}", escape_closing_tags(&sig.full_signature))?;
+                }
+                writeln!(output, "</signatures>")?;
+            }
+        }
+
+        if (ts_config.structure || ts_config.signatures_only(extension, content.len() as u64))
+            && let Some(outline) = crate::tree_sitter::extract_outline(content, extension)
+            && !outline.is_empty()
+        {
+            writeln!(output, "<outline>")?;
+            for entry in &outline {
+                writeln!(
+                    output,
+                    "{}",
+                    escape_xml(&crate::tree_sitter::outline::format_outline_line(entry))
+                )?;
+            }
+            writeln!(output, "</outline>")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Generates the XML document and writes it to `output_path`.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_xml(
+    output_path: &str,
+    input_dir: &str,
+    filters: &[String],
+    ignores: &[String],
+    file_tree: &FileTree,
+    files: &[DirEntry],
+    base_path: &Path,
+    line_numbers: bool,
+    encoding_strategy: Option<&str>,
+    redactor: Option<&Redactor>,
+    max_tokens: Option<usize>,
+    ts_config: &TreeSitterConfig,
+) -> io::Result<()> {
+    let mut output = open_output(output_path)?;
+
+    // --- Header --- //
+    writeln!(output, "<context>")?;
+    writeln!(
+        output,
+        "<project>{}</project>",
+        escape_xml(&resolve_input_dir_name(input_dir)?)
+    )?;
+    if !filters.is_empty() {
+        writeln!(
+            output,
+            "<filters>{}</filters>",
+            escape_xml(&filters.join(", "))
+        )?;
+    }
+    if !ignores.is_empty() {
+        writeln!(
+            output,
+            "<ignores>{}</ignores>",
+            escape_xml(&ignores.join(", "))
+        )?;
+    }
+    writeln!(
+        output,
+        "<content_hash>{:016x}</content_hash>",
+        compute_content_hash(files, base_path)
+    )?;
+
+    // --- File Tree --- //
+    writeln!(output, "<file_tree>")?;
+    let mut tree = Vec::new();
+    write_tree_to_file(&mut tree, file_tree, 0)?;
+    output.write_all(escape_xml(&String::from_utf8_lossy(&tree)).as_bytes())?;
+    writeln!(output, "</file_tree>")?;
+
+    // --- Documents --- //
+    writeln!(output, "<documents>")?;
+
+    // Index documents by their position in the (relevance-ordered) file list
+    let positions: std::collections::HashMap<&Path, usize> = files
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| (entry.path(), i + 1))
+        .collect();
+
+    let mut output = write_file_sections(
+        output,
+        files,
+        max_tokens,
+        ts_config,
+        |file_path, ts_config, buf| {
+            let index = positions.get(file_path).copied().unwrap_or(0);
+            process_file_xml(
+                base_path,
+                file_path,
+                index,
+                buf,
+                line_numbers,
+                encoding_strategy,
+                redactor,
+                ts_config,
+            )
+        },
+        |max, signatures_only, omitted| {
+            format!(
+                "<!-- Token budget ({}) reached. {} files reduced to signatures, {} files omitted. -->\n",
+                max, signatures_only, omitted
+            )
+        },
+    )?;
+
+    writeln!(output, "</documents>")?;
+    writeln!(output, "</context>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_utils::collect_files;
+    use crate::tree::build_file_tree;
+    use tempfile::tempdir;
+
+    fn generate(dir: &Path, line_numbers: bool, max_tokens: Option<usize>) -> String {
+        let files = collect_files(dir, &[], &[], &[]).unwrap();
+        let file_tree = build_file_tree(&files, dir);
+        let output_dir = tempdir().unwrap();
+        let output_path = output_dir.path().join("output.xml");
+        generate_xml(
+            output_path.to_str().unwrap(),
+            "project",
+            &[],
+            &[],
+            &file_tree,
+            &files,
+            dir,
+            line_numbers,
+            None,
+            None,
+            max_tokens,
+            &TreeSitterConfig::default(),
+        )
+        .unwrap();
+        fs::read_to_string(output_path).unwrap()
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("a<b>&\"c'"), "a&lt;b&gt;&amp;&quot;c&apos;");
+        assert_eq!(escape_xml("plain"), "plain");
+    }
+
+    #[test]
+    fn test_generate_xml_document_blocks() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"x\"\n").unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+
+        let out = generate(dir.path(), false, None);
+
+        assert!(out.starts_with("<context>\n<project>project</project>\n"));
+        assert!(out.contains("<file_tree>\n- 📄 Cargo.toml\n"));
+        // Relevance ordering from collect_files: config files come first
+        let toml_pos = out.find("<source>Cargo.toml</source>").unwrap();
+        let main_pos = out.find("<source>src/main.rs</source>").unwrap();
+        assert!(toml_pos < main_pos);
+        assert!(out.contains("<document index=\"1\">\n<source>Cargo.toml</source>"));
+        assert!(out.contains("<document index=\"2\">\n<source>src/main.rs</source>"));
+        assert!(out.contains("<document_content>\nfn main() {}\n</document_content>\n</document>"));
+        assert!(!out.contains("```"));
+        assert!(out.trim_end().ends_with("</documents>\n</context>"));
+    }
+
+    #[test]
+    fn test_generate_xml_keeps_backticks_and_escapes_closing_tags() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("README.md"),
+            "```rust\nfn x() {}\n```\n</document_content>\n",
+        )
+        .unwrap();
+
+        let out = generate(dir.path(), false, None);
+
+        assert!(out.contains("```rust\nfn x() {}\n```\n"));
+        assert!(out.contains("&lt;/document_content>\n</document_content>"));
+        assert_eq!(out.matches("</document_content>").count(), 1);
+    }
+
+    #[test]
+    fn test_generate_xml_line_numbers_and_binary() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "one\ntwo\n").unwrap();
+        let png = [
+            0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48,
+            0x44, 0x52, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        fs::write(dir.path().join("b.bin"), png).unwrap();
+
+        let out = generate(dir.path(), true, None);
+
+        assert!(out.contains("   1 | one\n   2 | two\n"));
+        assert!(out.contains("&lt;Binary file or unsupported encoding: 24 bytes&gt;"));
+    }
+
+    #[test]
+    fn test_generate_xml_token_budget() {
+        let dir = tempdir().unwrap();
+        for i in 0..5 {
+            fs::write(dir.path().join(format!("f{}.txt", i)), "x".repeat(400)).unwrap();
+        }
+
+        let out = generate(dir.path(), false, Some(150));
+
+        assert!(out.contains("<!-- Token budget (150) reached."));
+        assert!(out.trim_end().ends_with("</documents>\n</context>"));
+    }
+}
@@ -71,6 +71,27 @@ fn preview_mode_does_not_create_output_file() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter::new(true, true);
@@ -116,6 +137,27 @@ fn preview_mode_skips_overwrite_confirmation() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     // Use false for overwrite response to verify it's not called
@@ -166,6 +208,27 @@ fn token_count_mode_skips_overwrite_confirmation() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     // Use false for overwrite response to verify it's not called
@@ -213,6 +276,27 @@ fn both_preview_and_token_count_modes_work_together() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter::new(false, true); // false for overwrite since it should be skipped
@@ -273,6 +357,27 @@ fn end_to_end_generates_output_with_filters_ignores_and_line_numbers() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     // Always proceed without interactive prompts
@@ -365,6 +470,27 @@ fn overwrite_prompt_is_respected() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     // Deny overwrite
@@ -409,6 +535,27 @@ fn confirm_processing_receives_large_count() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter::new(true, true);
@@ -450,6 +597,27 @@ fn token_count_mode_does_not_create_output_file() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter::new(true, true);
@@ -464,3 +632,99 @@ fn token_count_mode_does_not_create_output_file() {
         "output file should not be created in token count mode"
     );
 }
+
+#[test]
+fn stdout_output_streams_document_and_keeps_status_on_stderr() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    write_file(&root.join("src/main.rs"), "fn main() {}\n");
+    write_file(&root.join("README.md"), "# Readme\n");
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_context-builder"))
+        .args(["-d", &root.to_string_lossy(), "-o", "-", "-y"])
+        .current_dir(root)
+        .output()
+        .expect("binary should run");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(stdout.starts_with("# Directory Structure Report"));
+    assert!(stdout.contains("### File: `src/main.rs`"));
+    assert!(!stdout.contains("Documentation"));
+    assert!(!stdout.contains("Processing time"));
+    assert!(stderr.contains("Documentation written to stdout"));
+    assert!(
+        !root.join("-").exists(),
+        "no file named '-' should be created"
+    );
+}
+
+#[test]
+fn stdout_output_works_with_json_format() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    write_file(&root.join("a.txt"), "alpha");
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_context-builder"))
+        .args([
+            "-d",
+            &root.to_string_lossy(),
+            "-o",
+            "-",
+            "--format",
+            "json",
+            "-y",
+        ])
+        .current_dir(root)
+        .output()
+        .expect("binary should run");
+
+    assert!(output.status.success());
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(doc["files"][0]["content"], "alpha");
+    assert!(!root.join("output.json").exists());
+}
+
+#[test]
+fn token_count_uses_selected_tokenizer() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    write_file(&root.join("src/lib.rs"), &"pub fn f() {}\n".repeat(20));
+
+    let count_with = |tokenizer: &str| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_context-builder"))
+            .args([
+                "-d",
+                &root.to_string_lossy(),
+                "--token-count",
+                "--token-count-format",
+                "json",
+                "--tokenizer",
+                tokenizer,
+            ])
+            .current_dir(root)
+            .output()
+            .expect("binary should run");
+        assert!(output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(report["tokenizer"], tokenizer);
+        report["total_tokens"].as_u64().unwrap()
+    };
+
+    let cl100k = count_with("cl100k");
+    let chars = count_with("chars:1");
+    assert!(chars > cl100k, "one token per character counts more");
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_context-builder"))
+        .args(["-d", &root.to_string_lossy(), "--token-count"])
+        .args(["--tokenizer", "gpt-9"])
+        .current_dir(root)
+        .output()
+        .expect("binary should run");
+    assert!(!output.status.success());
+}
@@ -89,6 +89,27 @@ fn test_auto_diff_workflow_basic() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
     let prompter = TestPrompter;
 
@@ -248,6 +269,27 @@ fn test_auto_diff_added_and_removed_files() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter;
@@ -412,6 +454,27 @@ diff_only = true
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter;
@@ -560,6 +623,27 @@ fn test_cache_invalidation_on_config_change() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter;
@@ -715,6 +799,27 @@ fn test_concurrent_cache_access() {
                     structure: false,
                     truncate: "smart".to_string(),
                     visibility: "all".to_string(),
+                    format: "markdown".to_string(),
+                    split: false,
+                    since: None,
+                    staged: false,
+                    unstaged: false,
+                    diff_against: None,
+                    diff_from: None,
+                    snapshot_save: None,
+                    list_snapshots: false,
+                    prune_snapshots: false,
+                    no_redact: false,
+                    watch: false,
+                    token_count_format: "text".to_string(),
+                    tokenizer: "cl100k".to_string(),
+                    focus: vec![],
+                    entry: vec![],
+                    entry_depth: None,
+                    symbol: vec![],
+                    signature_docs: "first-line".to_string(),
+                    strip_tests: false,
+                    compact: None,
                 };
 
                 let prompter = TestPrompter;
@@ -770,6 +875,27 @@ fn test_corrupted_cache_recovery() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter;
@@ -941,6 +1067,27 @@ diff_only = true
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     run_with_args(args.clone(), load_config().unwrap_or_default(), &prompter).unwrap();
@@ -1035,3 +1182,102 @@ diff_only = true
         "Existing unchanged files should not have full content in diff_only mode"
     );
 }
+
+#[test]
+#[serial]
+fn test_diff_from_named_snapshot() {
+    let temp_dir = tempdir().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    create_simple_project(&project_dir).unwrap();
+    let output_dir = temp_dir.path().join("output");
+    fs::create_dir_all(&output_dir).unwrap();
+
+    let prompter = TestPrompter;
+    let args = Args {
+        input: project_dir.to_string_lossy().to_string(),
+        output: output_dir.join("start.md").to_string_lossy().to_string(),
+        filter: vec!["rs".to_string()],
+        ignore: vec![],
+        preview: false,
+        token_count: false,
+        line_numbers: false,
+        yes: true,
+        diff_only: false,
+        clear_cache: false,
+        init: false,
+        max_tokens: None,
+        signatures: false,
+        structure: false,
+        truncate: "smart".to_string(),
+        visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: Some("sprint-start".to_string()),
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
+    };
+
+    // A plain (non auto-diff) run records the snapshot
+    run_with_args(args.clone(), Config::default(), &prompter).unwrap();
+    assert!(
+        project_dir
+            .join(".context-builder/snapshots/sprint-start.json")
+            .exists()
+    );
+
+    fs::write(
+        project_dir.join("src").join("lib.rs"),
+        "pub fn add(a: i32, b: i32) -> i32 {\n    a + b + 0\n}",
+    )
+    .unwrap();
+
+    // Diffing from the snapshot works without auto_diff or timestamped output,
+    // and survives intermediate runs
+    let intermediate = Args {
+        snapshot_save: None,
+        output: output_dir.join("mid.md").to_string_lossy().to_string(),
+        ..args.clone()
+    };
+    run_with_args(intermediate, Config::default(), &prompter).unwrap();
+
+    let diff_args = Args {
+        snapshot_save: None,
+        diff_from: Some("sprint-start".to_string()),
+        output: output_dir
+            .join("since_start.md")
+            .to_string_lossy()
+            .to_string(),
+        ..args.clone()
+    };
+    run_with_args(diff_args.clone(), Config::default(), &prompter).unwrap();
+
+    let output = fs::read_to_string(output_dir.join("since_start.md")).unwrap();
+    assert!(output.contains("**Compared against:** snapshot `sprint-start`"));
+    assert!(output.contains("- Modified: `src/lib.rs`"));
+    assert!(output.contains("+     a + b + 0"));
+    assert!(!output.contains("src/main.rs`\n\n_Status"));
+
+    // Unknown snapshots are an error
+    let missing = Args {
+        diff_from: Some("nope".to_string()),
+        ..diff_args
+    };
+    let err = run_with_args(missing, Config::default(), &prompter).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
@@ -115,6 +115,27 @@ fn test_binary_files_dont_crash_autodiff() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter::new(true, true);
@@ -198,6 +219,27 @@ fn test_mixed_text_and_binary_files_autodiff() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter::new(true, true);
@@ -265,6 +307,27 @@ fn test_large_binary_file_autodiff() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter::new(true, true);
@@ -123,6 +123,27 @@ fn test_comprehensive_binary_file_edge_cases() {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         };
 
         let prompter = TestPrompter::new(true, true);
@@ -212,6 +233,27 @@ fn test_configuration_precedence_edge_cases() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter::new(true, true);
@@ -252,6 +294,27 @@ fn test_configuration_precedence_edge_cases() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let result = run_with_args(args, Config::default(), &prompter);
@@ -312,6 +375,27 @@ timestamped_output = true
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let config = context_builder::config::load_config_from_path(&project_dir).unwrap_or_default();
@@ -418,6 +502,27 @@ fn test_error_conditions_and_exit_codes() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let result = run_with_args(args, Config::default(), &prompter);
@@ -448,6 +553,27 @@ fn test_error_conditions_and_exit_codes() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter_deny = TestPrompter::new(false, true); // Deny overwrite
@@ -475,6 +601,27 @@ fn test_error_conditions_and_exit_codes() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter_cancel = TestPrompter::new(true, false); // Allow overwrite, deny processing
@@ -524,6 +671,27 @@ fn test_memory_usage_under_parallel_processing() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter::new(true, true);
@@ -615,6 +783,27 @@ line_numbers = true
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         };
 
         let config =
@@ -717,6 +906,27 @@ fn test_edge_case_filenames_and_paths() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter::new(true, true);
@@ -96,6 +96,27 @@ fn test_deterministic_output_multiple_runs() {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         },
         Config::default(),
         &prompter,
@@ -123,6 +144,27 @@ fn test_deterministic_output_multiple_runs() {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         },
         Config::default(),
         &prompter,
@@ -274,6 +316,27 @@ fn test_deterministic_file_tree_order() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter;
@@ -342,6 +405,27 @@ fn test_cache_collision_prevention() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     run_with_args(args1, Config::default(), &prompter).unwrap();
@@ -370,6 +454,27 @@ fn test_cache_collision_prevention() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     run_with_args(args2, Config::default(), &prompter).unwrap();
@@ -439,6 +544,27 @@ fn test_custom_ignores_performance() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter;
@@ -498,6 +624,27 @@ fn test_configuration_affects_cache_key() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let args2 = Args {
@@ -517,6 +664,27 @@ fn test_configuration_affects_cache_key() {
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter;
@@ -586,6 +754,27 @@ auto_diff = true
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     let prompter = TestPrompter;
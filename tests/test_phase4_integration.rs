@@ -126,6 +126,27 @@ filter = ["rs", "txt"]
         structure: false,
         truncate: "smart".to_string(),
         visibility: "all".to_string(),
+        format: "markdown".to_string(),
+        split: false,
+        since: None,
+        staged: false,
+        unstaged: false,
+        diff_against: None,
+        diff_from: None,
+        snapshot_save: None,
+        list_snapshots: false,
+        prune_snapshots: false,
+        no_redact: false,
+        watch: false,
+        token_count_format: "text".to_string(),
+        tokenizer: "cl100k".to_string(),
+        focus: vec![],
+        entry: vec![],
+        entry_depth: None,
+        symbol: vec![],
+        signature_docs: "first-line".to_string(),
+        strip_tests: false,
+        compact: None,
     };
 
     // Apply config manually (simulating what happens in the real application)
@@ -304,6 +325,27 @@ fn test_encoding_strategy_configuration() {
             structure: false,
             truncate: "smart".to_string(),
             visibility: "all".to_string(),
+            format: "markdown".to_string(),
+            split: false,
+            since: None,
+            staged: false,
+            unstaged: false,
+            diff_against: None,
+            diff_from: None,
+            snapshot_save: None,
+            list_snapshots: false,
+            prune_snapshots: false,
+            no_redact: false,
+            watch: false,
+            token_count_format: "text".to_string(),
+            tokenizer: "cl100k".to_string(),
+            focus: vec![],
+            entry: vec![],
+            entry_depth: None,
+            symbol: vec![],
+            signature_docs: "first-line".to_string(),
+            strip_tests: false,
+            compact: None,
         };
 
         let result = run_with_args(args, config, &prompter);